@@ -0,0 +1,10 @@
+//! # C libraries
+//! Builds the [C API](mil1750a_converter::ffi) of the converter as `libmil1750a.so` (or
+//! `.dylib`, `.dll`) and `libmil1750a.a`, declared by `include/mil1750a.h`:
+//! `cargo build --release -p mil1750a-ffi`.
+//!
+//! The API lives in the main crate, so that the Rust tests cover it; this crate only sets the
+//! crate types, which the main crate cannot, as some of its optional dependencies do not link
+//! into a C library.
+
+pub use mil1750a_converter::ffi::*;
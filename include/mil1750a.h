@@ -0,0 +1,97 @@
+// Generated by cbindgen from src/ffi.rs, do not edit. The 16-bit functions need the
+// `half` feature, which is on by default.
+
+#ifndef MIL1750A_CONVERTER_H
+#define MIL1750A_CONVERTER_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of a batch conversion
+typedef enum Mil1750aStatus {
+  // The conversion succeeded
+  Mil1750aStatus_Ok = 0,
+  // An input or output pointer was null while `len` was not zero
+  Mil1750aStatus_NullPointer = 1,
+} Mil1750aStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Transform an IEEE half precision value, given as its bits, to 16-bit MIL-1750A
+uint16_t mil1750a_f16_to_1750a(uint16_t bits);
+
+// Transform a 32-bit floating point number to 32-bit MIL-1750A
+uint32_t mil1750a_f32_to_1750a(float value);
+
+// Transform a 64-bit floating point number to 48-bit MIL-1750A, in the low 48 bits
+uint64_t mil1750a_f48_to_1750a(double value);
+
+// Convert a 16-bit MIL-1750A word to the bits of an IEEE half precision value
+uint16_t mil1750a_to_16flt(uint16_t word);
+
+// Convert a 32-bit MIL-1750A word to a 32-bit floating point number
+float mil1750a_to_32flt(uint32_t word);
+
+// Convert a 48-bit MIL-1750A word, in the low 48 bits, to a 64-bit floating point number
+double mil1750a_to_48flt(uint64_t word);
+
+// Transform `len` IEEE half precision values, given as bits, to 16-bit MIL-1750A
+//
+// # Safety
+//
+// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+// elements.
+enum Mil1750aStatus mil1750a_f16_to_1750a_batch(const uint16_t *input,
+                                                uint16_t *output,
+                                                size_t len);
+
+// Transform `len` 32-bit floating point numbers to 32-bit MIL-1750A
+//
+// # Safety
+//
+// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+// elements.
+enum Mil1750aStatus mil1750a_f32_to_1750a_batch(const float *input, uint32_t *output, size_t len);
+
+// Transform `len` 64-bit floating point numbers to 48-bit MIL-1750A
+//
+// # Safety
+//
+// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+// elements.
+enum Mil1750aStatus mil1750a_f48_to_1750a_batch(const double *input, uint64_t *output, size_t len);
+
+// Convert `len` 16-bit MIL-1750A words to IEEE half precision bits
+//
+// # Safety
+//
+// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+// elements.
+enum Mil1750aStatus mil1750a_to_16flt_batch(const uint16_t *input, uint16_t *output, size_t len);
+
+// Convert `len` 32-bit MIL-1750A words to 32-bit floating point numbers
+//
+// # Safety
+//
+// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+// elements.
+enum Mil1750aStatus mil1750a_to_32flt_batch(const uint32_t *input, float *output, size_t len);
+
+// Convert `len` 48-bit MIL-1750A words to 64-bit floating point numbers
+//
+// # Safety
+//
+// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+// elements.
+enum Mil1750aStatus mil1750a_to_48flt_batch(const uint64_t *input, double *output, size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MIL1750A_CONVERTER_H */
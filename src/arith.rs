@@ -0,0 +1,1193 @@
+//! # Native arithmetic
+//! Arithmetic carried out on MIL-1750A words themselves, as a 1750A processor does it: the exact
+//! result of combining the two's-complement mantissas and exponents is rounded once to the
+//! format, to nearest with ties away from zero like the encoders.
+//!
+//! Results too large for the format saturate to its most positive or most negative value,
+//! results too small flush to zero, and dividing by zero saturates with the sign of the dividend.
+//! A [`TargetProfile`](crate::profile::TargetProfile) carries out the same arithmetic with the
+//! rounding and overflow of a particular 1750A implementation.
+
+use crate::profile::{Dialect, Rounding};
+
+/// Fraction bits of the 16-bit mantissa
+const FRACTION_16: u32 = 9;
+/// Fraction bits of the 32-bit mantissa
+const FRACTION_32: u32 = 23;
+/// Fraction bits of the 48-bit mantissa
+const FRACTION_48: u32 = 39;
+
+/// A word split into its signed mantissa and exponent, worth `mantissa * 2^(exponent - F)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Unpacked {
+    pub(crate) mantissa: i64,
+    pub(crate) exponent: i32,
+}
+
+pub(crate) fn unpack16(word: u16) -> Unpacked {
+    Unpacked {
+        mantissa: ((word as i16) >> 6) as i64,
+        exponent: (((word as i8) << 2) >> 2) as i32,
+    }
+}
+
+const fn pack16(unpacked: Unpacked) -> u16 {
+    ((unpacked.mantissa as u16) << 6) | (unpacked.exponent as u16 & 0x3F)
+}
+
+pub(crate) fn unpack32(word: u32) -> Unpacked {
+    Unpacked {
+        mantissa: ((word as i32) >> 8) as i64,
+        exponent: (word as u8 as i8) as i32,
+    }
+}
+
+const fn pack32(unpacked: Unpacked) -> u32 {
+    ((unpacked.mantissa as u32) << 8) | (unpacked.exponent as u8 as u32)
+}
+
+pub(crate) fn unpack48(word: u64) -> Unpacked {
+    let high = ((word as i64) << 16) >> 40;
+    Unpacked {
+        mantissa: (high << 16) | (word & 0xFFFF) as i64,
+        exponent: ((word >> 16) as u8 as i8) as i32,
+    }
+}
+
+const fn pack48(unpacked: Unpacked) -> u64 {
+    let mantissa = unpacked.mantissa as u64 & 0xFF_FFFF_FFFF;
+    ((mantissa >> 16) << 24) | ((unpacked.exponent as u8 as u64) << 16) | (mantissa & 0xFFFF)
+}
+
+/// How a word's mantissa and exponent relate to the normalized form the standard requires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Form {
+    /// The two most significant mantissa bits differ
+    Normalized,
+    /// The word is all zeros
+    Zero,
+    /// The mantissa is not zero but could be shifted up without losing bits
+    Unnormalized,
+    /// The mantissa is zero but the exponent is not
+    DirtyZero,
+}
+
+fn form(unpacked: Unpacked, fraction: u32) -> Form {
+    let Unpacked { mantissa, exponent } = unpacked;
+    let half = 1i64 << (fraction - 1);
+    if mantissa == 0 {
+        if exponent == 0 {
+            Form::Zero
+        } else {
+            Form::DirtyZero
+        }
+    } else if mantissa >= half || mantissa < -half {
+        Form::Normalized
+    } else {
+        Form::Unnormalized
+    }
+}
+
+/// Shift a non-normalized mantissa up as far as the exponent allows, without changing the value
+///
+/// Zero becomes an all-zero word, so every value has exactly one canonical encoding.
+fn canonical(unpacked: Unpacked, fraction: u32, min_exponent: i32) -> Unpacked {
+    let Unpacked { mantissa, exponent } = unpacked;
+    if mantissa == 0 {
+        return Unpacked {
+            mantissa: 0,
+            exponent: 0,
+        };
+    }
+    let sign_bits = if mantissa < 0 {
+        (!mantissa).leading_zeros()
+    } else {
+        mantissa.leading_zeros()
+    };
+    let shift = (sign_bits as i32 - (64 - fraction as i32)).min(exponent - min_exponent);
+    Unpacked {
+        mantissa: mantissa << shift,
+        exponent: exponent - shift,
+    }
+}
+
+/// Round `(-1)^negative * magnitude * 2^scale` to a normalized mantissa with `fraction` bits
+/// after the sign and an 8-bit exponent, saturating on overflow and flushing to zero on underflow.
+fn round_pack(negative: bool, magnitude: u128, scale: i32, fraction: u32) -> Unpacked {
+    try_round_pack(
+        negative,
+        magnitude,
+        scale,
+        fraction,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    )
+    .unwrap_or_else(|clamped| clamped)
+}
+
+/// Like [`round_pack`], rounding as `rounding` says and normalizing as `dialect` does, but an
+/// overflowing or underflowing result is returned as the error
+const fn try_round_pack(
+    negative: bool,
+    magnitude: u128,
+    scale: i32,
+    fraction: u32,
+    rounding: Rounding,
+    dialect: Dialect,
+) -> Result<Unpacked, Unpacked> {
+    const ZERO: Unpacked = Unpacked {
+        mantissa: 0,
+        exponent: 0,
+    };
+    if magnitude == 0 {
+        return Ok(ZERO);
+    }
+
+    let shift = (128 - magnitude.leading_zeros()) as i32 - fraction as i32;
+    let mut rounded = if shift > 0 {
+        let kept = magnitude >> shift;
+        let round = (magnitude >> (shift - 1)) & 1 == 1;
+        let sticky = magnitude & ((1 << (shift - 1)) - 1) != 0;
+        let up = match rounding {
+            Rounding::NearestTiesAway => round,
+            Rounding::NearestTiesEven => round && (sticky || kept & 1 == 1),
+            Rounding::TowardZero => false,
+            Rounding::TowardNegative => negative && (round || sticky),
+        };
+        kept + up as u128
+    } else {
+        magnitude << -shift
+    };
+    let mut exponent = scale + shift + fraction as i32;
+
+    let one = 1u128 << fraction;
+    let half = one >> 1;
+    let sign_magnitude = matches!(dialect, Dialect::SignMagnitude);
+    if rounded == one && (!negative || sign_magnitude) {
+        rounded = half;
+        exponent += 1;
+    } else if negative && rounded == half && !sign_magnitude {
+        // -0.5 is not normalized, -1.0 one exponent lower is
+        rounded = one;
+        exponent -= 1;
+    }
+
+    if exponent > 127 {
+        return Err(saturate(negative, fraction, dialect));
+    }
+    if exponent < -128 {
+        return Err(ZERO);
+    }
+    let mantissa = rounded as i64;
+    Ok(Unpacked {
+        mantissa: if negative { -mantissa } else { mantissa },
+        exponent,
+    })
+}
+
+/// The most positive or most negative value of the format, whose magnitude in the
+/// sign-magnitude dialect is no larger than the most positive
+const fn saturate(negative: bool, fraction: u32, dialect: Dialect) -> Unpacked {
+    Unpacked {
+        mantissa: match (negative, dialect) {
+            (true, Dialect::TwosComplement) => -(1 << fraction),
+            (true, Dialect::SignMagnitude) => 1 - (1 << fraction),
+            (false, _) => (1 << fraction) - 1,
+        },
+        exponent: 127,
+    }
+}
+
+/// The mantissa field of a `dialect` word holding `unpacked`: in the sign-magnitude dialect
+/// the magnitude with the sign above it
+const fn to_dialect(unpacked: Unpacked, fraction: u32, dialect: Dialect) -> Unpacked {
+    let Unpacked { mantissa, exponent } = unpacked;
+    match dialect {
+        Dialect::TwosComplement => unpacked,
+        Dialect::SignMagnitude => Unpacked {
+            mantissa: mantissa.unsigned_abs() as i64 | ((mantissa < 0) as i64) << fraction,
+            exponent,
+        },
+    }
+}
+
+/// The signed mantissa and exponent of a `dialect` word, the inverse of [`to_dialect`]; a
+/// sign-magnitude word with a zero magnitude is zero whatever its sign
+const fn from_dialect(unpacked: Unpacked, fraction: u32, dialect: Dialect) -> Unpacked {
+    let Unpacked { mantissa, exponent } = unpacked;
+    match dialect {
+        Dialect::TwosComplement => unpacked,
+        Dialect::SignMagnitude => {
+            let magnitude = mantissa & ((1 << fraction) - 1);
+            Unpacked {
+                mantissa: if mantissa >> fraction & 1 == 1 {
+                    -magnitude
+                } else {
+                    magnitude
+                },
+                exponent,
+            }
+        }
+    }
+}
+
+fn add(
+    a: Unpacked,
+    b: Unpacked,
+    fraction: u32,
+    rounding: Rounding,
+    dialect: Dialect,
+) -> Result<Unpacked, Unpacked> {
+    if a.mantissa == 0 {
+        return try_round_pack(
+            b.mantissa < 0,
+            b.mantissa.unsigned_abs() as u128,
+            b.exponent - fraction as i32,
+            fraction,
+            rounding,
+            dialect,
+        );
+    }
+    if b.mantissa == 0 {
+        return add(b, a, fraction, rounding, dialect);
+    }
+
+    let (big, small) = if a.exponent >= b.exponent {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    let distance = (big.exponent - small.exponent) as u32;
+    // Beyond this distance the smaller operand is far below half an LSB of the result, and only
+    // its sign matters, to the directed roundings: it stands in as one bit below the round bit
+    let (sum, scale) = if distance > 64 {
+        (
+            ((big.mantissa as i128) << 2) + small.mantissa.signum() as i128,
+            big.exponent - 2,
+        )
+    } else {
+        (
+            ((big.mantissa as i128) << distance) + small.mantissa as i128,
+            small.exponent,
+        )
+    };
+    try_round_pack(
+        sum < 0,
+        sum.unsigned_abs(),
+        scale - fraction as i32,
+        fraction,
+        rounding,
+        dialect,
+    )
+}
+
+fn neg(a: Unpacked, fraction: u32) -> Unpacked {
+    // Negating -1.0 needs the next exponent up, which may saturate
+    round_pack(
+        a.mantissa > 0,
+        a.mantissa.unsigned_abs() as u128,
+        a.exponent - fraction as i32,
+        fraction,
+    )
+}
+
+fn mul(
+    a: Unpacked,
+    b: Unpacked,
+    fraction: u32,
+    rounding: Rounding,
+    dialect: Dialect,
+) -> Result<Unpacked, Unpacked> {
+    let product = a.mantissa as i128 * b.mantissa as i128;
+    try_round_pack(
+        product < 0,
+        product.unsigned_abs(),
+        a.exponent + b.exponent - 2 * fraction as i32,
+        fraction,
+        rounding,
+        dialect,
+    )
+}
+
+fn div(
+    a: Unpacked,
+    b: Unpacked,
+    fraction: u32,
+    rounding: Rounding,
+    dialect: Dialect,
+) -> Result<Unpacked, Unpacked> {
+    let negative = (a.mantissa < 0) != (b.mantissa < 0);
+    if b.mantissa == 0 {
+        if a.mantissa == 0 {
+            return try_round_pack(false, 0, 0, fraction, rounding, dialect);
+        }
+        return Err(saturate(a.mantissa < 0, fraction, dialect));
+    }
+
+    // Enough quotient bits for any pair of mantissas, normalized or not, and a sticky bit for
+    // the remainder
+    let extra = 2 * fraction + 4;
+    let (dividend, divisor) = (
+        (a.mantissa.unsigned_abs() as u128) << extra,
+        b.mantissa.unsigned_abs() as u128,
+    );
+    let quotient = (dividend / divisor) | (dividend % divisor != 0) as u128;
+    try_round_pack(
+        negative,
+        quotient,
+        a.exponent - b.exponent - extra as i32,
+        fraction,
+        rounding,
+        dialect,
+    )
+}
+
+/// An arithmetic operation on two words
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Op {
+    /// `a + b`
+    Add,
+    /// `a - b`
+    Sub,
+    /// `a * b`
+    Mul,
+    /// `a / b`
+    Div,
+}
+
+/// Apply `op` to two words of `dialect`, rounding as `rounding` says; a result beyond the
+/// format is returned, saturated or flushed to zero, as the error
+fn apply(
+    op: Op,
+    a: Unpacked,
+    b: Unpacked,
+    fraction: u32,
+    rounding: Rounding,
+    dialect: Dialect,
+) -> Result<Unpacked, Unpacked> {
+    let (a, b) = (
+        from_dialect(a, fraction, dialect),
+        from_dialect(b, fraction, dialect),
+    );
+    let result = match op {
+        Op::Add => add(a, b, fraction, rounding, dialect),
+        Op::Sub => add(a, negate(b), fraction, rounding, dialect),
+        Op::Mul => mul(a, b, fraction, rounding, dialect),
+        Op::Div => div(a, b, fraction, rounding, dialect),
+    };
+    let packed = |unpacked| to_dialect(unpacked, fraction, dialect);
+    result.map(packed).map_err(packed)
+}
+
+/// The word of a result, saturated or flushed to zero if it is beyond the format
+fn clamped<T>(result: Result<T, T>) -> T {
+    result.unwrap_or_else(|clamped| clamped)
+}
+
+/// The unpacked word with its mantissa negated, exactly
+fn negate(a: Unpacked) -> Unpacked {
+    Unpacked {
+        mantissa: -a.mantissa,
+        exponent: a.exponent,
+    }
+}
+
+/// Apply `op` to two 32-bit MIL-1750A words, see [`apply`]
+pub(crate) fn apply32(
+    op: Op,
+    a: u32,
+    b: u32,
+    rounding: Rounding,
+    dialect: Dialect,
+) -> Result<u32, u32> {
+    apply(op, unpack32(a), unpack32(b), FRACTION_32, rounding, dialect)
+        .map(pack32)
+        .map_err(pack32)
+}
+
+/// Apply `op` to two 48-bit MIL-1750A words, see [`apply`]
+pub(crate) fn apply48(
+    op: Op,
+    a: u64,
+    b: u64,
+    rounding: Rounding,
+    dialect: Dialect,
+) -> Result<u64, u64> {
+    apply(op, unpack48(a), unpack48(b), FRACTION_48, rounding, dialect)
+        .map(pack48)
+        .map_err(pack48)
+}
+
+/// Round an `f64` to the nearest value with a `fraction`-bit mantissa, saturating
+pub(crate) fn round_f64(value: f64, fraction: u32) -> Unpacked {
+    if value.is_nan() {
+        return round_pack(false, 0, 0, fraction);
+    }
+    clamped(try_round_f64(
+        value,
+        fraction,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ))
+}
+
+/// Round an `f64` to a `fraction`-bit mantissa as `rounding` says, normalized as `dialect`
+/// does; infinities and magnitudes outside the format are returned, clamped, as the error
+const fn try_round_f64(
+    value: f64,
+    fraction: u32,
+    rounding: Rounding,
+    dialect: Dialect,
+) -> Result<Unpacked, Unpacked> {
+    if value.is_infinite() {
+        return Err(saturate(value < 0.0, fraction, dialect));
+    }
+    let (magnitude, scale) = f64_parts(value);
+    try_round_pack(
+        value < 0.0,
+        magnitude as u128,
+        scale,
+        fraction,
+        rounding,
+        dialect,
+    )
+}
+
+/// The magnitude of a finite `f64` as `significand * 2^scale`
+const fn f64_parts(value: f64) -> (u64, i32) {
+    let bits = value.to_bits();
+    let biased = ((bits >> 52) & 0x7FF) as i32;
+    let significand = bits & ((1 << 52) - 1);
+    if biased == 0 {
+        (significand, -1074)
+    } else {
+        (significand | (1 << 52), biased - 1075)
+    }
+}
+
+/// Round an `f64` to a `fraction`-bit mantissa away from zero with probability equal to its
+/// distance from the value towards zero, in units of the gap between them, and towards zero
+/// otherwise, saturating; `random` is uniformly distributed and decides the direction
+pub(crate) fn round_f64_stochastic(value: f64, fraction: u32, random: u64) -> Unpacked {
+    if !value.is_finite() {
+        return round_f64(value, fraction);
+    }
+    let (magnitude, scale) = f64_parts(value);
+    let shift = (64 - magnitude.leading_zeros()) as i32 - fraction as i32;
+    if shift <= 0 {
+        // Exact
+        return round_f64(value, fraction);
+    }
+    // The bits below the mantissa, against as many of the top bits of `random`
+    let remainder = magnitude & ((1 << shift) - 1);
+    let up = (random >> (64 - shift)) < remainder;
+    // The chosen neighbour is exact, so rounding it again cannot move it
+    round_pack(
+        value < 0.0,
+        ((magnitude >> shift) + up as u64) as u128,
+        scale + shift,
+        fraction,
+    )
+}
+
+/// Round the exact ratio `(-1)^negative * numerator / denominator` to the nearest value with
+/// a `fraction`-bit mantissa, like [`try_round_f64`] but without rounding to an `f64` first.
+/// `denominator` must be non-zero and below `2^126`.
+#[cfg(any(feature = "rust_decimal", test))]
+pub(crate) fn try_round_ratio(
+    negative: bool,
+    numerator: u128,
+    denominator: u128,
+    fraction: u32,
+) -> Result<Unpacked, Unpacked> {
+    if numerator == 0 {
+        return try_round_pack(
+            false,
+            0,
+            0,
+            fraction,
+            Rounding::NearestTiesAway,
+            Dialect::TwosComplement,
+        );
+    }
+    // Scale the quotient to at least fraction + 2 bits, so its truncation keeps the round bit
+    let bits = |n: u128| (128 - n.leading_zeros()) as i32;
+    let scale = fraction as i32 + 3 - bits(numerator) + bits(denominator);
+    let quotient = if scale > 0 {
+        // Long division, one quotient bit at a time, so the numerator never overflows
+        let (mut quotient, mut remainder) = (numerator / denominator, numerator % denominator);
+        for _ in 0..scale {
+            remainder <<= 1;
+            quotient <<= 1;
+            if remainder >= denominator {
+                remainder -= denominator;
+                quotient |= 1;
+            }
+        }
+        quotient
+    } else {
+        (numerator >> -scale) / denominator
+    };
+    try_round_pack(
+        negative,
+        quotient,
+        -scale,
+        fraction,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    )
+}
+
+/// The exact value of an unpacked word with a `fraction`-bit mantissa
+pub(crate) fn to_f64(unpacked: Unpacked, fraction: u32) -> f64 {
+    unpacked.mantissa as f64 * 2f64.powi(unpacked.exponent - fraction as i32)
+}
+
+/// Add two 32-bit MIL-1750A words
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::add32;
+/// // 1.0 + 5.234
+/// assert_eq!(add32(0x40000001, 0x53BE7703), 0x63BE7703);
+/// ```
+pub fn add32(a: u32, b: u32) -> u32 {
+    clamped(apply32(
+        Op::Add,
+        a,
+        b,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ))
+}
+
+/// Subtract the 32-bit MIL-1750A word `b` from `a`
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::sub32;
+/// // 1.0 - 1.0
+/// assert_eq!(sub32(0x40000001, 0x40000001), 0);
+/// ```
+pub fn sub32(a: u32, b: u32) -> u32 {
+    clamped(apply32(
+        Op::Sub,
+        a,
+        b,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ))
+}
+
+/// Negate a 32-bit MIL-1750A word
+///
+/// Negating the most negative value saturates to the most positive one.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::neg32;
+/// // -(-1.0)
+/// assert_eq!(neg32(0x80000000), 0x40000001);
+/// ```
+pub fn neg32(a: u32) -> u32 {
+    pack32(neg(unpack32(a), FRACTION_32))
+}
+
+/// Multiply two 32-bit MIL-1750A words
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::mul32;
+/// // -1.0 * -1.0
+/// assert_eq!(mul32(0x80000000, 0x80000000), 0x40000001);
+/// ```
+pub fn mul32(a: u32, b: u32) -> u32 {
+    clamped(apply32(
+        Op::Mul,
+        a,
+        b,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ))
+}
+
+/// Divide the 32-bit MIL-1750A word `a` by `b`
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::div32;
+/// // 1.0 / -1.0
+/// assert_eq!(div32(0x40000001, 0x80000000), 0x80000000);
+/// ```
+pub fn div32(a: u32, b: u32) -> u32 {
+    clamped(apply32(
+        Op::Div,
+        a,
+        b,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ))
+}
+
+/// Remainder of dividing the 32-bit MIL-1750A word `a` by `b`, with the sign of `a`
+///
+/// The remainder is always exact; a zero divisor gives zero.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::rem32;
+/// // 5.234 % 1.0
+/// assert_eq!(rem32(0x53BE7703, 0x40000001), 0x77CEE0FE);
+/// ```
+pub fn rem32(a: u32, b: u32) -> u32 {
+    round32(value32(a) % value32(b))
+}
+
+/// Add two 48-bit MIL-1750A words
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::add48;
+/// // 1.0 + 1.0
+/// assert_eq!(add48(0x400000010000, 0x400000010000), 0x400000020000);
+/// ```
+pub fn add48(a: u64, b: u64) -> u64 {
+    clamped(apply48(
+        Op::Add,
+        a,
+        b,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ))
+}
+
+/// Subtract the 48-bit MIL-1750A word `b` from `a`
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::sub48;
+/// // 1.0 - 2.0
+/// assert_eq!(sub48(0x400000010000, 0x400000020000), 0x800000000000);
+/// ```
+pub fn sub48(a: u64, b: u64) -> u64 {
+    clamped(apply48(
+        Op::Sub,
+        a,
+        b,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ))
+}
+
+/// Negate a 48-bit MIL-1750A word
+///
+/// Negating the most negative value saturates to the most positive one.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::neg48;
+/// // -(2.0)
+/// assert_eq!(neg48(0x400000020000), 0x800000010000);
+/// ```
+pub fn neg48(a: u64) -> u64 {
+    pack48(neg(unpack48(a), FRACTION_48))
+}
+
+/// Multiply two 48-bit MIL-1750A words
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::mul48;
+/// // 2.0 * 2.0
+/// assert_eq!(mul48(0x400000020000, 0x400000020000), 0x400000030000);
+/// ```
+pub fn mul48(a: u64, b: u64) -> u64 {
+    clamped(apply48(
+        Op::Mul,
+        a,
+        b,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ))
+}
+
+/// Divide the 48-bit MIL-1750A word `a` by `b`
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::div48;
+/// // 1.0 / 2.0
+/// assert_eq!(div48(0x400000010000, 0x400000020000), 0x400000000000);
+/// ```
+pub fn div48(a: u64, b: u64) -> u64 {
+    clamped(apply48(
+        Op::Div,
+        a,
+        b,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ))
+}
+
+/// Remainder of dividing the 48-bit MIL-1750A word `a` by `b`, with the sign of `a`
+///
+/// The remainder is always exact; a zero divisor gives zero.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::arith::rem48;
+/// // -3.0 % 2.0
+/// assert_eq!(rem48(0xA00000020000, 0x400000020000), 0x800000000000);
+/// ```
+pub fn rem48(a: u64, b: u64) -> u64 {
+    round48(value48(a) % value48(b))
+}
+
+/// Round an `f64` to a 16-bit MIL-1750A word, saturating
+pub(crate) fn round16(value: f64) -> u16 {
+    saturate16(round_f64(value, FRACTION_16))
+}
+
+/// Round an `f64` to a 16-bit MIL-1750A word at random, see [`round_f64_stochastic`]
+pub(crate) fn round16_stochastic(value: f64, random: u64) -> u16 {
+    saturate16(round_f64_stochastic(value, FRACTION_16, random))
+}
+
+/// Pack a mantissa and 8-bit exponent as a 16-bit word, saturating exponents beyond its six
+/// bits
+fn saturate16(rounded: Unpacked) -> u16 {
+    pack16(clamp16(rounded, Dialect::TwosComplement))
+}
+
+/// Saturate a mantissa and 8-bit exponent to the six exponent bits of a 16-bit word of
+/// `dialect`, or flush it to zero
+fn clamp16(rounded: Unpacked, dialect: Dialect) -> Unpacked {
+    if rounded.exponent > 31 {
+        Unpacked {
+            exponent: 31,
+            ..saturate(rounded.mantissa < 0, FRACTION_16, dialect)
+        }
+    } else if rounded.exponent < -32 {
+        Unpacked {
+            mantissa: 0,
+            exponent: 0,
+        }
+    } else {
+        rounded
+    }
+}
+
+/// Round an `f64` to a 32-bit MIL-1750A word, saturating
+pub(crate) fn round32(value: f64) -> u32 {
+    pack32(round_f64(value, FRACTION_32))
+}
+
+/// Round an `f64` to a 48-bit MIL-1750A word, saturating
+pub(crate) fn round48(value: f64) -> u64 {
+    pack48(round_f64(value, FRACTION_48))
+}
+
+/// Round an `f64` to a 32-bit MIL-1750A word at random, see [`round_f64_stochastic`]
+pub(crate) fn round32_stochastic(value: f64, random: u64) -> u32 {
+    pack32(round_f64_stochastic(value, FRACTION_32, random))
+}
+
+/// Round an `f64` to a 48-bit MIL-1750A word at random, see [`round_f64_stochastic`]
+pub(crate) fn round48_stochastic(value: f64, random: u64) -> u64 {
+    pack48(round_f64_stochastic(value, FRACTION_48, random))
+}
+
+/// Round an `f64` to a 16-bit MIL-1750A word, or `None` if it is not finite or out of range
+pub(crate) const fn try_round16(value: f64) -> Option<u16> {
+    if value.is_nan() {
+        return None;
+    }
+    match try_round_f64(
+        value,
+        FRACTION_16,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ) {
+        Ok(rounded)
+            if rounded.mantissa == 0 || (rounded.exponent >= -32 && rounded.exponent <= 31) =>
+        {
+            Some(pack16(rounded))
+        }
+        _ => None,
+    }
+}
+
+/// Round an `f64` to a 32-bit MIL-1750A word, or `None` if it is not finite or out of range
+pub(crate) const fn try_round32(value: f64) -> Option<u32> {
+    if value.is_nan() {
+        return None;
+    }
+    match try_round_f64(
+        value,
+        FRACTION_32,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ) {
+        Ok(rounded) => Some(pack32(rounded)),
+        Err(_) => None,
+    }
+}
+
+/// Round an `f64` to a 48-bit MIL-1750A word, or `None` if it is not finite or out of range
+pub(crate) const fn try_round48(value: f64) -> Option<u64> {
+    if value.is_nan() {
+        return None;
+    }
+    match try_round_f64(
+        value,
+        FRACTION_48,
+        Rounding::NearestTiesAway,
+        Dialect::TwosComplement,
+    ) {
+        Ok(rounded) => Some(pack48(rounded)),
+        Err(_) => None,
+    }
+}
+
+/// Round a finite `f64` to a 16-bit MIL-1750A word of `dialect` as `rounding` says; a value
+/// beyond the format is returned, saturated or flushed to zero, as the error
+pub(crate) fn try_round16_with(
+    value: f64,
+    rounding: Rounding,
+    dialect: Dialect,
+) -> Result<u16, u16> {
+    let packed = |rounded| pack16(to_dialect(rounded, FRACTION_16, dialect));
+    match try_round_f64(value, FRACTION_16, rounding, dialect) {
+        Ok(rounded) if rounded.mantissa == 0 || (-32..=31).contains(&rounded.exponent) => {
+            Ok(packed(rounded))
+        }
+        Ok(rounded) | Err(rounded) => Err(packed(clamp16(rounded, dialect))),
+    }
+}
+
+/// Round a finite `f64` to a 32-bit MIL-1750A word of `dialect` as `rounding` says, see
+/// [`try_round16_with`]
+pub(crate) fn try_round32_with(
+    value: f64,
+    rounding: Rounding,
+    dialect: Dialect,
+) -> Result<u32, u32> {
+    let packed = |rounded| pack32(to_dialect(rounded, FRACTION_32, dialect));
+    try_round_f64(value, FRACTION_32, rounding, dialect)
+        .map(packed)
+        .map_err(packed)
+}
+
+/// Round a finite `f64` to a 48-bit MIL-1750A word of `dialect` as `rounding` says, see
+/// [`try_round16_with`]
+pub(crate) fn try_round48_with(
+    value: f64,
+    rounding: Rounding,
+    dialect: Dialect,
+) -> Result<u64, u64> {
+    let packed = |rounded| pack48(to_dialect(rounded, FRACTION_48, dialect));
+    try_round_f64(value, FRACTION_48, rounding, dialect)
+        .map(packed)
+        .map_err(packed)
+}
+
+/// Round an exact ratio to a 16-bit MIL-1750A word, or `None` if it is out of range
+#[cfg(any(feature = "rust_decimal", test))]
+pub(crate) fn try_round_ratio16(negative: bool, numerator: u128, denominator: u128) -> Option<u16> {
+    try_round_ratio(negative, numerator, denominator, FRACTION_16)
+        .ok()
+        .filter(|rounded| rounded.mantissa == 0 || (-32..=31).contains(&rounded.exponent))
+        .map(pack16)
+}
+
+/// Round an exact ratio to a 32-bit MIL-1750A word, or `None` if it is out of range
+#[cfg(any(feature = "rust_decimal", test))]
+pub(crate) fn try_round_ratio32(negative: bool, numerator: u128, denominator: u128) -> Option<u32> {
+    try_round_ratio(negative, numerator, denominator, FRACTION_32)
+        .ok()
+        .map(pack32)
+}
+
+/// Round an exact ratio to a 48-bit MIL-1750A word, or `None` if it is out of range
+#[cfg(any(feature = "rust_decimal", test))]
+pub(crate) fn try_round_ratio48(negative: bool, numerator: u128, denominator: u128) -> Option<u64> {
+    try_round_ratio(negative, numerator, denominator, FRACTION_48)
+        .ok()
+        .map(pack48)
+}
+
+/// The form of a 16-bit MIL-1750A word
+pub(crate) fn form16(word: u16) -> Form {
+    form(unpack16(word), FRACTION_16)
+}
+
+/// The form of a 32-bit MIL-1750A word
+pub(crate) fn form32(word: u32) -> Form {
+    form(unpack32(word), FRACTION_32)
+}
+
+/// The form of a 48-bit MIL-1750A word
+pub(crate) fn form48(word: u64) -> Form {
+    form(unpack48(word), FRACTION_48)
+}
+
+/// The canonical encoding of a 16-bit MIL-1750A word's value
+pub(crate) fn canonical16(word: u16) -> u16 {
+    pack16(canonical(unpack16(word), FRACTION_16, -32))
+}
+
+/// The canonical encoding of a 32-bit MIL-1750A word's value
+pub(crate) fn canonical32(word: u32) -> u32 {
+    pack32(canonical(unpack32(word), FRACTION_32, -128))
+}
+
+/// The canonical encoding of a 48-bit MIL-1750A word's value
+pub(crate) fn canonical48(word: u64) -> u64 {
+    pack48(canonical(unpack48(word), FRACTION_48, -128))
+}
+
+/// A word's sign, and its magnitude as an integer that orders like it: the magnitude of its
+/// canonical mantissa plus `2^(fraction - 1)` per step of its exponent above the minimum, or
+/// zero for zero
+///
+/// A normalized magnitude spans one such step, so a mantissa of exactly 1.0 meets 0.5 at the
+/// exponent above, which is the same value.
+fn rank(unpacked: Unpacked, fraction: u32, min_exponent: i32) -> (bool, u64) {
+    let Unpacked { mantissa, exponent } = canonical(unpacked, fraction, min_exponent);
+    if mantissa == 0 {
+        return (false, 0);
+    }
+    let steps = (exponent - min_exponent) as u64;
+    (
+        mantissa < 0,
+        (steps << (fraction - 1)) + mantissa.unsigned_abs(),
+    )
+}
+
+/// The sign and ordered magnitude of a 16-bit MIL-1750A word, see [`rank`]
+pub(crate) fn rank16(word: u16) -> (bool, u64) {
+    rank(unpack16(word), FRACTION_16, -32)
+}
+
+/// The sign and ordered magnitude of a 32-bit MIL-1750A word, see [`rank`]
+pub(crate) fn rank32(word: u32) -> (bool, u64) {
+    rank(unpack32(word), FRACTION_32, -128)
+}
+
+/// The sign and ordered magnitude of a 48-bit MIL-1750A word, see [`rank`]
+pub(crate) fn rank48(word: u64) -> (bool, u64) {
+    rank(unpack48(word), FRACTION_48, -128)
+}
+
+/// The exact value of a 16-bit MIL-1750A word, with a signed mantissa and exponent
+pub(crate) fn value16(word: u16) -> f64 {
+    to_f64(unpack16(word), FRACTION_16)
+}
+
+/// The exact value of a 32-bit MIL-1750A word
+pub(crate) fn value32(word: u32) -> f64 {
+    to_f64(unpack32(word), FRACTION_32)
+}
+
+/// The exact value of a 48-bit MIL-1750A word
+pub(crate) fn value48(word: u64) -> f64 {
+    to_f64(unpack48(word), FRACTION_48)
+}
+
+/// The exact value of a 16-bit word of `dialect`
+pub(crate) fn value16_in(word: u16, dialect: Dialect) -> f64 {
+    to_f64(
+        from_dialect(unpack16(word), FRACTION_16, dialect),
+        FRACTION_16,
+    )
+}
+
+/// The exact value of a 32-bit word of `dialect`
+pub(crate) fn value32_in(word: u32, dialect: Dialect) -> f64 {
+    to_f64(
+        from_dialect(unpack32(word), FRACTION_32, dialect),
+        FRACTION_32,
+    )
+}
+
+/// The exact value of a 48-bit word of `dialect`
+pub(crate) fn value48_in(word: u64, dialect: Dialect) -> f64 {
+    to_f64(
+        from_dialect(unpack48(word), FRACTION_48, dialect),
+        FRACTION_48,
+    )
+}
+
+/// The value of the least significant mantissa bit of a 16-bit MIL-1750A word
+pub(crate) fn ulp16(word: u16) -> f64 {
+    2f64.powi(unpack16(word).exponent - FRACTION_16 as i32)
+}
+
+/// The value of the least significant mantissa bit of a 32-bit MIL-1750A word
+pub(crate) fn ulp32(word: u32) -> f64 {
+    2f64.powi(unpack32(word).exponent - FRACTION_32 as i32)
+}
+
+/// The value of the least significant mantissa bit of a 48-bit MIL-1750A word
+pub(crate) fn ulp48(word: u64) -> f64 {
+    2f64.powi(unpack48(word).exponent - FRACTION_48 as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{extended, single};
+
+    #[test]
+    fn test_unpack_pack() {
+        for word in [0x40000001, 0x80000000, 0x997AE105, 0x400000FF, 0x7FFFFF7F] {
+            assert_eq!(pack32(unpack32(word)), word);
+        }
+        for word in [0x69A3B50754AB, 0x9B781202AEEF, 0x800000FF0000] {
+            assert_eq!(pack48(unpack48(word)), word);
+        }
+        assert_eq!(value32(0x400000FF), 0.25);
+        assert_eq!(value48(0x9B781202AEEF), -value48(0x6487ED025111));
+    }
+
+    #[test]
+    fn test_round_matches_encoders() {
+        for value in [1.0f32, -1.0, 5.234, -25.63, 25.63, 0.1, -0.75, 1e30, -3e-30] {
+            assert_eq!(round32(value.into()), single::encode(value), "{value}");
+        }
+        for value in [
+            105.639485637361,
+            std::f64::consts::PI,
+            -std::f64::consts::PI,
+            -1.0,
+        ] {
+            assert_eq!(round48(value), extended::encode(value), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_round_edges() {
+        assert_eq!(round32(0.0), 0);
+        assert_eq!(round32(f64::NAN), 0);
+        assert_eq!(round32(1e300), 0x7FFFFF7F);
+        assert_eq!(round32(-1e300), 0x8000007F);
+        assert_eq!(round32(1e-300), 0);
+        assert_eq!(round32(-0.5), 0x800000FF);
+        assert_eq!(round48(f64::NEG_INFINITY), 0x8000007F0000);
+        assert_eq!(try_round32(-2f64.powi(127)), Some(0x8000007F));
+        assert_eq!(try_round32(2f64.powi(127)), None);
+        assert_eq!(try_round32(2f64.powi(-129)), Some(0x40000080));
+        assert_eq!(try_round32(2f64.powi(-131)), None);
+        assert_eq!(try_round48(f64::NAN), None);
+    }
+
+    #[test]
+    fn test_round48_near_ties() {
+        // (m + 1/2 ± 2^-12) ulp is exact in an f64 and must round to m + 1 or m, whatever the
+        // magnitude of m
+        let mut state = 0x9E3779B97F4A7C15u64;
+        for _ in 0..10_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let mantissa = (1 << 38) | 1 | (state >> 26) as i64 & ((1 << 38) - 4);
+            let exponent = (state & 0xFF) as i32 - 128;
+            let scale = 2f64.powi(exponent - 39 - 13);
+            let near = |offset: i64| ((2 * mantissa + 1) << 12) as f64 + offset as f64;
+            let word = |mantissa: i64| pack48(Unpacked { mantissa, exponent });
+            assert_eq!(round48(near(1) * scale), word(mantissa + 1));
+            assert_eq!(round48(near(0) * scale), word(mantissa + 1));
+            assert_eq!(round48(near(-1) * scale), word(mantissa));
+            assert_eq!(round48(-near(1) * scale), word(-mantissa - 1));
+            assert_eq!(round48(-near(-1) * scale), word(-mantissa));
+        }
+    }
+
+    #[test]
+    fn test_round16() {
+        assert_eq!(round16(1.0), 0x4001);
+        assert_eq!(round16(-1.0), 0x8000);
+        assert_eq!(round16(12.4), 0x6344);
+        assert_eq!(round16(1e30), 0x7FDF);
+        assert_eq!(round16(-1e30), 0x801F);
+        assert_eq!(round16(1e-30), 0);
+        assert_eq!(try_round16(-25.63), Some(0x9985));
+        assert_eq!(try_round16(2f64.powi(31)), None);
+        assert_eq!(try_round16(2f64.powi(-34)), None);
+    }
+
+    #[test]
+    fn test_form() {
+        assert_eq!(form32(0x40000001), Form::Normalized);
+        assert_eq!(form32(0x80000000), Form::Normalized);
+        assert_eq!(form32(0xC0000000), Form::Unnormalized);
+        assert_eq!(form32(0x20000001), Form::Unnormalized);
+        assert_eq!(form32(0), Form::Zero);
+        assert_eq!(form32(0x00000005), Form::DirtyZero);
+        assert_eq!(form48(0x000000000001), Form::Unnormalized);
+        assert_eq!(form48(0x000000010000), Form::DirtyZero);
+        assert_eq!(form16(0x6344), Form::Normalized);
+        assert_eq!(form16(0x003F), Form::DirtyZero);
+    }
+
+    #[test]
+    fn test_canonical() {
+        assert_eq!(canonical32(0x20000002), 0x40000001);
+        assert_eq!(canonical32(0xC0000002), 0x80000001);
+        assert_eq!(canonical32(0x00000182), 0x00000480);
+        assert_eq!(canonical32(0x00000180), 0x00000180);
+        assert_eq!(canonical32(0x0000007F), 0);
+        assert_eq!(canonical32(0x53BE7703), 0x53BE7703);
+        assert_eq!(canonical48(0x200000020000), 0x400000010000);
+        assert_eq!(canonical16(0x3002), 0x6001);
+        assert_eq!(value16(0x6344), 12.40625);
+        assert_eq!(value16(canonical16(0xFFC0)), -2f64.powi(-9));
+    }
+
+    #[test]
+    fn test_arith32() {
+        let [a, b] = [5.234f32, -25.63].map(single::encode);
+        assert_eq!(
+            value32(add32(a, b)),
+            value32(round32(value32(a) + value32(b)))
+        );
+        assert_eq!(
+            value32(sub32(a, b)),
+            value32(round32(value32(a) - value32(b)))
+        );
+        assert_eq!(mul32(a, b), round32(value32(a) * value32(b)));
+        assert_eq!(div32(a, b), round32(value32(a) / value32(b)));
+        assert_eq!(sub32(a, a), 0);
+        assert_eq!(div32(a, 0), 0x7FFFFF7F);
+        assert_eq!(div32(0, 0), 0);
+        assert_eq!(mul32(0x7FFFFF7F, 0x7FFFFF7F), 0x7FFFFF7F);
+        assert_eq!(mul32(0x40000080, 0x40000080), 0);
+        assert_eq!(neg32(a), single::encode(-5.234));
+        assert_eq!(neg32(0x8000007F), 0x7FFFFF7F);
+        assert_eq!(neg32(0), 0);
+    }
+
+    #[test]
+    fn test_arith48() {
+        let [a, b] = [105.639485637361, -std::f64::consts::PI].map(extended::encode);
+        assert_eq!(add48(a, b), round48(value48(a) + value48(b)));
+        assert_eq!(mul48(a, b), round48(value48(a) * value48(b)));
+        assert_eq!(sub48(b, b), 0);
+        assert_eq!(rem48(a, b), round48(value48(a) % value48(b)));
+        assert_eq!(rem48(a, 0), 0);
+        assert_eq!(div48(b, b), 0x400000010000);
+        assert_eq!(neg48(neg48(b)), b);
+    }
+}
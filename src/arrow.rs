@@ -0,0 +1,79 @@
+//! # Arrow columns
+//! Convert whole [Apache Arrow](arrow_array) columns of MIL-1750A words, such as those of a
+//! Polars DataFrame read from Parquet, into columns of IEEE values and back.
+//!
+//! Each function maps the column's value buffer through the format's conversion in one pass,
+//! without building an element at a time, and keeps its validity bitmap, so null words stay
+//! null. Words are unsigned integers holding the word in their low bits: `UInt16Array` for the
+//! 16-bit format, which decodes to `Float32Array` since `f32` holds all of its values,
+//! `UInt32Array` for the 32-bit format and `UInt64Array` for the 48-bit format.
+//!
+//! ```
+//! use arrow_array::{Array, Float32Array, UInt32Array};
+//! use mil1750a_converter::arrow::{decode32, encode32};
+//!
+//! let words = UInt32Array::from(vec![Some(0x40000001), None, Some(0x53BE7703)]);
+//! let values = decode32(&words);
+//! assert_eq!(values, Float32Array::from(vec![Some(1.0), None, Some(5.234)]));
+//! assert_eq!(encode32(&values), words);
+//! assert_eq!(values.null_count(), 1);
+//! ```
+
+use crate::{MilFormat, F32, F48};
+use arrow_array::{Float32Array, Float64Array, UInt16Array, UInt32Array, UInt64Array};
+
+/// Decode a column of 16-bit words, see [`half::decode_f32`](crate::half::decode_f32)
+pub fn decode16(words: &UInt16Array) -> Float32Array {
+    words.unary(crate::half::decode_f32)
+}
+
+/// Encode a column of values as 16-bit words, see
+/// [`half::encode_f32`](crate::half::encode_f32)
+pub fn encode16(values: &Float32Array) -> UInt16Array {
+    values.unary(crate::half::encode_f32)
+}
+
+/// Decode a column of 32-bit words, see [`MilFormat::decode`]
+pub fn decode32(words: &UInt32Array) -> Float32Array {
+    words.unary(F32::decode)
+}
+
+/// Encode a column of values as 32-bit words, see [`MilFormat::encode`]
+pub fn encode32(values: &Float32Array) -> UInt32Array {
+    values.unary(F32::encode)
+}
+
+/// Decode a column of 48-bit words, see [`MilFormat::decode`]
+pub fn decode48(words: &UInt64Array) -> Float64Array {
+    words.unary(F48::decode)
+}
+
+/// Encode a column of values as 48-bit words, see [`MilFormat::encode`]
+pub fn encode48(values: &Float64Array) -> UInt64Array {
+    values.unary(F48::encode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+
+    #[test]
+    fn test_round_trip() {
+        let values = Float64Array::from(vec![Some(105.639485637361), Some(1.0), None]);
+        let words = encode48(&values);
+        assert_eq!(words.value(1), 0x400000010000);
+        assert!(words.is_null(2));
+        let decoded = decode48(&words);
+        assert_eq!(decoded.value(0), 105.63948563742451);
+        assert_eq!(decoded.null_count(), 1);
+
+        let words = UInt16Array::from(vec![0x6344, 0x7FDF]);
+        let values = decode16(&words);
+        assert_eq!(values.values(), &[12.40625, 511.0 * 2f32.powi(22)]);
+        assert_eq!(encode16(&values), words);
+
+        let sliced = decode32(&UInt32Array::from(vec![0x40000001, 0x40000002]).slice(1, 1));
+        assert_eq!(sliced.values(), &[2.0]);
+    }
+}
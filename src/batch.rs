@@ -0,0 +1,1043 @@
+//! # Batch conversions
+//! Convert whole slices without stopping at the first bad element.
+//!
+//! Each function converts every element it can and reports the rest by index in
+//! [`Batch::errors`]. The plain forms leave failed elements out of [`Batch::converted`]; the
+//! `_or` forms put a sentinel in their place, so `converted[i]` always belongs to input `i`.
+//!
+//! ```
+//! use mil1750a_converter::batch;
+//! use mil1750a_converter::{Mil1750Error, F32};
+//!
+//! let batch = batch::try_encode_all_or::<F32>(&[1.0, f32::NAN, 5.234], 0);
+//! assert_eq!(batch.converted, [0x40000001, 0, 0x53BE7703]);
+//! assert!(matches!(batch.errors[..], [(1, Mil1750Error::NotFinite { .. })]));
+//! ```
+//!
+//! The `checked` decoders instead stop at the first bad word and say where it is, for buffers
+//! that should hold nothing but normalized words, and [`decode_strided`] does the same for one
+//! channel of an interleaved buffer without de-interleaving it first. [`decode_bytes_parallel`]
+//! splits a large buffer across threads. [`try_encode_mixed`] packs values of different formats,
+//! each named by a [`FormatTag`], into one stream of 16-bit words. For pipelines that keep
+//! mantissas, exponents or word halves in separate arrays, see [`soa`](crate::soa).
+//!
+//! [`decode_into_uninit`] and [`decode_extend`] write values straight into memory that has not
+//! been initialized, so a large output buffer need not be zeroed before it is filled:
+//!
+//! ```
+//! use mil1750a_converter::{batch, F32};
+//!
+//! let words = vec![0x40000001; 1 << 20];
+//! let mut values = Vec::with_capacity(words.len());
+//! batch::decode_extend::<F32>(&words, &mut values);
+//! assert_eq!(values.len(), 1 << 20);
+//! assert!(values.iter().all(|&value| value == 1.0));
+//! ```
+//!
+//! With the `tracing` feature each conversion runs in a `debug` span with the number of
+//! elements, emits a `warn` event with the index of every element that fails, and ends with a
+//! `debug` event of the counts and the time taken.
+//!
+//! The `_reported` forms also return a [`ConversionReport`] of the run, which serializes to
+//! JSON with the `serde` feature, for pipelines that must keep evidence that a run was clean.
+//! Streaming conversions can fill one as they go with [`ConversionReport::record`] and
+//! [`ConversionReport::record_failure`].
+//!
+//! ```
+//! use mil1750a_converter::batch::{self, FailureKind};
+//! use mil1750a_converter::F32;
+//!
+//! let words = [0x40000001, 0x20000001, 0x99999AFD];
+//! let (batch, report) = batch::decode_strict_all_reported::<F32>(&words);
+//! assert_eq!(batch.converted.len(), 2);
+//! assert_eq!((report.processed, report.failed()), (3, 1));
+//! assert_eq!(report.failures[&FailureKind::Unnormalized], 1);
+//! assert_eq!(report.max, Some(1.0));
+//! ```
+
+use crate::{
+    arith, fixed, BufferError, DecodeError, Decoded, Mil1750Error, MilFormat, TargetFormat,
+    WordOrder, F32, F48,
+};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// The size of the chunks [`decode_bytes_parallel`] hands to its threads, rounded down to whole
+/// words
+const PARALLEL_CHUNK_BYTES: usize = 1 << 16;
+
+/// The outcome of converting a slice
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Batch<T, E = Mil1750Error> {
+    /// The converted elements, in input order
+    pub converted: Vec<T>,
+    /// The index and error of every element that failed, in input order
+    pub errors: Vec<(usize, E)>,
+}
+
+impl<T, E> Batch<T, E> {
+    /// Whether every element converted
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// The kinds of failure a [`ConversionReport`] counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum FailureKind {
+    /// A NaN or infinite value, see [`Mil1750Error::NotFinite`]
+    NotFinite,
+    /// A value out of range of the format, see [`Mil1750Error::OutOfRange`]
+    OutOfRange,
+    /// A word that is not normalized, see [`DecodeError::Unnormalized`]
+    Unnormalized,
+    /// A zero with a non-zero exponent, see [`DecodeError::NonZeroExponent`]
+    NonZeroExponent,
+    /// Any other error
+    Other,
+}
+
+impl From<Mil1750Error> for FailureKind {
+    fn from(error: Mil1750Error) -> Self {
+        match error {
+            Mil1750Error::NotFinite { .. } => FailureKind::NotFinite,
+            Mil1750Error::OutOfRange { .. } => FailureKind::OutOfRange,
+            _ => FailureKind::Other,
+        }
+    }
+}
+
+impl From<DecodeError> for FailureKind {
+    fn from(error: DecodeError) -> Self {
+        match error {
+            DecodeError::Unnormalized { .. } => FailureKind::Unnormalized,
+            DecodeError::NonZeroExponent { .. } => FailureKind::NonZeroExponent,
+        }
+    }
+}
+
+/// A summary of a conversion run: how much was converted, how much failed and why, the
+/// range of the values and the time it took
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConversionReport {
+    /// Number of elements converted or rejected
+    pub processed: usize,
+    /// Number of elements rejected, by kind of failure; kinds that did not occur are absent
+    pub failures: BTreeMap<FailureKind, usize>,
+    /// Smallest value converted, decoded or encoded, if any was
+    pub min: Option<f64>,
+    /// Largest value converted, if any was
+    pub max: Option<f64>,
+    /// How long the conversion took
+    pub duration: Duration,
+}
+
+impl ConversionReport {
+    /// An empty report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count an element converted to or from `value`
+    pub fn record(&mut self, value: f64) {
+        self.processed += 1;
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+    }
+
+    /// Count an element rejected for `kind`
+    pub fn record_failure(&mut self, kind: impl Into<FailureKind>) {
+        self.processed += 1;
+        *self.failures.entry(kind.into()).or_default() += 1;
+    }
+
+    /// Number of elements rejected
+    pub fn failed(&self) -> usize {
+        self.failures.values().sum()
+    }
+
+    /// Whether every element converted
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Count the outcome of converting `value`
+    fn record_result<T, E: Copy + Into<FailureKind>>(&mut self, value: f64, result: &Result<T, E>) {
+        match result {
+            Ok(_) => self.record(value),
+            Err(error) => self.record_failure(*error),
+        }
+    }
+}
+
+/// Encode every value that `F` can represent, see [`MilFormat::try_encode`]
+pub fn try_encode_all<F: MilFormat>(values: &[F::Ieee]) -> Batch<F::Bits> {
+    collect(values, F::try_encode, None)
+}
+
+/// Encode every value, putting `sentinel` in place of those `F` cannot represent
+pub fn try_encode_all_or<F: MilFormat>(values: &[F::Ieee], sentinel: F::Bits) -> Batch<F::Bits> {
+    collect(values, F::try_encode, Some(sentinel))
+}
+
+/// Decode every normalized word, see [`MilFormat::decode_strict`]
+pub fn decode_strict_all<F: MilFormat>(words: &[F::Bits]) -> Batch<F::Ieee, DecodeError> {
+    collect(words, F::decode_strict, None)
+}
+
+/// Decode every word, putting `sentinel` in place of those that are not normalized
+pub fn decode_strict_all_or<F: MilFormat>(
+    words: &[F::Bits],
+    sentinel: F::Ieee,
+) -> Batch<F::Ieee, DecodeError> {
+    collect(words, F::decode_strict, Some(sentinel))
+}
+
+/// Encode every value `F` can represent, as [`try_encode_all`] does, and report the run
+pub fn try_encode_all_reported<F: MilFormat>(
+    values: &[F::Ieee],
+) -> (Batch<F::Bits>, ConversionReport)
+where
+    F::Ieee: Into<f64>,
+{
+    let start = Instant::now();
+    let mut report = ConversionReport::new();
+    let batch = collect(
+        values,
+        |value| {
+            let result = F::try_encode(value);
+            report.record_result(value.into(), &result);
+            result
+        },
+        None,
+    );
+    report.duration = start.elapsed();
+    (batch, report)
+}
+
+/// Decode every normalized word, as [`decode_strict_all`] does, and report the run
+pub fn decode_strict_all_reported<F: MilFormat>(
+    words: &[F::Bits],
+) -> (Batch<F::Ieee, DecodeError>, ConversionReport)
+where
+    F::Ieee: Into<f64>,
+{
+    let start = Instant::now();
+    let mut report = ConversionReport::new();
+    let batch = collect(
+        words,
+        |word| {
+            let result = F::decode_strict(word);
+            let value = result.map_or(f64::NAN, Into::into);
+            report.record_result(value, &result);
+            result
+        },
+        None,
+    );
+    report.duration = start.elapsed();
+    (batch, report)
+}
+
+/// Decode a buffer of words, failing on the first word that is not normalized
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{batch, BufferError, F32};
+///
+/// let error = batch::decode_checked::<F32>(&[0x40000001, 0x20000002]).unwrap_err();
+/// assert_eq!((error.index(), error.byte_offset()), (1, 4));
+/// assert!(matches!(error, BufferError::Decode { .. }));
+/// ```
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(len = words.len()))
+)]
+pub fn decode_checked<F: MilFormat>(words: &[F::Bits]) -> Result<Vec<F::Ieee>, BufferError> {
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+    let values = words
+        .iter()
+        .enumerate()
+        .map(|(index, &word)| {
+            F::decode_strict(word).map_err(|error| BufferError::Decode {
+                index,
+                byte_offset: index * F::BYTES,
+                error,
+            })
+        })
+        .collect();
+    #[cfg(feature = "tracing")]
+    finish(&values, start);
+    values
+}
+
+/// Decode a buffer of big-endian words, failing on the first word that is not normalized or
+/// on a partial word at the end
+pub fn decode_be_bytes_checked<F: MilFormat>(bytes: &[u8]) -> Result<Vec<F::Ieee>, BufferError> {
+    decode_bytes_checked::<F>(bytes, WordOrder::MsWordFirst)
+}
+
+/// Decode a buffer of words with their 16-bit words in `order`, as
+/// [`decode_be_bytes_checked`] does
+pub fn decode_bytes_checked<F: MilFormat>(
+    bytes: &[u8],
+    order: WordOrder,
+) -> Result<Vec<F::Ieee>, BufferError> {
+    let chunks = bytes.chunks_exact(F::BYTES);
+    if let len @ 1.. = chunks.remainder().len() {
+        let index = bytes.len() / F::BYTES;
+        let error = BufferError::Truncated {
+            index,
+            byte_offset: index * F::BYTES,
+            len,
+        };
+        #[cfg(feature = "tracing")]
+        tracing::warn!(index, byte_offset = index * F::BYTES, %error, "buffer rejected");
+        return Err(error);
+    }
+    let words: Vec<F::Bits> = chunks.map(|word| F::read_ordered(word, order)).collect();
+    decode_checked::<F>(&words)
+}
+
+/// Decode a buffer as [`decode_bytes_checked`] does, on `jobs` threads
+///
+/// The buffer is cut into chunks of whole words, which the threads take in turn, so the values
+/// and the error are the same as from one thread: the first bad word of the buffer, by index.
+/// `jobs` of zero uses as many threads as
+/// [`available_parallelism`](std::thread::available_parallelism). Each time a chunk is decoded,
+/// `progress` is called, from the thread that decoded it, with the number of bytes decoded so
+/// far.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{batch, WordOrder, F32};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// let bytes: Vec<u8> = [0x40, 0x00, 0x00, 0x01].repeat(100_000);
+/// let done = AtomicUsize::new(0);
+/// let progress = |bytes| {
+///     done.fetch_max(bytes, Ordering::Relaxed);
+/// };
+/// let values = batch::decode_bytes_parallel::<F32>(&bytes, WordOrder::MsWordFirst, 4, progress);
+/// assert_eq!(values.unwrap(), vec![1.0; 100_000]);
+/// assert_eq!(done.into_inner(), bytes.len());
+/// ```
+pub fn decode_bytes_parallel<F: MilFormat>(
+    bytes: &[u8],
+    order: WordOrder,
+    jobs: usize,
+    progress: impl Fn(usize) + Sync,
+) -> Result<Vec<F::Ieee>, BufferError>
+where
+    F::Ieee: Send,
+{
+    let chunk_len = PARALLEL_CHUNK_BYTES / F::BYTES * F::BYTES;
+    let chunks: Vec<&[u8]> = bytes.chunks(chunk_len).collect();
+    let jobs = match jobs {
+        0 => std::thread::available_parallelism().map_or(1, usize::from),
+        jobs => jobs,
+    };
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    // Chunks after the first that fails are not needed
+    let first_error = AtomicUsize::new(usize::MAX);
+    let mut decoded: Vec<_> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.min(chunks.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut decoded = Vec::new();
+                    loop {
+                        let chunk = next.fetch_add(1, Ordering::Relaxed);
+                        if chunk >= chunks.len() || chunk > first_error.load(Ordering::Relaxed) {
+                            return decoded;
+                        }
+                        let start = chunk * chunk_len;
+                        let values = decode_bytes_checked::<F>(chunks[chunk], order)
+                            .map_err(|error| error.offset_by(start / F::BYTES, start));
+                        if values.is_err() {
+                            first_error.fetch_min(chunk, Ordering::Relaxed);
+                        }
+                        progress(
+                            done.fetch_add(chunks[chunk].len(), Ordering::Relaxed)
+                                + chunks[chunk].len(),
+                        );
+                        decoded.push((chunk, values));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("decoding thread panicked"))
+            .collect()
+    });
+    decoded.sort_unstable_by_key(|&(chunk, _)| chunk);
+    let mut values = Vec::with_capacity(bytes.len() / F::BYTES);
+    for (_, chunk) in decoded {
+        values.extend(chunk?);
+    }
+    Ok(values)
+}
+
+/// Decode `count` words of one channel of an interleaved buffer, word `i` starting at byte
+/// `offset + i * stride`, with their 16-bit words in `order`
+///
+/// Fails on the first word that is not normalized or that runs past the end of the buffer,
+/// reporting its index within the channel and its byte offset in the buffer. A word whose offset
+/// does not fit a `usize` runs past the end, at byte offset `usize::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{batch, WordOrder, F32};
+///
+/// // Frames of a 16-bit counter followed by a 32-bit float
+/// let frames = [0x00, 0x01, 0x40, 0x00, 0x00, 0x01, 0x00, 0x02, 0x53, 0xBE, 0x77, 0x03];
+/// let values = batch::decode_strided::<F32>(&frames, 2, 6, 2, WordOrder::MsWordFirst);
+/// assert_eq!(values, Ok(vec![1.0, 5.234]));
+/// ```
+pub fn decode_strided<F: MilFormat>(
+    bytes: &[u8],
+    offset: usize,
+    stride: usize,
+    count: usize,
+    order: WordOrder,
+) -> Result<Vec<F::Ieee>, BufferError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("decode_strided", offset, stride, count).entered();
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+    let values = (0..count)
+        .map(|index| {
+            let start = index
+                .checked_mul(stride)
+                .and_then(|start| start.checked_add(offset));
+            let byte_offset = start.unwrap_or(usize::MAX);
+            let word = start
+                .and_then(|start| bytes.get(start..start.checked_add(F::BYTES)?))
+                .ok_or(BufferError::Truncated {
+                    index,
+                    byte_offset,
+                    len: bytes.len().saturating_sub(byte_offset),
+                })?;
+            F::decode_strict(F::read_ordered(word, order)).map_err(|error| BufferError::Decode {
+                index,
+                byte_offset,
+                error,
+            })
+        })
+        .collect();
+    #[cfg(feature = "tracing")]
+    finish(&values, start);
+    values
+}
+
+/// Decode every word, as [`MilFormat::decode`] does, into the start of `out`, which need not be
+/// initialized, and return the decoded values
+///
+/// Elements of `out` beyond the words are left as they were.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{batch, F32};
+/// use std::mem::MaybeUninit;
+///
+/// let mut out = [MaybeUninit::uninit(); 4];
+/// let values = batch::decode_into_uninit::<F32>(&[0x40000001, 0x40000002], &mut out);
+/// assert_eq!(values, [1.0, 2.0]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `out` is shorter than `words`.
+pub fn decode_into_uninit<'a, F: MilFormat>(
+    words: &[F::Bits],
+    out: &'a mut [MaybeUninit<F::Ieee>],
+) -> &'a mut [F::Ieee] {
+    assert!(
+        out.len() >= words.len(),
+        "{} words do not fit in {} values",
+        words.len(),
+        out.len()
+    );
+    let out = &mut out[..words.len()];
+    for (slot, &word) in out.iter_mut().zip(words) {
+        slot.write(F::decode(word));
+    }
+    // SAFETY: every element of `out` was written above, and `MaybeUninit<T>` has the layout of
+    // `T`.
+    unsafe { &mut *(out as *mut [MaybeUninit<F::Ieee>] as *mut [F::Ieee]) }
+}
+
+/// Decode every word, as [`MilFormat::decode`] does, onto the end of `values`, writing into its
+/// spare capacity rather than zeroing it first
+pub fn decode_extend<F: MilFormat>(words: &[F::Bits], values: &mut Vec<F::Ieee>) {
+    values.reserve(words.len());
+    let len = values.len();
+    decode_into_uninit::<F>(words, values.spare_capacity_mut());
+    // SAFETY: the capacity is at least `len + words.len()`, and the `words.len()` elements after
+    // `len` were initialized above.
+    unsafe { values.set_len(len + words.len()) };
+}
+
+/// The format of one value of a mixed batch, see [`try_encode_mixed`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum FormatTag {
+    /// A 16-bit MIL-1750A float, one word
+    F16,
+    /// A 32-bit MIL-1750A float, two words
+    F32,
+    /// A 48-bit MIL-1750A float, three words
+    F48,
+    /// A 16-bit fixed-point value with binary scale `bscale`, one word, see [`fixed`]
+    Fixed {
+        /// Binary scale of the value
+        bscale: i32,
+    },
+}
+
+impl FormatTag {
+    /// Number of 16-bit words a value of the format takes
+    pub const fn words(self) -> usize {
+        match self {
+            FormatTag::F16 | FormatTag::Fixed { .. } => 1,
+            FormatTag::F32 => 2,
+            FormatTag::F48 => 3,
+        }
+    }
+
+    /// Encode `value`, rounding floats to the nearest normalized word
+    pub(crate) fn encode(self, value: f64) -> Result<u64, Mil1750Error> {
+        let rounded = |bits: Option<u64>, target| {
+            bits.ok_or(if value.is_finite() {
+                Mil1750Error::OutOfRange { value, target }
+            } else {
+                Mil1750Error::NotFinite { value, target }
+            })
+        };
+        match self {
+            FormatTag::F16 => rounded(
+                arith::try_round16(value).map(u64::from),
+                TargetFormat::Float16,
+            ),
+            FormatTag::F32 => rounded(
+                arith::try_round32(value).map(u64::from),
+                TargetFormat::Float32,
+            ),
+            FormatTag::F48 => rounded(arith::try_round48(value), TargetFormat::Float48),
+            FormatTag::Fixed { bscale } => fixed::encode16(value, bscale).map(u64::from),
+        }
+    }
+
+    /// Append the 16-bit words of `bits` to `words` in `order`
+    fn extend(self, bits: u64, order: WordOrder, words: &mut Vec<u16>) {
+        let bits = match (self, order) {
+            (FormatTag::F32, WordOrder::LsWordFirst) => F32::swap_words(bits as u32).into(),
+            (FormatTag::F48, WordOrder::LsWordFirst) => F48::swap_words(bits),
+            _ => bits,
+        };
+        words.extend(
+            (0..self.words())
+                .rev()
+                .map(|word| (bits >> (16 * word)) as u16),
+        );
+    }
+}
+
+/// Encode each value in the format of its tag, in one pass, into a stream of 16-bit words with
+/// the words of each value in `order`
+///
+/// A value that fails takes zero words of its format's width, so every value stays at its
+/// offset in the stream, and is reported by index in [`Batch::errors`].
+///
+/// # Panics
+///
+/// Panics if `values` and `tags` are not the same length.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::batch::{self, FormatTag};
+/// use mil1750a_converter::{Mil1750Error, WordOrder};
+///
+/// let tags = [FormatTag::Fixed { bscale: 15 }, FormatTag::F32, FormatTag::F16];
+/// let batch = batch::try_encode_mixed(&[3.0, 5.234, f64::NAN], &tags, WordOrder::MsWordFirst);
+/// assert_eq!(batch.converted, [0x0003, 0x53BE, 0x7703, 0x0000]);
+/// assert!(matches!(batch.errors[..], [(2, Mil1750Error::NotFinite { .. })]));
+/// ```
+pub fn try_encode_mixed(values: &[f64], tags: &[FormatTag], order: WordOrder) -> Batch<u16> {
+    assert_eq!(values.len(), tags.len(), "one tag per value");
+    let mut formats = tags.iter();
+    let encoded = collect(
+        values,
+        |value| formats.next().expect("one tag per value").encode(value),
+        Some(0),
+    );
+    let mut words = Vec::with_capacity(tags.iter().map(|tag| tag.words()).sum());
+    for (&bits, tag) in encoded.converted.iter().zip(tags) {
+        tag.extend(bits, order, &mut words);
+    }
+    Batch {
+        converted: words,
+        errors: encoded.errors,
+    }
+}
+
+/// A decoded word with its format, for reports that show the original bits next to the value
+///
+/// With the `serde` feature a record serializes to an object of the `format`, the `raw` word in
+/// hex, zero-padded to the width of the format, the `value` and whether the word is `valid`, one
+/// line of a JSON Lines stream.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// use mil1750a_converter::batch::{FormatTag, Record};
+/// use mil1750a_converter::{MilFormat, F32};
+///
+/// let record = Record::new(FormatTag::F32, F32::decode_traced(0x40000001));
+/// assert_eq!(
+///     serde_json::to_string(&record).unwrap(),
+///     r#"{"format":"f32","raw":"0x40000001","value":1.0,"valid":true}"#
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Record<B, T> {
+    /// The format of the word
+    pub format: FormatTag,
+    /// The word and its value
+    pub decoded: Decoded<B, T>,
+}
+
+impl<B, T> Record<B, T> {
+    /// Pair a decoded word with its format
+    pub fn new(format: FormatTag, decoded: Decoded<B, T>) -> Self {
+        Record { format, decoded }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<B: Copy + Into<u64>, T: serde::Serialize> serde::Serialize for Record<B, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let raw: u64 = self.decoded.raw.into();
+        let mut record = serializer.serialize_struct("Record", 4)?;
+        record.serialize_field("format", &self.format)?;
+        record.serialize_field(
+            "raw",
+            &format!("0x{raw:0width$X}", width = 4 * self.format.words()),
+        )?;
+        record.serialize_field("value", &self.decoded.value)?;
+        record.serialize_field("valid", &self.decoded.valid)?;
+        record.end()
+    }
+}
+
+/// Trace the outcome of a checked decode that started at `start`
+#[cfg(feature = "tracing")]
+fn finish<T>(values: &Result<Vec<T>, BufferError>, start: std::time::Instant) {
+    match values {
+        Ok(values) => tracing::debug!(
+            converted = values.len(),
+            elapsed = ?start.elapsed(),
+            "buffer decoded"
+        ),
+        Err(error) => tracing::warn!(
+            index = error.index(),
+            byte_offset = error.byte_offset(),
+            %error,
+            "buffer rejected"
+        ),
+    }
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(len = items.len()))
+)]
+fn collect<A: Copy, T: Copy, E: Display>(
+    items: &[A],
+    mut convert: impl FnMut(A) -> Result<T, E>,
+    sentinel: Option<T>,
+) -> Batch<T, E> {
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+    let mut batch = Batch {
+        converted: Vec::with_capacity(items.len()),
+        errors: Vec::new(),
+    };
+    for (index, &item) in items.iter().enumerate() {
+        match convert(item) {
+            Ok(converted) => batch.converted.push(converted),
+            Err(error) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(index, %error, "element not converted");
+                batch.errors.push((index, error));
+                batch.converted.extend(sentinel);
+            }
+        }
+    }
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        converted = batch.converted.len(),
+        errors = batch.errors.len(),
+        elapsed = ?start.elapsed(),
+        "batch converted"
+    );
+    batch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "half")]
+    use crate::F16;
+    #[cfg(feature = "half")]
+    use half::f16;
+
+    #[test]
+    fn test_encode() {
+        let batch = try_encode_all::<F48>(&[1.0, f64::INFINITY, 1e300, -1.0]);
+        assert_eq!(batch.converted, [0x400000010000, 0x800000000000]);
+        assert_eq!(
+            batch.errors,
+            [
+                (
+                    1,
+                    Mil1750Error::NotFinite {
+                        value: f64::INFINITY,
+                        target: TargetFormat::Float48
+                    }
+                ),
+                (
+                    2,
+                    Mil1750Error::OutOfRange {
+                        value: 1e300,
+                        target: TargetFormat::Float48
+                    }
+                )
+            ]
+        );
+        assert!(!batch.is_ok());
+        #[cfg(feature = "half")]
+        assert_eq!(
+            try_encode_all::<F16>(&[f16::ONE, f16::NEG_ONE]).converted,
+            [0x4001, 0x8000]
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let words = [0x40000001, 0x20000001, 0x00000001];
+        let batch = decode_strict_all::<F32>(&words);
+        assert_eq!(batch.converted, [1.0]);
+        assert_eq!(
+            batch.errors,
+            [
+                (
+                    1,
+                    DecodeError::Unnormalized {
+                        word: 0x20000001,
+                        bits: 32
+                    }
+                ),
+                (
+                    2,
+                    DecodeError::NonZeroExponent {
+                        word: 0x00000001,
+                        bits: 32
+                    }
+                )
+            ]
+        );
+        let batch = decode_strict_all_or::<F32>(&words, f32::NAN);
+        assert_eq!(batch.converted.len(), 3);
+        assert!(batch.converted[2].is_nan());
+    }
+
+    #[test]
+    fn test_reported() {
+        let (batch, report) = try_encode_all_reported::<F48>(&[1.0, f64::NAN, -2.5, 1e300]);
+        assert_eq!(batch.converted.len(), 2);
+        assert_eq!(report.processed, 4);
+        assert_eq!(
+            report.failures,
+            BTreeMap::from([(FailureKind::NotFinite, 1), (FailureKind::OutOfRange, 1)])
+        );
+        assert_eq!((report.min, report.max), (Some(-2.5), Some(1.0)));
+        assert!(!report.is_clean());
+
+        let (_, report) = decode_strict_all_reported::<F32>(&[]);
+        assert_eq!(
+            report,
+            ConversionReport {
+                duration: report.duration,
+                ..ConversionReport::new()
+            }
+        );
+        assert!(report.is_clean());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_report_json() {
+        let mut report = ConversionReport::new();
+        report.record(0.5);
+        report.record_failure(DecodeError::NonZeroExponent { word: 1, bits: 16 });
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["failures"]["non_zero_exponent"], 1);
+        assert_eq!(json["min"], 0.5);
+        assert_eq!(
+            serde_json::from_value::<ConversionReport>(json).unwrap(),
+            report
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_record_json() {
+        let record = Record::new(FormatTag::F48, F48::decode_traced(0x200000010000));
+        assert_eq!(
+            serde_json::to_value(record).unwrap(),
+            serde_json::json!({
+                "format": "f48",
+                "raw": "0x200000010000",
+                "value": 0.5,
+                "valid": false
+            })
+        );
+        let record = Record::new(
+            FormatTag::Fixed { bscale: 15 },
+            Decoded {
+                raw: 0x0003u16,
+                value: 3.0,
+                valid: true,
+            },
+        );
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"format":{"fixed":{"bscale":15}},"raw":"0x0003","value":3.0,"valid":true}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&crate::single::decode_lenient(0x20000002)).unwrap(),
+            r#"{"value":1.0,"normalized":false}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&crate::single::decode_traced(0x20000002)).unwrap(),
+            r#"{"raw":536870914,"value":1.0,"valid":false}"#
+        );
+    }
+
+    #[test]
+    fn test_decode_uninit() {
+        let mut out = [MaybeUninit::new(-1.0); 3];
+        let values = decode_into_uninit::<F48>(&[0x69A3B50754AB, 0x800000FF0000], &mut out);
+        assert_eq!(values, [105.63948563742451, -0.5]);
+        assert_eq!(unsafe { out[2].assume_init() }, -1.0);
+        assert!(decode_into_uninit::<F48>(&[], &mut []).is_empty());
+
+        let mut values = vec![2.0];
+        decode_extend::<F32>(&[0x40000001, 0x400000FF], &mut values);
+        decode_extend::<F32>(&[], &mut values);
+        assert_eq!(values, [2.0, 1.0, 0.25]);
+    }
+
+    #[test]
+    #[should_panic(expected = "2 words do not fit in 1 values")]
+    fn test_decode_uninit_short() {
+        decode_into_uninit::<F32>(&[0, 0], &mut [MaybeUninit::uninit()]);
+    }
+
+    #[test]
+    fn test_decode_strided() {
+        let frames = [0xAA, 0x40, 0x00, 0x00, 0x00, 0x00, 0xBB, 0x40, 0x00];
+        #[cfg(feature = "half")]
+        assert_eq!(
+            decode_strided::<F16>(&frames, 1, 3, 3, WordOrder::MsWordFirst),
+            Ok(vec![f16::from_f32(0.5), f16::ZERO, f16::from_f32(0.5)])
+        );
+        #[cfg(feature = "half")]
+        assert_eq!(
+            decode_strided::<F16>(&frames, 2, 3, 3, WordOrder::MsWordFirst),
+            Err(BufferError::Decode {
+                index: 1,
+                byte_offset: 5,
+                error: DecodeError::Unnormalized {
+                    word: 0x00BB,
+                    bits: 16
+                }
+            })
+        );
+        assert_eq!(
+            decode_strided::<F48>(&frames, 1, 6, 2, WordOrder::MsWordFirst),
+            Err(BufferError::Truncated {
+                index: 1,
+                byte_offset: 7,
+                len: 2
+            })
+        );
+        assert_eq!(
+            decode_strided::<F48>(&frames, 0, 0, 0, WordOrder::MsWordFirst),
+            Ok(vec![])
+        );
+        // Offsets past the end of the address space are truncated words, not overflows
+        assert_eq!(
+            decode_strided::<F32>(&frames, 1, usize::MAX, 2, WordOrder::MsWordFirst),
+            Err(BufferError::Truncated {
+                index: 1,
+                byte_offset: usize::MAX,
+                len: 0
+            })
+        );
+        assert_eq!(
+            decode_strided::<F32>(&frames, usize::MAX - 1, 0, 1, WordOrder::MsWordFirst),
+            Err(BufferError::Truncated {
+                index: 0,
+                byte_offset: usize::MAX - 1,
+                len: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_parallel() {
+        let mut bytes = [0x53, 0xBE, 0x77, 0x03, 0x00, 0x00].repeat(30_000);
+        let values = decode_bytes_parallel::<F48>(&bytes, WordOrder::MsWordFirst, 3, |_| ());
+        assert_eq!(
+            values,
+            decode_bytes_checked::<F48>(&bytes, WordOrder::MsWordFirst)
+        );
+        assert_eq!(values.unwrap().len(), 30_000);
+
+        // The first bad word wins, whichever thread finds it
+        bytes[6 * 20_000] = 0x20;
+        bytes[6 * 25_000] = 0x20;
+        let error = decode_bytes_parallel::<F48>(&bytes, WordOrder::MsWordFirst, 0, |_| ());
+        assert_eq!(error.unwrap_err().index(), 20_000);
+        let error = decode_bytes_parallel::<F32>(
+            &bytes[..6 * 10_000 + 1],
+            WordOrder::MsWordFirst,
+            2,
+            |_| (),
+        );
+        assert_eq!(
+            error,
+            Err(BufferError::Truncated {
+                index: 15_000,
+                byte_offset: 60_000,
+                len: 1
+            })
+        );
+        assert_eq!(
+            decode_bytes_parallel::<F32>(&[], WordOrder::MsWordFirst, 2, |_| panic!()),
+            Ok(vec![])
+        );
+    }
+
+    #[test]
+    fn test_encode_mixed() {
+        let tags = [
+            FormatTag::F48,
+            FormatTag::F16,
+            FormatTag::Fixed { bscale: 0 },
+            FormatTag::F32,
+        ];
+        let values = [105.63948563742451, 1.0, 0.5, -1.0];
+        let batch = try_encode_mixed(&values, &tags, WordOrder::MsWordFirst);
+        assert!(batch.is_ok());
+        assert_eq!(
+            batch.converted,
+            [0x69A3, 0xB507, 0x54AB, 0x4001, 0x4000, 0x8000, 0x0000]
+        );
+        let batch = try_encode_mixed(&values, &tags, WordOrder::LsWordFirst);
+        assert_eq!(
+            batch.converted,
+            [0x54AB, 0xB507, 0x69A3, 0x4001, 0x4000, 0x0000, 0x8000]
+        );
+
+        let batch = try_encode_mixed(&[1e300, 1.0, 2.0], &tags[1..], WordOrder::MsWordFirst);
+        assert_eq!(batch.converted, [0x0000, 0x0000, 0x4000, 0x0002]);
+        assert_eq!(
+            batch.errors,
+            [
+                (
+                    0,
+                    Mil1750Error::OutOfRange {
+                        value: 1e300,
+                        target: TargetFormat::Float16
+                    }
+                ),
+                (
+                    1,
+                    Mil1750Error::OutOfRange {
+                        value: 1.0,
+                        target: TargetFormat::Fixed16
+                    }
+                )
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "one tag per value")]
+    fn test_encode_mixed_tags() {
+        try_encode_mixed(&[1.0], &[], WordOrder::MsWordFirst);
+    }
+
+    #[test]
+    fn test_decode_checked() {
+        let bytes = [
+            0x40, 0x00, 0x00, 0x01, 0x00, 0x00, 0x20, 0x00, 0x00, 0x02, 0x00, 0x00,
+        ];
+        assert_eq!(
+            decode_be_bytes_checked::<F48>(&bytes),
+            Err(BufferError::Decode {
+                index: 1,
+                byte_offset: 6,
+                error: DecodeError::Unnormalized {
+                    word: 0x200000020000,
+                    bits: 48
+                }
+            })
+        );
+        assert_eq!(
+            decode_be_bytes_checked::<F48>(&bytes[..8]),
+            Err(BufferError::Truncated {
+                index: 1,
+                byte_offset: 6,
+                len: 2
+            })
+        );
+        assert_eq!(decode_be_bytes_checked::<F48>(&bytes[..6]), Ok(vec![1.0]));
+        assert_eq!(
+            decode_bytes_checked::<F48>(&[0, 0, 0, 1, 0x40, 0], WordOrder::LsWordFirst),
+            Ok(vec![1.0])
+        );
+        assert_eq!(
+            decode_checked::<F32>(&[0x40000001, 0x00000001])
+                .unwrap_err()
+                .to_string(),
+            "word 1 at byte offset 4: 32-bit MIL-1750A word 0x1 is a zero with a non-zero exponent"
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! # CCSDS space packets
+//! Extraction of MIL-1750A parameters from the data field of CCSDS space packets.
+//!
+//! A [`PacketLayout`] places each parameter at a bit offset into the packet data field, the
+//! bytes after the primary header (and including any secondary header), so a parameter may
+//! start anywhere inside a byte. The bits are read big-endian, most significant word first,
+//! and decoded as a [`Schema`] field would be.
+//!
+//! ```
+//! use mil1750a_converter::ccsds::{Packet, PacketLayout};
+//! use mil1750a_converter::schema::{FieldFormat, Value};
+//!
+//! // APID 0x123, one flag bit, then 1.0 as a 32-bit float
+//! let bytes = [0x01, 0x23, 0xC0, 0x00, 0x00, 0x04, 0xA0, 0x00, 0x00, 0x00, 0x80];
+//! let packet = Packet::parse(&bytes).unwrap();
+//! assert_eq!(packet.apid, 0x123);
+//!
+//! let layout = PacketLayout::new().parameter("gain", 1, FieldFormat::F32);
+//! let frame = packet.decode(&layout).unwrap();
+//! assert_eq!(frame.get("gain"), Some(Value::F32(1.0)));
+//! ```
+
+use crate::schema::{FieldFormat, Frame, Schema, SchemaError};
+use crate::WordOrder;
+
+/// Bytes in the packet primary header
+const HEADER_LEN: usize = 6;
+
+/// A CCSDS space packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packet<'a> {
+    /// Packet version number, 3 bits
+    pub version: u8,
+    /// Whether the packet is a telecommand rather than telemetry
+    pub is_telecommand: bool,
+    /// Whether the data field starts with a secondary header
+    pub has_secondary_header: bool,
+    /// Application process identifier, 11 bits
+    pub apid: u16,
+    /// Sequence flags, 2 bits
+    pub sequence_flags: u8,
+    /// Packet sequence count, 14 bits
+    pub sequence_count: u16,
+    /// The packet data field
+    pub data: &'a [u8],
+}
+
+impl<'a> Packet<'a> {
+    /// Parse the packet at the start of `bytes`; bytes after its data field are ignored
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, CcsdsError> {
+        let Some(header) = bytes.get(..HEADER_LEN) else {
+            return Err(CcsdsError::Truncated {
+                needed: HEADER_LEN,
+                len: bytes.len(),
+            });
+        };
+        let read = |at: usize| u16::from_be_bytes([header[at], header[at + 1]]);
+        let (id, sequence) = (read(0), read(2));
+        let needed = HEADER_LEN + usize::from(read(4)) + 1;
+        let Some(data) = bytes.get(HEADER_LEN..needed) else {
+            return Err(CcsdsError::Truncated {
+                needed,
+                len: bytes.len(),
+            });
+        };
+
+        Ok(Self {
+            version: (id >> 13) as u8,
+            is_telecommand: id & 0x1000 != 0,
+            has_secondary_header: id & 0x0800 != 0,
+            apid: id & 0x07FF,
+            sequence_flags: (sequence >> 14) as u8,
+            sequence_count: sequence & 0x3FFF,
+            data,
+        })
+    }
+
+    /// Decode the parameters of `layout` from the data field
+    pub fn decode(&self, layout: &PacketLayout) -> Result<Frame, CcsdsError> {
+        layout.decode(self.data)
+    }
+}
+
+/// The parameters of a packet data field, by bit offset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PacketLayout {
+    schema: Schema,
+    bit_offsets: Vec<usize>,
+}
+
+impl PacketLayout {
+    /// A layout with no parameters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a parameter starting `bit_offset` bits into the data field
+    pub fn parameter(
+        mut self,
+        name: impl Into<String>,
+        bit_offset: usize,
+        format: FieldFormat,
+    ) -> Self {
+        // Parameters are gathered one after the other into a frame for the schema
+        let offset = self.schema.len_words();
+        self.schema = self.schema.field(name, offset, format);
+        self.bit_offsets.push(bit_offset);
+        self
+    }
+
+    /// Reject unnormalized floating point parameters, as [`Schema::strict`] does
+    pub fn strict(mut self) -> Self {
+        self.schema = self.schema.strict();
+        self
+    }
+
+    /// Take multi-word parameters to be in `order`, as [`Schema::word_order`] does
+    pub fn word_order(mut self, order: WordOrder) -> Self {
+        self.schema = self.schema.word_order(order);
+        self
+    }
+
+    /// Decode every parameter from a packet data field
+    pub fn decode(&self, data: &[u8]) -> Result<Frame, CcsdsError> {
+        let mut words = Vec::with_capacity(self.schema.len_words());
+        for (field, &bit_offset) in self.schema.fields().iter().zip(&self.bit_offsets) {
+            let len = field.format.words();
+            let end = bit_offset + 16 * len;
+            if end > data.len() * 8 {
+                return Err(CcsdsError::Truncated {
+                    needed: end.div_ceil(8),
+                    len: data.len(),
+                });
+            }
+            words.extend((0..len).map(|word| read_word(data, bit_offset + 16 * word)));
+        }
+        Ok(self.schema.decode(&words)?)
+    }
+}
+
+/// Read the 16 bits starting `bit_offset` bits into `data`, which must hold them
+fn read_word(data: &[u8], bit_offset: usize) -> u16 {
+    let byte = bit_offset / 8;
+    let window = data[byte..data.len().min(byte + 3)]
+        .iter()
+        .enumerate()
+        .fold(0u32, |acc, (index, &byte)| {
+            acc | u32::from(byte) << (16 - 8 * index)
+        });
+    (window >> (8 - bit_offset % 8)) as u16
+}
+
+/// Reasons parameters cannot be read from a space packet
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum CcsdsError {
+    /// The packet, or its data field, is shorter than a header or parameter needs
+    #[error("space packet needs {needed} bytes but has {len}")]
+    Truncated {
+        /// Number of bytes needed
+        needed: usize,
+        /// Number of bytes available
+        len: usize,
+    },
+    /// A parameter could not be decoded
+    #[error("{0}")]
+    Schema(#[from] SchemaError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Value;
+
+    #[test]
+    fn test_parse() {
+        let bytes = [0x1F, 0xFF, 0x7F, 0xFF, 0x00, 0x01, 0xAB, 0xCD, 0xEF];
+        let packet = Packet::parse(&bytes).unwrap();
+        assert_eq!(packet.version, 0);
+        assert!(packet.is_telecommand);
+        assert!(packet.has_secondary_header);
+        assert_eq!(packet.apid, 0x7FF);
+        assert_eq!(packet.sequence_flags, 1);
+        assert_eq!(packet.sequence_count, 0x3FFF);
+        assert_eq!(packet.data, [0xAB, 0xCD]);
+
+        assert_eq!(
+            Packet::parse(&bytes[..7]),
+            Err(CcsdsError::Truncated { needed: 8, len: 7 })
+        );
+        assert_eq!(
+            Packet::parse(&bytes[..4]),
+            Err(CcsdsError::Truncated { needed: 6, len: 4 })
+        );
+    }
+
+    #[test]
+    fn test_straddling_parameters() {
+        // Three set bits, 0x69A3B50754AB, then 0xFFFE, padded to nine bytes
+        let bits = (0b111u128 << 64 | 0x69A3B50754ABu128 << 16 | 0xFFFE) << 5;
+        let data = &bits.to_be_bytes()[7..];
+
+        let layout = PacketLayout::new()
+            .parameter("extended", 3, FieldFormat::F48)
+            .parameter("count", 51, FieldFormat::Int16);
+        let frame = layout.decode(data).unwrap();
+        assert_eq!(frame.get("extended"), Some(Value::F48(105.63948563742451)));
+        assert_eq!(frame.get("count"), Some(Value::Int16(-2)));
+
+        assert_eq!(
+            layout.decode(&data[..8]),
+            Err(CcsdsError::Truncated { needed: 9, len: 8 })
+        );
+        assert_eq!(
+            PacketLayout::new()
+                .parameter("late", 60, FieldFormat::F32)
+                .decode(data),
+            Err(CcsdsError::Truncated { needed: 12, len: 9 })
+        );
+    }
+
+    #[test]
+    fn test_word_order() {
+        let layout = PacketLayout::new()
+            .parameter("single", 8, FieldFormat::F32)
+            .word_order(WordOrder::LsWordFirst);
+        let frame = layout.decode(&[0xFF, 0x00, 0x01, 0x40, 0x00]).unwrap();
+        assert_eq!(frame.get("single"), Some(Value::F32(1.0)));
+    }
+
+    #[test]
+    fn test_strict() {
+        let layout = PacketLayout::new()
+            .parameter("single", 4, FieldFormat::F32)
+            .strict();
+        assert!(matches!(
+            layout.decode(&[0x01, 0x00, 0x00, 0x00, 0x00]),
+            Err(CcsdsError::Schema(SchemaError::Field { .. }))
+        ));
+    }
+}
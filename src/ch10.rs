@@ -0,0 +1,231 @@
+//! # IRIG 106 Chapter 10
+//! Extraction of MIL-1750A values from the bodies of Chapter 10 MIL-STD-1553 (format 1)
+//! packets.
+//!
+//! Chapter 10 stores every 16-bit word little-endian, while a multi-word 1750A value keeps the
+//! 1750A order, most significant word first, across consecutive 1553 data words. [`Message`]
+//! reads the data words in packet order so a [`Schema`] can decode them with offsets straight
+//! from the ICD; for terminals that send the least significant word first, give the schema a
+//! [`WordOrder::LsWordFirst`](crate::WordOrder::LsWordFirst) word order.
+//!
+//! ```
+//! use mil1750a_converter::ch10;
+//! use mil1750a_converter::schema::{FieldFormat, Schema, Value};
+//!
+//! // One BC-to-RT message: command word (RT 1, subaddress 2, 2 words), two data words, status
+//! let mut body = vec![1, 0, 0, 0];
+//! body.extend([0; 8]); // time stamp
+//! body.extend([0, 0, 0, 0, 8, 0]); // block status, gap times, length
+//! body.extend([0x42, 0x08, 0x00, 0x40, 0x01, 0x00, 0x00, 0x08]);
+//!
+//! let schema = Schema::new().field("gain", 0, FieldFormat::F32);
+//! let message = ch10::messages(&body).unwrap().next().unwrap().unwrap();
+//! assert_eq!(message.data_words(), [0x4000, 0x0001]);
+//! assert_eq!(message.decode(&schema).unwrap().get("gain"), Some(Value::F32(1.0)));
+//! ```
+
+use crate::schema::{Frame, Schema, SchemaError};
+
+/// Bytes in the channel specific data word at the start of a packet body
+const CSDW_LEN: usize = 4;
+/// Bytes in the intra-packet header before each message
+const HEADER_LEN: usize = 14;
+/// Block status bit set for RT-to-RT transfers
+const RT_TO_RT: u16 = 1 << 11;
+/// Command word bit set when the RT transmits
+const TRANSMIT: u16 = 1 << 10;
+
+/// A 1553 message from a Chapter 10 packet body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Message<'a> {
+    /// Intra-packet time stamp, as stored in the packet
+    pub time_stamp: [u8; 8],
+    /// Block status word
+    pub block_status: u16,
+    /// Gap times word
+    pub gap_times: u16,
+    data: &'a [u8],
+}
+
+impl<'a> Message<'a> {
+    /// Every word of the message (command, status and data words) in bus order
+    pub fn words(&self) -> impl Iterator<Item = u16> + 'a {
+        self.data
+            .chunks_exact(2)
+            .map(|word| u16::from_le_bytes([word[0], word[1]]))
+    }
+
+    /// The first command word, the receive command of an RT-to-RT transfer
+    pub fn command_word(&self) -> Option<u16> {
+        self.words().next()
+    }
+
+    /// Whether the message is an RT-to-RT transfer
+    pub fn is_rt_to_rt(&self) -> bool {
+        self.block_status & RT_TO_RT != 0
+    }
+
+    /// The data words, without command and status words
+    ///
+    /// Messages cut short by bus errors yield the data words that were recorded.
+    pub fn data_words(&self) -> Vec<u16> {
+        let words: Vec<u16> = self.words().collect();
+        let Some(&command) = words.first() else {
+            return Vec::new();
+        };
+        let start = if self.is_rt_to_rt() {
+            // Receive command, transmit command, transmit status
+            3
+        } else if command & TRANSMIT != 0 {
+            // Command, status
+            2
+        } else {
+            1
+        };
+
+        let subaddress = (command >> 5) & 0x1F;
+        let count = if subaddress == 0 || subaddress == 0x1F {
+            // Mode codes 16 to 31 carry one data word
+            usize::from(command & 0x10 != 0)
+        } else {
+            match command & 0x1F {
+                0 => 32,
+                count => count as usize,
+            }
+        };
+        words.into_iter().skip(start).take(count).collect()
+    }
+
+    /// Decode the data words with `schema`, offsets counting from the first data word
+    pub fn decode(&self, schema: &Schema) -> Result<Frame, SchemaError> {
+        schema.decode(&self.data_words())
+    }
+}
+
+/// The messages of a Chapter 10 1553 packet body, see [`messages`]
+#[derive(Debug, Clone)]
+pub struct Messages<'a> {
+    body: &'a [u8],
+    offset: usize,
+    remaining: u32,
+}
+
+impl<'a> Iterator for Messages<'a> {
+    type Item = Result<Message<'a>, Ch10Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let offset = self.offset;
+        let Some(header) = self.body.get(offset..offset + HEADER_LEN) else {
+            self.remaining = 0;
+            return Some(Err(Ch10Error::Truncated { offset }));
+        };
+        let read = |at: usize| u16::from_le_bytes([header[at], header[at + 1]]);
+        let length = read(12) as usize;
+        let start = offset + HEADER_LEN;
+        let Some(data) = self.body.get(start..start + length) else {
+            self.remaining = 0;
+            return Some(Err(Ch10Error::Truncated { offset }));
+        };
+
+        self.offset = start + length;
+        Some(Ok(Message {
+            time_stamp: header[..8].try_into().unwrap(),
+            block_status: read(8),
+            gap_times: read(10),
+            data,
+        }))
+    }
+}
+
+/// Iterate over the messages of a Chapter 10 1553 format 1 packet body, the bytes following
+/// the packet header
+pub fn messages(body: &[u8]) -> Result<Messages<'_>, Ch10Error> {
+    let Some(csdw) = body.get(..CSDW_LEN) else {
+        return Err(Ch10Error::Truncated { offset: 0 });
+    };
+    let csdw = u32::from_le_bytes(csdw.try_into().unwrap());
+    Ok(Messages {
+        body,
+        offset: CSDW_LEN,
+        remaining: csdw & 0xFF_FFFF,
+    })
+}
+
+/// Reasons a Chapter 10 packet body cannot be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum Ch10Error {
+    /// The body ends inside the channel specific data word or a message
+    #[error("Chapter 10 packet body is truncated at byte {offset}")]
+    Truncated {
+        /// Byte offset of the incomplete structure in the body
+        offset: usize,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{FieldFormat, Value};
+
+    fn message(block_status: u16, words: &[u16]) -> Vec<u8> {
+        let mut bytes = vec![0; 8];
+        bytes.extend(block_status.to_le_bytes());
+        bytes.extend([0, 0]);
+        bytes.extend((words.len() as u16 * 2).to_le_bytes());
+        bytes.extend(words.iter().flat_map(|word| word.to_le_bytes()));
+        bytes
+    }
+
+    #[test]
+    fn test_message_layouts() {
+        let mut body = 3u32.to_le_bytes().to_vec();
+        // RT 1 transmits three words from subaddress 2
+        body.extend(message(0, &[0x0C43, 0x0800, 0x69A3, 0xB507, 0x54AB]));
+        // RT 2 receives from RT 1
+        body.extend(message(
+            RT_TO_RT,
+            &[0x1042, 0x0C42, 0x0800, 0x4000, 0x0001, 0x1000],
+        ));
+        // Mode code 17, synchronize with data word
+        body.extend(message(0, &[0x0811, 0x1234, 0x0800]));
+
+        let messages: Vec<_> = messages(&body).unwrap().map(Result::unwrap).collect();
+        assert_eq!(messages.len(), 3);
+        let schema = Schema::new().field("value", 0, FieldFormat::F48);
+        assert_eq!(
+            messages[0].decode(&schema).unwrap().get("value"),
+            Some(Value::F48(105.63948563742451))
+        );
+        assert!(messages[1].is_rt_to_rt());
+        assert_eq!(messages[1].data_words(), [0x4000, 0x0001]);
+        assert_eq!(messages[2].data_words(), [0x1234]);
+        assert_eq!(messages[2].command_word(), Some(0x0811));
+    }
+
+    #[test]
+    fn test_truncated() {
+        assert_eq!(
+            messages(&[1, 0]).unwrap_err(),
+            Ch10Error::Truncated { offset: 0 }
+        );
+        let mut body = 2u32.to_le_bytes().to_vec();
+        body.extend(message(0, &[0x0822, 0x1234, 0x5678, 0x0800]));
+        body.extend(&message(0, &[0x0821, 0x1234, 0x0800])[..16]);
+        let mut messages = messages(&body).unwrap();
+        assert_eq!(
+            messages.next().unwrap().unwrap().data_words(),
+            [0x1234, 0x5678]
+        );
+        assert_eq!(
+            messages.next(),
+            Some(Err(Ch10Error::Truncated { offset: 26 }))
+        );
+        assert_eq!(messages.next(), None);
+    }
+}
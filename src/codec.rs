@@ -0,0 +1,286 @@
+//! # Tokio codecs
+//! [`Decoder`]/[`Encoder`] implementations for streams of packed, big-endian MIL-1750A words.
+//!
+//! Wrap a socket in a [`tokio_util::codec::Framed`] (or a `UdpFramed`) with one of these codecs
+//! to get a stream of decoded values, one per word, each converted as [`MilFormat::decode`] and
+//! [`MilFormat::encode`] convert it. [`ProfileCodec`] instead reads and writes words in the
+//! layout of a [`TargetProfile`] and encodes them as it says.
+
+use crate::profile::TargetProfile;
+#[cfg(feature = "half")]
+use crate::F16;
+use crate::{MilFormat, F32, F48};
+use bytes::{Buf, BufMut, BytesMut};
+#[cfg(feature = "half")]
+use half::f16;
+use std::io;
+use std::marker::PhantomData;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Codec for 16-bit MIL-1750A words, decoded to [`f16`](struct@f16)
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use half::f16;
+/// use tokio_util::codec::Decoder;
+/// use mil1750a_converter::codec::Mil16Codec;
+/// let mut buf = BytesMut::from(&[0x63, 0x44][..]);
+/// assert_eq!(Mil16Codec.decode(&mut buf).unwrap(), Some(f16::from_f32(12.40625)));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg(feature = "half")]
+pub struct Mil16Codec;
+
+/// Codec for 32-bit MIL-1750A words, decoded to [`f32`]
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use tokio_util::codec::Decoder;
+/// use mil1750a_converter::codec::Mil32Codec;
+/// let mut buf = BytesMut::from(&[0x40, 0x00, 0x00, 0x01][..]);
+/// assert_eq!(Mil32Codec.decode(&mut buf).unwrap(), Some(1.0));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Mil32Codec;
+
+/// Codec for 48-bit MIL-1750A words, decoded to [`f64`]
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use tokio_util::codec::Decoder;
+/// use mil1750a_converter::codec::Mil48Codec;
+/// let mut buf = BytesMut::from(&[0x69, 0xA3, 0xB5, 0x07, 0x54, 0xAB][..]);
+/// assert_eq!(Mil48Codec.decode(&mut buf).unwrap(), Some(105.63948563742451));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Mil48Codec;
+
+/// Codec for words of the format `F` as a [`TargetProfile`] stores and encodes them
+///
+/// Values the profile cannot encode fail with an [`io::ErrorKind::InvalidData`] error wrapping
+/// the [`Mil1750Error`](crate::Mil1750Error).
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use tokio_util::codec::{Decoder, Encoder};
+/// use mil1750a_converter::codec::ProfileCodec;
+/// use mil1750a_converter::profile::TargetProfile;
+/// use mil1750a_converter::{WordOrder, F32};
+///
+/// let profile = TargetProfile {
+///     word_order: WordOrder::LsWordFirst,
+///     ..TargetProfile::STANDARD
+/// };
+/// let mut codec = ProfileCodec::<F32>::new(profile);
+/// let mut buf = BytesMut::new();
+/// codec.encode(1.0, &mut buf).unwrap();
+/// assert_eq!(&buf[..], [0x00, 0x01, 0x40, 0x00]);
+/// assert_eq!(codec.decode(&mut buf).unwrap(), Some(1.0));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProfileCodec<F> {
+    profile: TargetProfile,
+    format: PhantomData<F>,
+}
+
+impl<F: MilFormat> ProfileCodec<F> {
+    /// A codec for the words of `profile`
+    pub fn new(profile: TargetProfile) -> Self {
+        ProfileCodec {
+            profile,
+            format: PhantomData,
+        }
+    }
+
+    /// The profile the codec reads, writes and encodes words with
+    pub fn profile(&self) -> &TargetProfile {
+        &self.profile
+    }
+}
+
+/// Returns `true` when `src` holds at least one full word of `width` bytes,
+/// reserving room for the rest of the word otherwise.
+fn has_word(src: &mut BytesMut, width: usize) -> bool {
+    if src.len() < width {
+        src.reserve(width - src.len());
+        return false;
+    }
+    true
+}
+
+#[cfg(feature = "half")]
+impl Decoder for Mil16Codec {
+    type Item = f16;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<f16>, io::Error> {
+        if !has_word(src, 2) {
+            return Ok(None);
+        }
+        Ok(Some(F16::decode(src.get_u16())))
+    }
+}
+
+#[cfg(feature = "half")]
+impl Encoder<f16> for Mil16Codec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: f16, dst: &mut BytesMut) -> Result<(), io::Error> {
+        dst.put_u16(F16::encode(item));
+        Ok(())
+    }
+}
+
+impl Decoder for Mil32Codec {
+    type Item = f32;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<f32>, io::Error> {
+        if !has_word(src, 4) {
+            return Ok(None);
+        }
+        Ok(Some(F32::decode(src.get_u32())))
+    }
+}
+
+impl Encoder<f32> for Mil32Codec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: f32, dst: &mut BytesMut) -> Result<(), io::Error> {
+        dst.put_u32(F32::encode(item));
+        Ok(())
+    }
+}
+
+impl Decoder for Mil48Codec {
+    type Item = f64;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<f64>, io::Error> {
+        if !has_word(src, 6) {
+            return Ok(None);
+        }
+        Ok(Some(F48::decode(src.get_uint(6))))
+    }
+}
+
+impl Encoder<f64> for Mil48Codec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: f64, dst: &mut BytesMut) -> Result<(), io::Error> {
+        dst.put_uint(F48::encode(item), 6);
+        Ok(())
+    }
+}
+
+impl<F: MilFormat> Decoder for ProfileCodec<F> {
+    type Item = F::Ieee;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<F::Ieee>, io::Error> {
+        if !has_word(src, F::BYTES) {
+            return Ok(None);
+        }
+        let word = self.profile.read::<F>(&src.split_to(F::BYTES));
+        Ok(Some(self.profile.decode::<F>(word)))
+    }
+}
+
+impl<F: MilFormat> Encoder<F::Ieee> for ProfileCodec<F> {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: F::Ieee, dst: &mut BytesMut) -> Result<(), io::Error> {
+        let word = self
+            .profile
+            .encode::<F>(item)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let mut bytes = [0; 6];
+        self.profile.write::<F>(word, &mut bytes);
+        dst.put_slice(&bytes[..F::BYTES]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mil32_codec_partial_frames() {
+        let mut buf = BytesMut::from(&[0x40, 0x00][..]);
+        assert_eq!(Mil32Codec.decode(&mut buf).unwrap(), None);
+        buf.extend_from_slice(&[0x00, 0x01, 0x99, 0x7A, 0xE1, 0x05]);
+        assert_eq!(Mil32Codec.decode(&mut buf).unwrap(), Some(1.0));
+        assert_eq!(
+            Mil32Codec.decode(&mut buf).unwrap(),
+            Some(F32::decode(0x997AE105))
+        );
+        buf.extend_from_slice(&[0x40, 0x00, 0x00, 0xFF]);
+        assert_eq!(Mil32Codec.decode(&mut buf).unwrap(), Some(0.25));
+        assert_eq!(Mil32Codec.decode(&mut buf).unwrap(), None);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_mil32_codec_eof_with_trailing_bytes() {
+        let mut buf = BytesMut::from(&[0x40, 0x00, 0x00][..]);
+        assert!(Mil32Codec.decode_eof(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_codec_encode() {
+        let mut buf = BytesMut::new();
+        Mil32Codec.encode(5.234, &mut buf).unwrap();
+        Mil48Codec.encode(105.639485637361, &mut buf).unwrap();
+        assert_eq!(
+            &buf[..],
+            &[0x53, 0xBE, 0x77, 0x03, 0x69, 0xA3, 0xB5, 0x07, 0x54, 0xAB]
+        );
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_mil16_codec_encode() {
+        let mut buf = BytesMut::new();
+        Mil16Codec.encode(f16::from_f32(12.4), &mut buf).unwrap();
+        assert_eq!(&buf[..], &[0x63, 0x44]);
+    }
+
+    #[test]
+    fn test_profile_codec() {
+        use crate::profile::Overflow;
+        use crate::{ByteOrder, F48};
+
+        let profile = TargetProfile {
+            overflow: Overflow::Error,
+            byte_order: ByteOrder::LittleEndian,
+            ..TargetProfile::STANDARD
+        };
+        let mut codec = ProfileCodec::<F48>::new(profile);
+        let mut buf = BytesMut::from(&[0xA3, 0x69, 0x07][..]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        buf.extend_from_slice(&[0xB5, 0xAB, 0x54]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(105.63948563742451));
+        assert!(buf.is_empty());
+        let error = codec.encode(1e300, &mut buf).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(buf.is_empty());
+        assert_eq!(codec.profile(), &profile);
+    }
+
+    #[test]
+    fn test_mil48_codec_round_trip() {
+        let mut buf = BytesMut::new();
+        Mil48Codec.encode(std::f64::consts::PI, &mut buf).unwrap();
+        let decoded = Mil48Codec.decode(&mut buf).unwrap().unwrap();
+        assert!((decoded - std::f64::consts::PI).abs() < 1e-11);
+    }
+}
@@ -0,0 +1,323 @@
+//! # Acceptance comparisons
+//! Check words produced by another implementation, or read back from target memory, against
+//! the IEEE values they should hold.
+//!
+//! [`compare`] pairs expected value `i` with word `i`, decodes each word as the standard defines
+//! it (see [`single::decode_lenient`](crate::single::decode_lenient)) and records every pair that
+//! differs, with the difference in absolute value and in ULPs of the word. A pair passes when
+//! it is within either bound of the [`Tolerance`], and a [`Comparison`] passes when every pair
+//! does and neither side has elements the other lacks. [`compare_files`] does the same for a
+//! file of IEEE values and a file of words in any [`Layout`].
+//!
+//! ```
+//! use mil1750a_converter::compare::{compare, Tolerance};
+//!
+//! let expected = [1.0, 5.234, -0.1];
+//! let words = [0x40000001, 0x53BE7703, 0x99999AFD];
+//! let comparison = compare(&expected, &words, 32, Tolerance::ulps(0.5));
+//! assert!(comparison.passed());
+//! assert!(comparison.max_ulps() > 0.0);
+//!
+//! let strict = compare(&expected, &words[..2], 32, Tolerance::ulps(0.5));
+//! assert!(!strict.passed());
+//! assert_eq!(strict.unmatched(), 1);
+//! ```
+
+use crate::arith;
+use crate::detect::Layout;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// How far a decoded word may be from its expected value
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Tolerance {
+    /// Largest absolute difference that passes
+    pub absolute: f64,
+    /// Largest difference in ULPs of the word that passes
+    pub ulps: f64,
+}
+
+impl Tolerance {
+    /// Pass only exact matches
+    pub const EXACT: Tolerance = Tolerance {
+        absolute: 0.0,
+        ulps: 0.0,
+    };
+
+    /// Pass differences of at most `ulps` ULPs
+    pub const fn ulps(ulps: f64) -> Self {
+        Tolerance {
+            absolute: 0.0,
+            ulps,
+        }
+    }
+
+    /// Pass differences of at most `absolute`
+    pub const fn absolute(absolute: f64) -> Self {
+        Tolerance {
+            absolute,
+            ulps: 0.0,
+        }
+    }
+
+    /// Whether a difference of `delta`, or `ulps` ULPs, passes
+    fn allows(&self, delta: f64, ulps: f64) -> bool {
+        delta.abs() <= self.absolute || ulps.abs() <= self.ulps
+    }
+}
+
+/// The width of the values of an IEEE file, stored in native byte order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Precision {
+    /// `f32` values, four bytes each
+    Single,
+    /// `f64` values, eight bytes each
+    Double,
+}
+
+/// A pair whose decoded word differs from its expected value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// Index of the pair
+    pub index: usize,
+    /// The expected value
+    pub expected: f64,
+    /// The word, in the low bits
+    pub word: u64,
+    /// The value of the word
+    pub actual: f64,
+    /// `actual - expected`
+    pub delta: f64,
+    /// `delta` in units of the least significant mantissa bit of the word
+    pub ulps: f64,
+    /// Whether the difference is within the tolerance
+    pub passed: bool,
+}
+
+/// The differences between expected values and words, in index order
+///
+/// [`Display`](fmt::Display) prints a summary line and one line per failing pair.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comparison {
+    differences: Vec<Difference>,
+    compared: usize,
+    unmatched: usize,
+}
+
+impl Comparison {
+    /// Every pair that differs, within the tolerance or not
+    pub fn differences(&self) -> &[Difference] {
+        &self.differences
+    }
+
+    /// The pairs that differ by more than the tolerance
+    pub fn failures(&self) -> impl Iterator<Item = &Difference> {
+        self.differences
+            .iter()
+            .filter(|difference| !difference.passed)
+    }
+
+    /// Number of pairs compared
+    pub fn compared(&self) -> usize {
+        self.compared
+    }
+
+    /// Number of values or words beyond the end of the shorter side
+    pub fn unmatched(&self) -> usize {
+        self.unmatched
+    }
+
+    /// Whether every pair is within the tolerance and both sides have the same length
+    pub fn passed(&self) -> bool {
+        self.unmatched == 0 && self.failures().next().is_none()
+    }
+
+    /// Largest absolute difference, zero if every pair matches
+    pub fn max_abs(&self) -> f64 {
+        self.differences
+            .iter()
+            .map(|difference| difference.delta.abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Largest difference in ULPs, zero if every pair matches
+    pub fn max_ulps(&self) -> f64 {
+        self.differences
+            .iter()
+            .map(|difference| difference.ulps.abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} compared, {} differ, ",
+            if self.passed() { "PASS" } else { "FAIL" },
+            self.compared,
+            self.differences.len(),
+        )?;
+        writeln!(
+            f,
+            "{} beyond tolerance, {} unmatched, max {} ({} ulp)",
+            self.failures().count(),
+            self.unmatched,
+            self.max_abs(),
+            self.max_ulps(),
+        )?;
+        for difference in self.failures() {
+            writeln!(
+                f,
+                "[{}] {:#x}  {} -> {}  ({:+}, {:+} ulp)",
+                difference.index,
+                difference.word,
+                difference.expected,
+                difference.actual,
+                difference.delta,
+                difference.ulps,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare `expected` values with `bits`-wide words, pair by pair
+///
+/// # Panics
+///
+/// Panics if `bits` is not 16, 32 or 48.
+pub fn compare(expected: &[f64], words: &[u64], bits: u32, tolerance: Tolerance) -> Comparison {
+    // The value of a word and of its least significant mantissa bit
+    let read: fn(u64) -> (f64, f64) = match bits {
+        16 => |word| (arith::value16(word as u16), arith::ulp16(word as u16)),
+        32 => |word| (arith::value32(word as u32), arith::ulp32(word as u32)),
+        48 => |word| (arith::value48(word), arith::ulp48(word)),
+        _ => panic!("words must be 16, 32 or 48 bits"),
+    };
+
+    let mut differences = Vec::new();
+    for (index, (&expected, &word)) in expected.iter().zip(words).enumerate() {
+        let (actual, ulp) = read(word);
+        if actual == expected {
+            continue;
+        }
+        let delta = actual - expected;
+        let ulps = delta / ulp;
+        differences.push(Difference {
+            index,
+            expected,
+            word,
+            actual,
+            delta,
+            ulps,
+            passed: tolerance.allows(delta, ulps),
+        });
+    }
+    Comparison {
+        differences,
+        compared: expected.len().min(words.len()),
+        unmatched: expected.len().abs_diff(words.len()),
+    }
+}
+
+/// Compare a file of IEEE values in native byte order with a file of words in `layout`
+///
+/// Trailing bytes of either file that do not form a whole value or word are ignored.
+pub fn compare_files(
+    expected: impl AsRef<Path>,
+    precision: Precision,
+    actual: impl AsRef<Path>,
+    layout: Layout,
+    tolerance: Tolerance,
+) -> io::Result<Comparison> {
+    let bytes = fs::read(expected)?;
+    let expected: Vec<f64> = match precision {
+        Precision::Single => bytes
+            .chunks_exact(4)
+            .map(|value| f32::from_ne_bytes(value.try_into().unwrap()).into())
+            .collect(),
+        Precision::Double => bytes
+            .chunks_exact(8)
+            .map(|value| f64::from_ne_bytes(value.try_into().unwrap()))
+            .collect(),
+    };
+    let words = layout.read_words(&fs::read(actual)?);
+    Ok(compare(&expected, &words, layout.bits, tolerance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ByteOrder, WordOrder};
+
+    #[test]
+    fn test_compare() {
+        let expected = [1.0, 2.0, -1.0, 0.1];
+        // 2.0 one ULP high, -1.0 exact, 0.1 as its nearest word
+        let words = [
+            0x400000010000,
+            0x400000020001,
+            0x800000000000,
+            0x666666FD6666,
+        ];
+        let comparison = compare(&expected, &words, 48, Tolerance::EXACT);
+        assert_eq!(comparison.compared(), 4);
+        let indices: Vec<_> = comparison.differences().iter().map(|d| d.index).collect();
+        assert_eq!(indices, [1, 3]);
+        assert_eq!(comparison.differences()[0].ulps, 1.0);
+        assert!(!comparison.passed());
+
+        assert!(compare(&expected, &words, 48, Tolerance::ulps(1.0)).passed());
+        assert!(compare(&expected, &words, 48, Tolerance::absolute(1e-11)).passed());
+        let summary = compare(&expected, &words, 48, Tolerance::ulps(0.5)).to_string();
+        assert!(summary.starts_with("FAIL: 4 compared, 2 differ, 1 beyond tolerance"));
+        assert!(summary.contains("[1] 0x400000020001"));
+
+        let comparison = compare(
+            &[12.40625, f64::NAN],
+            &[0x6344, 0x6344],
+            16,
+            Tolerance::EXACT,
+        );
+        assert_eq!(comparison.failures().count(), 1);
+    }
+
+    #[test]
+    fn test_compare_files() {
+        let dir = std::env::temp_dir();
+        let expected = dir.join(format!("mil1750a-{}-expected", std::process::id()));
+        let actual = dir.join(format!("mil1750a-{}-actual", std::process::id()));
+        let values: Vec<u8> = [1.0f32, 5.234]
+            .iter()
+            .flat_map(|v| v.to_ne_bytes())
+            .collect();
+        fs::write(&expected, values).unwrap();
+        // Least significant word first, each word little-endian
+        fs::write(
+            &actual,
+            [0x01, 0x00, 0x00, 0x40, 0x03, 0x77, 0xBE, 0x53, 0xFF],
+        )
+        .unwrap();
+
+        let layout = Layout {
+            bits: 32,
+            byte_order: ByteOrder::LittleEndian,
+            word_order: WordOrder::LsWordFirst,
+        };
+        let comparison = compare_files(
+            &expected,
+            Precision::Single,
+            &actual,
+            layout,
+            Tolerance::EXACT,
+        )
+        .unwrap();
+        fs::remove_file(expected).unwrap();
+        fs::remove_file(actual).unwrap();
+        assert_eq!(comparison.compared(), 2);
+        assert!(comparison.passed(), "{comparison}");
+    }
+}
@@ -0,0 +1,388 @@
+//! # Conformance
+//! Check the conversions against a fixed table of words and their values, as evidence on
+//! delivery that the library reads and forms words as MIL-STD-1750A defines them.
+//!
+//! [`VECTORS`] holds the floating point examples of the standard, for the 32- and 48-bit
+//! formats, and vectors worked from its definitions for the extremes of each format and for the
+//! 16-bit format, which it does not tabulate. Each pairs a word with its exact value.
+//! [`verify_all`] decodes every word with [`MilFormat::decode`] and encodes every value with
+//! [`MilFormat::encode`], and reports each that does not give the other back; [`verify`] does
+//! the same for a table of your own, such as one read from a golden file. The 32-bit values an
+//! `f32` cannot hold, below 2^-126 in magnitude, are checked with the exact conversions of
+//! [`arith`] and [`roundtrip`](crate::roundtrip) instead.
+//!
+//! ```
+//! use mil1750a_converter::conformance;
+//!
+//! let report = conformance::verify_all();
+//! assert!(report.passed(), "{report}");
+//! assert_eq!(report.checked(), conformance::VECTORS.len());
+//! ```
+
+use crate::{arith, half, MilFormat, TargetFormat, F32, F48};
+use std::fmt;
+use Source::{Derived, Standard};
+use TargetFormat::{Float16, Float32, Float48};
+
+/// Where a [`Vector`] comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// The floating point examples of MIL-STD-1750A
+    Standard,
+    /// Worked from the definitions of the standard
+    Derived,
+    /// Supplied by the caller of [`verify`]
+    User,
+}
+
+/// A word and its exact value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector {
+    /// The format of the word, [`TargetFormat::Float16`], [`TargetFormat::Float32`] or
+    /// [`TargetFormat::Float48`]
+    pub format: TargetFormat,
+    /// The word, in the low bits
+    pub word: u64,
+    /// The value of the word
+    pub value: f64,
+    /// Where the vector comes from
+    pub source: Source,
+    /// What the vector shows, as its source writes it
+    pub note: &'static str,
+}
+
+const fn vector(
+    format: TargetFormat,
+    word: u64,
+    value: f64,
+    source: Source,
+    note: &'static str,
+) -> Vector {
+    Vector {
+        format,
+        word,
+        value,
+        source,
+        note,
+    }
+}
+
+/// 2^127
+const MAX_SCALE: f64 = 1.7014118346046923e38;
+/// 2^-128
+const MIN_SCALE: f64 = 2.938735877055719e-39;
+
+/// The built-in vectors, see the [module documentation](self)
+pub static VECTORS: &[Vector] = &[
+    vector(
+        Float32,
+        0x7FFFFF7F,
+        (1.0 - 1.0 / 8388608.0) * MAX_SCALE,
+        Standard,
+        "0.9999998 x 2^127",
+    ),
+    vector(
+        Float32,
+        0x4000007F,
+        0.5 * MAX_SCALE,
+        Standard,
+        "0.5 x 2^127",
+    ),
+    vector(Float32, 0x50000004, 0.625 * 16.0, Standard, "0.625 x 2^4"),
+    vector(Float32, 0x40000001, 0.5 * 2.0, Standard, "0.5 x 2^1"),
+    vector(Float32, 0x40000000, 0.5, Standard, "0.5 x 2^0"),
+    vector(Float32, 0x400000FF, 0.5 * 0.5, Standard, "0.5 x 2^-1"),
+    vector(
+        Float32,
+        0x40000080,
+        0.5 * MIN_SCALE,
+        Standard,
+        "0.5 x 2^-128",
+    ),
+    vector(Float32, 0x00000000, 0.0, Standard, "0.0 x 2^0"),
+    vector(Float32, 0x80000000, -1.0, Standard, "-1.0 x 2^0"),
+    vector(
+        Float32,
+        0xBFFFFF80,
+        -(0.5 + 1.0 / 8388608.0) * MIN_SCALE,
+        Standard,
+        "-0.5000001 x 2^-128",
+    ),
+    vector(
+        Float32,
+        0x9FFFFF04,
+        -(0.75 + 1.0 / 8388608.0) * 16.0,
+        Standard,
+        "-0.7500001 x 2^4",
+    ),
+    vector(Float32, 0x8000007F, -MAX_SCALE, Derived, "-1.0 x 2^127"),
+    vector(
+        Float32,
+        0x53BE7703,
+        5.234000205993652,
+        Derived,
+        "5.234 rounded",
+    ),
+    vector(
+        Float32,
+        0x997AE105,
+        -25.630001068115234,
+        Derived,
+        "-25.63 rounded",
+    ),
+    vector(
+        Float48,
+        0x4000007F0000,
+        0.5 * MAX_SCALE,
+        Standard,
+        "0.5 x 2^127",
+    ),
+    vector(Float48, 0x400000000000, 0.5, Standard, "0.5 x 2^0"),
+    vector(Float48, 0x400000FF0000, 0.5 * 0.5, Standard, "0.5 x 2^-1"),
+    vector(
+        Float48,
+        0x400000800000,
+        0.5 * MIN_SCALE,
+        Standard,
+        "0.5 x 2^-128",
+    ),
+    vector(
+        Float48,
+        0x8000007F0000,
+        -MAX_SCALE,
+        Standard,
+        "-1.0 x 2^127",
+    ),
+    vector(Float48, 0x800000000000, -1.0, Standard, "-1.0 x 2^0"),
+    vector(
+        Float48,
+        0x800000800000,
+        -MIN_SCALE,
+        Standard,
+        "-1.0 x 2^-128",
+    ),
+    vector(Float48, 0x000000000000, 0.0, Standard, "0.0 x 2^0"),
+    vector(
+        Float48,
+        0xA00000FF0000,
+        -0.75 * 0.5,
+        Standard,
+        "-0.75 x 2^-1",
+    ),
+    vector(
+        Float48,
+        0x7FFFFF7FFFFF,
+        (1.0 - 1.0 / 549755813888.0) * MAX_SCALE,
+        Derived,
+        "(1 - 2^-39) x 2^127",
+    ),
+    vector(
+        Float48,
+        0x69A3B50754AB,
+        105.63948563742451,
+        Derived,
+        "105.639485637361 rounded",
+    ),
+    vector(Float16, 0x4001, 1.0, Derived, "0.5 x 2^1"),
+    vector(Float16, 0x8000, -1.0, Derived, "-1.0 x 2^0"),
+    vector(Float16, 0x403F, 0.25, Derived, "0.5 x 2^-1"),
+    vector(Float16, 0x6344, 12.40625, Derived, "12.4 rounded"),
+    vector(
+        Float16,
+        0x7FDF,
+        (1.0 - 1.0 / 512.0) * 2147483648.0,
+        Derived,
+        "(1 - 2^-9) x 2^31",
+    ),
+    vector(Float16, 0x4020, 0.5 / 4294967296.0, Derived, "0.5 x 2^-32"),
+];
+
+/// A vector the conversions do not reproduce
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Failure {
+    /// The word decodes to another value
+    Decode {
+        /// The vector
+        vector: Vector,
+        /// The decoded value
+        actual: f64,
+    },
+    /// The value encodes to another word
+    Encode {
+        /// The vector
+        vector: Vector,
+        /// The encoded word
+        actual: u64,
+    },
+}
+
+impl Failure {
+    /// The vector that failed
+    pub fn vector(&self) -> &Vector {
+        match self {
+            Failure::Decode { vector, .. } | Failure::Encode { vector, .. } => vector,
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let vector = self.vector();
+        let width = vector.format.bits() as usize / 4 + 2;
+        write!(f, "{} ({}): ", vector.note, vector.format)?;
+        match *self {
+            Failure::Decode { actual, .. } => write!(
+                f,
+                "{:#0width$x} decodes to {actual}, expected {}",
+                vector.word, vector.value
+            ),
+            Failure::Encode { actual, .. } => write!(
+                f,
+                "{} encodes to {actual:#0width$x}, expected {:#0width$x}",
+                vector.value, vector.word
+            ),
+        }
+    }
+}
+
+/// The outcome of a conformance check, failures in table order
+///
+/// [`Display`](fmt::Display) prints a summary line and one line per failure.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    failures: Vec<Failure>,
+    checked: usize,
+}
+
+impl Report {
+    /// Every failure found
+    pub fn failures(&self) -> &[Failure] {
+        &self.failures
+    }
+
+    /// Number of vectors checked
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+
+    /// Whether every vector was reproduced
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} vectors checked, {} failures",
+            if self.passed() { "PASS" } else { "FAIL" },
+            self.checked,
+            self.failures.len(),
+        )?;
+        for failure in &self.failures {
+            writeln!(f, "{failure}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check the conversions against [`VECTORS`], see the [module documentation](self)
+pub fn verify_all() -> Report {
+    verify(VECTORS)
+}
+
+/// Check the conversions against `vectors`, see the [module documentation](self)
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::conformance::{verify, Failure, Source, Vector};
+/// use mil1750a_converter::TargetFormat;
+///
+/// let golden = Vector {
+///     format: TargetFormat::Float32,
+///     word: 0x40000002,
+///     value: 1.0,
+///     source: Source::User,
+///     note: "wrong on purpose",
+/// };
+/// let report = verify(&[golden]);
+/// assert!(matches!(report.failures(), [Failure::Decode { actual: 2.0, .. }, Failure::Encode { .. }]));
+/// ```
+///
+/// # Panics
+///
+/// Panics if a vector is not of a floating point format.
+pub fn verify(vectors: &[Vector]) -> Report {
+    let mut report = Report::default();
+    for &vector in vectors {
+        report.checked += 1;
+        let (decoded, encoded) = match vector.format {
+            Float16 => (
+                half::decode_f32(vector.word as u16).into(),
+                half::encode_f32(vector.value as f32).into(),
+            ),
+            Float32 if f64::from(vector.value as f32) == vector.value => (
+                F32::decode(vector.word as u32).into(),
+                F32::encode(vector.value as f32).into(),
+            ),
+            Float32 => (
+                arith::value32(vector.word as u32),
+                arith::round32(vector.value).into(),
+            ),
+            Float48 => (F48::decode(vector.word), F48::encode(vector.value)),
+            format => panic!("{format} is not a floating point format"),
+        };
+        if decoded != vector.value {
+            report.failures.push(Failure::Decode {
+                vector,
+                actual: decoded,
+            });
+        }
+        if encoded != vector.word {
+            report.failures.push(Failure::Encode {
+                vector,
+                actual: encoded,
+            });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors() {
+        assert!(verify_all().passed(), "{}", verify_all());
+        assert_eq!(MAX_SCALE, 2f64.powi(127));
+        assert_eq!(MIN_SCALE, 2f64.powi(-128));
+        for vector in VECTORS {
+            let exact = match vector.format {
+                Float16 => arith::value16(vector.word as u16),
+                Float32 => arith::value32(vector.word as u32),
+                _ => arith::value48(vector.word),
+            };
+            assert_eq!(exact, vector.value, "{}", vector.note);
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let wrong = Vector {
+            word: 0x40000002,
+            ..VECTORS[3]
+        };
+        let report = verify(&[VECTORS[4], wrong]);
+        assert_eq!((report.checked(), report.failures().len()), (2, 2));
+        assert_eq!(report.failures()[0].vector(), &wrong);
+        assert_eq!(
+            report.to_string(),
+            "FAIL: 2 vectors checked, 2 failures\n\
+             0.5 x 2^1 (32-bit MIL-1750A float): 0x40000002 decodes to 2, expected 1\n\
+             0.5 x 2^1 (32-bit MIL-1750A float): 1 encodes to 0x40000001, expected 0x40000002\n"
+        );
+    }
+}
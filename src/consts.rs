@@ -0,0 +1,194 @@
+//! # Constants
+//! Mathematical constants and conversion factors for ICD tables.
+//!
+//! [`encoded`] holds each already rounded to the nearest word of every format, so tables need
+//! not be encoded by hand.
+
+pub mod encoded {
+    //! Constants as the nearest word of each format, in [`half`], [`single`] and [`extended`]
+    //!
+    //! Every word is the correctly rounded value of the constant, ties away from zero, computed
+    //! from its exact value rather than from the nearest `f64`, which can differ from it in the
+    //! last bit of a 48-bit word.
+    //!
+    //! ```
+    //! use mil1750a_converter::consts::encoded;
+    //!
+    //! assert_eq!(encoded::single::PI.to_bits(), 0x6487ED02);
+    //! assert_eq!(encoded::extended::FT_TO_M.to_f64(), 0.3047999999998865);
+    //! assert_eq!(encoded::single::DEG_TO_RAD.to_f32(), 0.017453294);
+    //! ```
+
+    pub mod half {
+        //! Constants as 16-bit words
+        use crate::Mil16;
+
+        /// π
+        pub const PI: Mil16 = Mil16::from_bits(0x6482);
+        /// Euler's number, e
+        pub const E: Mil16 = Mil16::from_bits(0x5702);
+        /// √2
+        pub const SQRT_2: Mil16 = Mil16::from_bits(0x5A81);
+        /// ln 2
+        pub const LN_2: Mil16 = Mil16::from_bits(0x58C0);
+        /// Radians in a degree, π / 180
+        pub const DEG_TO_RAD: Mil16 = Mil16::from_bits(0x47BB);
+        /// Degrees in a radian, 180 / π
+        pub const RAD_TO_DEG: Mil16 = Mil16::from_bits(0x7286);
+        /// Metres in an international foot, 0.3048
+        pub const FT_TO_M: Mil16 = Mil16::from_bits(0x4E3F);
+        /// International feet in a metre, 1 / 0.3048
+        pub const M_TO_FT: Mil16 = Mil16::from_bits(0x6902);
+    }
+
+    pub mod single {
+        //! Constants as 32-bit words
+        use crate::Mil32;
+
+        /// π
+        pub const PI: Mil32 = Mil32::from_bits(0x6487ED02);
+        /// Euler's number, e
+        pub const E: Mil32 = Mil32::from_bits(0x56FC2A02);
+        /// √2
+        pub const SQRT_2: Mil32 = Mil32::from_bits(0x5A827A01);
+        /// ln 2
+        pub const LN_2: Mil32 = Mil32::from_bits(0x58B90C00);
+        /// Radians in a degree, π / 180
+        pub const DEG_TO_RAD: Mil32 = Mil32::from_bits(0x477D1BFB);
+        /// Degrees in a radian, 180 / π
+        pub const RAD_TO_DEG: Mil32 = Mil32::from_bits(0x72977006);
+        /// Metres in an international foot, 0.3048
+        pub const FT_TO_M: Mil32 = Mil32::from_bits(0x4E075FFF);
+        /// International feet in a metre, 1 / 0.3048
+        pub const M_TO_FT: Mil32 = Mil32::from_bits(0x68FCA402);
+    }
+
+    pub mod extended {
+        //! Constants as 48-bit words
+        use crate::Mil48;
+
+        /// π
+        pub const PI: Mil48 = Mil48::from_bits(0x6487ED025111);
+        /// Euler's number, e
+        pub const E: Mil48 = Mil48::from_bits(0x56FC2A022C51);
+        /// √2
+        pub const SQRT_2: Mil48 = Mil48::from_bits(0x5A82790199FD);
+        /// ln 2
+        pub const LN_2: Mil48 = Mil48::from_bits(0x58B90B00FBE9);
+        /// Radians in a degree, π / 180
+        pub const DEG_TO_RAD: Mil48 = Mil48::from_bits(0x477D1AFB894A);
+        /// Degrees in a radian, 180 / π
+        pub const RAD_TO_DEG: Mil48 = Mil48::from_bits(0x72977006698F);
+        /// Metres in an international foot, 0.3048
+        pub const FT_TO_M: Mil48 = Mil48::from_bits(0x4E075FFF6FD2);
+        /// International feet in a metre, 1 / 0.3048
+        pub const M_TO_FT: Mil48 = Mil48::from_bits(0x68FCA302F290);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encoded::{extended, half, single};
+    use crate::{arith, Mil16, Mil32, Mil48};
+
+    /// 10^35, the denominator of the decimal expansions
+    const SCALE: u128 = 100_000_000_000_000_000_000_000_000_000_000_000;
+    /// π to 35 decimal places, over [`SCALE`]
+    const PI: u128 = 314_159_265_358_979_323_846_264_338_327_950_288;
+
+    /// Every constant of each format, in the same order
+    const HALVES: [Mil16; 8] = [
+        half::PI,
+        half::E,
+        half::SQRT_2,
+        half::LN_2,
+        half::DEG_TO_RAD,
+        half::RAD_TO_DEG,
+        half::FT_TO_M,
+        half::M_TO_FT,
+    ];
+    const SINGLES: [Mil32; 8] = [
+        single::PI,
+        single::E,
+        single::SQRT_2,
+        single::LN_2,
+        single::DEG_TO_RAD,
+        single::RAD_TO_DEG,
+        single::FT_TO_M,
+        single::M_TO_FT,
+    ];
+    const EXTENDEDS: [Mil48; 8] = [
+        extended::PI,
+        extended::E,
+        extended::SQRT_2,
+        extended::LN_2,
+        extended::DEG_TO_RAD,
+        extended::RAD_TO_DEG,
+        extended::FT_TO_M,
+        extended::M_TO_FT,
+    ];
+
+    #[test]
+    fn test_correctly_rounded() {
+        // Each constant as an exact ratio, its truncation far below half a 48-bit ULP
+        let exact = [
+            (PI, SCALE),
+            (271_828_182_845_904_523_536_028_747_135_266_250, SCALE),
+            (141_421_356_237_309_504_880_168_872_420_969_808, SCALE),
+            (69_314_718_055_994_530_941_723_212_145_817_657, SCALE),
+            (PI, 180 * SCALE),
+            (180 * SCALE, PI),
+            (3048, 10000),
+            (10000, 3048),
+        ];
+        for (index, (numerator, denominator)) in exact.into_iter().enumerate() {
+            assert_eq!(
+                Some(HALVES[index].to_bits()),
+                arith::try_round_ratio16(false, numerator, denominator)
+            );
+            assert_eq!(
+                Some(SINGLES[index].to_bits()),
+                arith::try_round_ratio32(false, numerator, denominator)
+            );
+            assert_eq!(
+                Some(EXTENDEDS[index].to_bits()),
+                arith::try_round_ratio48(false, numerator, denominator)
+            );
+        }
+        let pi = extended::PI.to_bits();
+        assert!((arith::value48(pi) - std::f64::consts::PI).abs() <= arith::ulp48(pi) / 2.0);
+    }
+
+    #[test]
+    fn test_wrapper_values() {
+        use std::f64::consts;
+
+        // The nearest f64 of each constant, itself off the exact value by up to half an f64 ULP,
+        // so the 48-bit words are only checked to within one of theirs
+        let nearest = [
+            consts::PI,
+            consts::E,
+            consts::SQRT_2,
+            consts::LN_2,
+            consts::PI / 180.0,
+            180.0 / consts::PI,
+            0.3048,
+            1.0 / 0.3048,
+        ];
+        for (index, value) in nearest.into_iter().enumerate() {
+            let word = HALVES[index];
+            let error = (f64::from(word.to_f32()) - value).abs();
+            assert!(error <= arith::ulp16(word.to_bits()) / 2.0, "{word:?}");
+            #[cfg(feature = "half")]
+            assert_eq!(word.to_f16(), ::half::f16::from_f32(word.to_f32()));
+
+            let word = SINGLES[index];
+            let error = (f64::from(word.to_f32()) - value).abs();
+            assert!(error <= arith::ulp32(word.to_bits()) / 2.0, "{word:?}");
+
+            let word = EXTENDEDS[index];
+            let error = (word.to_f64() - value).abs();
+            assert!(error <= arith::ulp48(word.to_bits()), "{word:?}");
+        }
+    }
+}
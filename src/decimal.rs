@@ -0,0 +1,149 @@
+//! [`rust_decimal`] input for [`Mil16`], [`Mil32`] and [`Mil48`].
+//!
+//! A [`Decimal`] is exact, so converting one rounds once, straight to the format: a value such
+//! as `549755813888.99999999999999` is rounded down to the nearer 48-bit word, where it would
+//! first round to the `f64` tie `549755813889.0` and then away from zero. Ties round away from
+//! zero like the encoders.
+
+use crate::{arith, Mil16, Mil1750Error, Mil32, Mil48, TargetFormat};
+use rust_decimal::Decimal;
+
+/// The sign, magnitude and power-of-ten denominator of a decimal
+fn ratio(value: Decimal) -> (bool, u128, u128) {
+    let mantissa = value.mantissa();
+    (
+        mantissa < 0,
+        mantissa.unsigned_abs(),
+        10u128.pow(value.scale()),
+    )
+}
+
+/// An approximation of a decimal, for errors
+fn approximate(value: Decimal) -> f64 {
+    value.mantissa() as f64 / 10f64.powi(value.scale() as i32)
+}
+
+/// The error for a decimal outside the range of `target`
+fn out_of_range(value: Decimal, target: TargetFormat) -> Mil1750Error {
+    Mil1750Error::OutOfRange {
+        value: approximate(value),
+        target,
+    }
+}
+
+impl TryFrom<Decimal> for Mil16 {
+    type Error = Mil1750Error;
+
+    /// Round a decimal exactly to the nearest word, failing if it is out of range
+    fn try_from(value: Decimal) -> Result<Self, Mil1750Error> {
+        let (negative, numerator, denominator) = ratio(value);
+        arith::try_round_ratio16(negative, numerator, denominator)
+            .map(Mil16::from_bits)
+            .ok_or(out_of_range(value, TargetFormat::Float16))
+    }
+}
+
+impl TryFrom<Decimal> for Mil32 {
+    type Error = Mil1750Error;
+
+    /// Round a decimal exactly to the nearest word, failing if it is out of range
+    fn try_from(value: Decimal) -> Result<Self, Mil1750Error> {
+        let (negative, numerator, denominator) = ratio(value);
+        arith::try_round_ratio32(negative, numerator, denominator)
+            .map(Mil32::from_bits)
+            .ok_or(out_of_range(value, TargetFormat::Float32))
+    }
+}
+
+impl TryFrom<Decimal> for Mil48 {
+    type Error = Mil1750Error;
+
+    /// Round a decimal exactly to the nearest word, failing if it is out of range
+    fn try_from(value: Decimal) -> Result<Self, Mil1750Error> {
+        let (negative, numerator, denominator) = ratio(value);
+        arith::try_round_ratio48(negative, numerator, denominator)
+            .map(Mil48::from_bits)
+            .ok_or(out_of_range(value, TargetFormat::Float48))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn decimal(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_matches_f64_encoding() {
+        for value in [
+            "0.1",
+            "-0.1",
+            "5.234",
+            "1",
+            "-1",
+            "0",
+            "105.639485637361",
+            "-3e-9",
+        ] {
+            let approximate = approximate(decimal(value));
+            assert_eq!(
+                Mil32::try_from(decimal(value)).unwrap().to_bits(),
+                arith::round32(approximate),
+                "{value}"
+            );
+            assert_eq!(
+                Mil48::try_from(decimal(value)).unwrap().to_bits(),
+                arith::round48(approximate),
+                "{value}"
+            );
+            assert_eq!(
+                Mil16::try_from(decimal(value)).unwrap().to_bits(),
+                arith::round16(approximate),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rounds_once() {
+        // Just below the tie between 2^39 and 2^39 + 2, which an f64 rounds onto
+        let below = decimal("549755813888.99999999999999");
+        assert_eq!(approximate(below), 549755813889.0);
+        assert_eq!(Mil48::try_from(below).unwrap().to_f64(), 549755813888.0);
+        assert_eq!(
+            Mil48::try_from(decimal("549755813889")).unwrap().to_f64(),
+            549755813890.0
+        );
+        assert_eq!(
+            Mil48::try_from(-below).unwrap().to_bits(),
+            arith::round48(-549755813888.0)
+        );
+
+        let below = decimal("8388608.9999999999");
+        assert_eq!(Mil32::try_from(below).unwrap().to_bits(), 0x40000018);
+        assert_eq!(
+            Mil32::try_from(decimal("8388609")).unwrap().to_bits(),
+            0x40000118
+        );
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert_eq!(
+            Mil16::try_from(decimal("1e20")),
+            Err(Mil1750Error::OutOfRange {
+                value: 1e20,
+                target: TargetFormat::Float16
+            })
+        );
+        assert!(Mil16::try_from(decimal("1e-28")).is_err());
+        assert!(Mil32::try_from(Decimal::MAX).is_ok());
+        assert_eq!(
+            Mil32::try_from(decimal("1e-28")).unwrap().to_bits(),
+            arith::round32(1e-28)
+        );
+    }
+}
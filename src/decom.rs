@@ -0,0 +1,353 @@
+//! # Decommutation
+//! Split a block of interleaved PCM frames into one time series per channel.
+//!
+//! A [`DecomMap`] names each channel with the word offset of its first sample, the number of
+//! words from one sample to the next, its [`FieldFormat`] and a scale factor. Decoding a block
+//! reads every sample of every channel that fits in it, decoded as a [`Schema`] field would be
+//! and multiplied by the channel's scale, without de-interleaving the block first.
+//!
+//! ```
+//! use mil1750a_converter::decom::DecomMap;
+//! use mil1750a_converter::schema::FieldFormat;
+//!
+//! // Three-word frames: a counter and a 32-bit temperature
+//! let map = DecomMap::new()
+//!     .channel("count", 0, 3, FieldFormat::Int16, 1.0)
+//!     .channel("temperature", 1, 3, FieldFormat::F32, 0.5);
+//! let block = [0x0001, 0x4000, 0x0001, 0x0002, 0x5000, 0x0003];
+//! let series = map.decode(&block).unwrap();
+//! assert_eq!(series.get("count"), Some(&[1.0, 2.0][..]));
+//! assert_eq!(series.get("temperature"), Some(&[0.5, 2.5][..]));
+//! ```
+//!
+//! With the `serde` feature a map can be loaded from a YAML or JSON file as a [`Schema`] can,
+//! with a `stride` for each channel and a `scale` that defaults to one.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use mil1750a_converter::decom::DecomMap;
+//!
+//! let map: DecomMap = serde_json::from_str(
+//!     r#"{"channels": [{"name": "count", "offset": 0, "stride": 2, "format": "int16"}]}"#,
+//! )
+//! .unwrap();
+//! let series = map.decode(&[1, 0xFFFF, 2]).unwrap();
+//! assert_eq!(series.get("count"), Some(&[1.0, 2.0][..]));
+//! # }
+//! ```
+
+use crate::schema::{Calibration, Field, FieldFormat, Schema, SchemaError};
+use crate::WordOrder;
+
+/// The channels of an interleaved block of frames
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "DecomFile", try_from = "DecomFile")
+)]
+pub struct DecomMap {
+    schema: Schema,
+    strides: Vec<usize>,
+    scales: Vec<f64>,
+}
+
+impl DecomMap {
+    /// A map with no channels
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a channel whose first sample starts at word `offset`, with a sample every `stride`
+    /// words, each multiplied by `scale`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is zero.
+    pub fn channel(
+        mut self,
+        name: impl Into<String>,
+        offset: usize,
+        stride: usize,
+        format: FieldFormat,
+        scale: f64,
+    ) -> Self {
+        assert!(stride > 0, "channel stride must not be zero");
+        self.schema = self.schema.field(name, offset, format);
+        self.strides.push(stride);
+        self.scales.push(scale);
+        self
+    }
+
+    /// Give the channel added last a calibration, applied to each sample before its scale,
+    /// see [`Schema::calibrated`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map has no channels.
+    pub fn calibrated(mut self, calibration: Calibration) -> Self {
+        self.schema = self.schema.calibrated(calibration);
+        self
+    }
+
+    /// Reject unnormalized samples, see [`Schema::strict`]
+    pub fn strict(mut self) -> Self {
+        self.schema = self.schema.strict();
+        self
+    }
+
+    /// Store multi-word samples in `order` rather than most significant word first
+    pub fn word_order(mut self, order: WordOrder) -> Self {
+        self.schema = self.schema.word_order(order);
+        self
+    }
+
+    /// The channels, as fields at the offset of their first sample, in the order they were
+    /// added
+    pub fn channels(&self) -> &[Field] {
+        self.schema.fields()
+    }
+
+    /// Decode every sample of every channel in `block`; spare channels give empty series
+    pub fn decode(&self, block: &[u16]) -> Result<Series, SchemaError> {
+        let mut series = Vec::with_capacity(self.strides.len());
+        let mut units = Vec::with_capacity(self.strides.len());
+        for ((field, &stride), &scale) in
+            self.channels().iter().zip(&self.strides).zip(&self.scales)
+        {
+            let words = field.format.words();
+            let mut samples = Vec::new();
+            let mut offset = field.offset;
+            while let Some(sample) = block.get(offset..offset + words) {
+                if let Some(value) = self.schema.decode_field(field, sample)? {
+                    samples.push(value.to_f64() * scale);
+                }
+                offset += stride;
+            }
+            series.push((field.name.clone(), samples));
+            units.push(field.calibration.as_ref().map(|c| c.units.clone()));
+        }
+        Ok(Series { series, units })
+    }
+}
+
+/// The channels of a [`DecomMap`] as they are kept in a file
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DecomFile {
+    #[serde(default)]
+    strict: bool,
+    #[serde(default)]
+    word_order: WordOrder,
+    channels: Vec<Channel>,
+}
+
+/// One channel of a [`DecomFile`]
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Channel {
+    name: String,
+    offset: usize,
+    stride: usize,
+    format: FieldFormat,
+    #[serde(default = "unit_scale")]
+    scale: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    calibration: Option<Calibration>,
+}
+
+#[cfg(feature = "serde")]
+fn unit_scale() -> f64 {
+    1.0
+}
+
+#[cfg(feature = "serde")]
+impl From<DecomMap> for DecomFile {
+    fn from(map: DecomMap) -> Self {
+        DecomFile {
+            strict: map.schema.is_strict(),
+            word_order: map.schema.order(),
+            channels: map
+                .channels()
+                .iter()
+                .zip(map.strides.iter().zip(&map.scales))
+                .map(|(field, (&stride, &scale))| Channel {
+                    name: field.name.clone(),
+                    offset: field.offset,
+                    stride,
+                    format: field.format,
+                    scale,
+                    calibration: field.calibration.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DecomFile> for DecomMap {
+    type Error = SchemaError;
+
+    fn try_from(file: DecomFile) -> Result<Self, SchemaError> {
+        let mut map = DecomMap::new().word_order(file.word_order);
+        if file.strict {
+            map = map.strict();
+        }
+        for channel in file.channels {
+            let reason = match crate::schema::invalid(channel.offset, channel.format) {
+                Some(reason) => reason,
+                None if channel.stride == 0 => "channel stride must not be zero".to_owned(),
+                None => {
+                    map = map.channel(
+                        channel.name,
+                        channel.offset,
+                        channel.stride,
+                        channel.format,
+                        channel.scale,
+                    );
+                    if let Some(calibration) = channel.calibration {
+                        map = map.calibrated(calibration);
+                    }
+                    continue;
+                }
+            };
+            return Err(SchemaError::Invalid {
+                name: channel.name,
+                reason,
+            });
+        }
+        Ok(map)
+    }
+}
+
+/// The decoded samples of each channel of a [`DecomMap`], in map order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Series {
+    series: Vec<(String, Vec<f64>)>,
+    units: Vec<Option<String>>,
+}
+
+impl Series {
+    /// The samples of the channel called `name`
+    pub fn get(&self, name: &str) -> Option<&[f64]> {
+        self.series
+            .iter()
+            .find(|(channel, _)| channel == name)
+            .map(|(_, samples)| samples.as_slice())
+    }
+
+    /// The engineering units of the channel called `name`, if it has a [`Calibration`]
+    pub fn units(&self, name: &str) -> Option<&str> {
+        let index = self
+            .series
+            .iter()
+            .position(|(channel, _)| channel == name)?;
+        self.units[index].as_deref()
+    }
+
+    /// The samples by channel name, in map order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[f64])> {
+        self.series
+            .iter()
+            .map(|(name, samples)| (name.as_str(), samples.as_slice()))
+    }
+
+    /// Number of channels
+    pub fn len(&self) -> usize {
+        self.series.len()
+    }
+
+    /// Whether there are no channels
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+}
+
+impl IntoIterator for Series {
+    type Item = (String, Vec<f64>);
+    type IntoIter = std::vec::IntoIter<(String, Vec<f64>)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.series.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DecodeError;
+
+    #[test]
+    fn test_decode() {
+        // Four-word frames with a channel subcommutated into every other frame
+        let map = DecomMap::new()
+            .channel("count", 0, 4, FieldFormat::Int16, 2.0)
+            .channel("slow", 1, 8, FieldFormat::F32, 1.0)
+            .channel("spare", 3, 4, FieldFormat::Spare { words: 1 }, 1.0)
+            .word_order(WordOrder::LsWordFirst);
+        let block = [
+            1, 0x0001, 0x4000, 0, 2, 0xFFFF, 0xFFFF, 0, 3, 0x0003, 0x5000, 0,
+        ];
+        let series = map.decode(&block).unwrap();
+        let names: Vec<_> = series.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["count", "slow", "spare"]);
+        assert_eq!(series.get("count"), Some(&[2.0, 4.0, 6.0][..]));
+        assert_eq!(series.get("slow"), Some(&[1.0, 5.0][..]));
+        assert_eq!(series.get("spare"), Some(&[][..]));
+        assert_eq!(series.get("missing"), None);
+        assert_eq!(map.channels()[1].offset, 1);
+        assert_eq!(series.units("count"), None);
+    }
+
+    #[test]
+    fn test_calibrated() {
+        let map = DecomMap::new()
+            .channel("pressure", 0, 1, FieldFormat::Int16, 1e3)
+            .calibrated(Calibration::new(0.5, 1.0, "kPa"));
+        let series = map.decode(&[0, 2, 0xFFFE]).unwrap();
+        assert_eq!(series.get("pressure"), Some(&[1e3, 2e3, 0.0][..]));
+        assert_eq!(series.units("pressure"), Some("kPa"));
+    }
+
+    #[test]
+    fn test_strict() {
+        let map = DecomMap::new()
+            .channel("value", 0, 1, FieldFormat::F32, 1.0)
+            .strict();
+        assert_eq!(
+            map.decode(&[0x4000, 0x0001, 0x2000, 0x0001]),
+            Err(SchemaError::Field {
+                name: "value".into(),
+                error: DecodeError::Unnormalized {
+                    word: 0x00012000,
+                    bits: 32
+                }
+            })
+        );
+        assert!(DecomMap::new().decode(&[1, 2]).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let map = DecomMap::new()
+            .channel("pressure", 0, 2, FieldFormat::Fixed { bscale: 3 }, 1e3)
+            .calibrated(Calibration::new(0.5, 1.0, "kPa"))
+            .channel("flag", 1, 4, FieldFormat::Bool { bit: 15 }, 1.0)
+            .word_order(WordOrder::LsWordFirst)
+            .strict();
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(serde_json::from_str::<DecomMap>(&json).unwrap(), map);
+
+        let error = serde_json::from_str::<DecomMap>(
+            r#"{"channels": [{"name": "x", "offset": 0, "stride": 0, "format": "f32"}]}"#,
+        )
+        .unwrap_err();
+        assert!(
+            error.to_string().contains("stride must not be zero"),
+            "{error}"
+        );
+    }
+}
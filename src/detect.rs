@@ -0,0 +1,219 @@
+//! # Layout detection
+//! Guess how an undocumented dump of MIL-1750A floats is laid out.
+//!
+//! [`detect_layout`] reads a sample of the buffer in every candidate [`Layout`]: each format,
+//! each byte order within the 16-bit words and, for the 32- and 48-bit formats, each
+//! [`WordOrder`]. The candidates are ranked by the share of non-zero words that decode as
+//! normalized numbers of plausible magnitude, between `1e-9` and `1e9`. Random bits pass
+//! that test far less often than real data, but a guess is only a guess: a buffer of 32-bit
+//! values also reads well as 16-bit ones when its low words happen to be plausible.
+//!
+//! ```
+//! use mil1750a_converter::detect::detect_layout;
+//! use mil1750a_converter::ByteOrder;
+//! use mil1750a_converter::{single, WordOrder};
+//!
+//! let mut dump = Vec::new();
+//! for value in [1.5, -27.125, 3.3e4, 0.0078, 912.27, -0.3, 64.0, 5.234] {
+//!     let word = single::encode(value);
+//!     // Least significant word first, each word little-endian
+//!     for half in [word as u16, (word >> 16) as u16] {
+//!         dump.extend(half.to_le_bytes());
+//!     }
+//! }
+//!
+//! let best = detect_layout(&dump).best().unwrap().layout;
+//! assert_eq!(best.bits, 32);
+//! assert_eq!(best.byte_order, ByteOrder::LittleEndian);
+//! assert_eq!(best.word_order, WordOrder::LsWordFirst);
+//! ```
+
+use crate::arith::{self, Form};
+use crate::{ByteOrder, MilFormat, WordOrder, F32, F48};
+
+/// Most bytes of a buffer that [`detect_layout`] looks at
+pub const SAMPLE_BYTES: usize = 64 * 1024;
+
+/// A way of reading MIL-1750A words from bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Layout {
+    /// Width of the format, 16, 32 or 48 bits
+    pub bits: u32,
+    /// Order of the bytes within each 16-bit word
+    pub byte_order: ByteOrder,
+    /// Order of the 16-bit words within a word, always most significant first for 16 bits
+    pub word_order: WordOrder,
+}
+
+impl Layout {
+    /// Bytes in a word
+    pub fn bytes(&self) -> usize {
+        self.bits as usize / 8
+    }
+
+    /// Read every whole word of `bytes`, each in the low bits of a `u64`; a trailing partial
+    /// word is ignored
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`bits`](Self::bits) is not 16, 32 or 48.
+    pub fn read_words(&self, bytes: &[u8]) -> Vec<u64> {
+        assert!(
+            matches!(self.bits, 16 | 32 | 48),
+            "words must be 16, 32 or 48 bits"
+        );
+        bytes
+            .chunks_exact(self.bytes())
+            .map(|word| {
+                let halves = word.chunks_exact(2).map(|pair| {
+                    let pair = [pair[0], pair[1]];
+                    u64::from(match self.byte_order {
+                        ByteOrder::BigEndian => u16::from_be_bytes(pair),
+                        ByteOrder::LittleEndian => u16::from_le_bytes(pair),
+                    })
+                });
+                match self.word_order {
+                    WordOrder::MsWordFirst => halves.fold(0, |bits, half| (bits << 16) | half),
+                    WordOrder::LsWordFirst => {
+                        halves.rev().fold(0, |bits, half| (bits << 16) | half)
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// A candidate layout and how well the sample reads in it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate {
+    /// The layout
+    pub layout: Layout,
+    /// Share of the non-zero words read that were plausible, from 0 to 1
+    pub score: f64,
+    /// Number of words read
+    pub words: usize,
+}
+
+/// The candidate layouts of a buffer, best first
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutGuess {
+    candidates: Vec<Candidate>,
+}
+
+impl LayoutGuess {
+    /// The highest ranked candidate, or `None` if the buffer is too short for any word
+    pub fn best(&self) -> Option<&Candidate> {
+        self.candidates.first()
+    }
+
+    /// Every candidate that read at least one word, best first
+    pub fn candidates(&self) -> &[Candidate] {
+        &self.candidates
+    }
+}
+
+/// Rank the candidate layouts of `bytes` by how plausible its words are in each, reading at
+/// most [`SAMPLE_BYTES`] bytes
+pub fn detect_layout(bytes: &[u8]) -> LayoutGuess {
+    let sample = &bytes[..bytes.len().min(SAMPLE_BYTES)];
+    let mut candidates = Vec::new();
+    for byte_order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+        let sample: Vec<u8> = match byte_order {
+            ByteOrder::BigEndian => sample.to_vec(),
+            ByteOrder::LittleEndian => sample
+                .chunks(2)
+                .flat_map(|pair| pair.iter().rev().copied())
+                .collect(),
+        };
+        candidates.extend(score(
+            &sample,
+            layout(16, byte_order, WordOrder::MsWordFirst),
+            |b| {
+                let word = u16::from_be_bytes([b[0], b[1]]);
+                (arith::form16(word), arith::value16(word))
+            },
+        ));
+        for word_order in [WordOrder::MsWordFirst, WordOrder::LsWordFirst] {
+            candidates.extend(score(&sample, layout(32, byte_order, word_order), |b| {
+                let word = F32::read_ordered(b, word_order);
+                (arith::form32(word), arith::value32(word))
+            }));
+            candidates.extend(score(&sample, layout(48, byte_order, word_order), |b| {
+                let word = F48::read_ordered(b, word_order);
+                (arith::form48(word), arith::value48(word))
+            }));
+        }
+    }
+    // Stable, so ties keep the order above: big-endian, most significant word first
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    LayoutGuess { candidates }
+}
+
+fn layout(bits: u32, byte_order: ByteOrder, word_order: WordOrder) -> Layout {
+    Layout {
+        bits,
+        byte_order,
+        word_order,
+    }
+}
+
+/// Score the words of `sample` read in `layout`, each word's bytes turned into its form and
+/// value by `read`
+fn score(sample: &[u8], layout: Layout, read: impl Fn(&[u8]) -> (Form, f64)) -> Option<Candidate> {
+    let (mut words, mut non_zero, mut plausible) = (0, 0, 0);
+    for bytes in sample.chunks_exact(layout.bits as usize / 8) {
+        words += 1;
+        match read(bytes) {
+            (Form::Zero, _) => {}
+            (form, value) => {
+                non_zero += 1;
+                if form == Form::Normalized && (1e-9..=1e9).contains(&value.abs()) {
+                    plausible += 1;
+                }
+            }
+        }
+    }
+    (words > 0).then(|| Candidate {
+        layout,
+        score: if non_zero == 0 {
+            0.0
+        } else {
+            plausible as f64 / non_zero as f64
+        },
+        words,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extended;
+
+    #[test]
+    fn test_detect_48() {
+        let mut dump = Vec::new();
+        for value in [105.639485637361, -3.75, 1e-3, 42.0, -0.0625, 7.77e7] {
+            dump.extend(&extended::encode(value).to_be_bytes()[2..]);
+        }
+        let guess = detect_layout(&dump);
+        let best = guess.best().unwrap();
+        assert_eq!(
+            best.layout,
+            Layout {
+                bits: 48,
+                byte_order: ByteOrder::BigEndian,
+                word_order: WordOrder::MsWordFirst
+            }
+        );
+        assert_eq!((best.score, best.words), (1.0, 6));
+        assert_eq!(guess.candidates().len(), 10);
+    }
+
+    #[test]
+    fn test_detect_short() {
+        assert_eq!(detect_layout(&[0x40]).best(), None);
+        let guess = detect_layout(&[0, 0]);
+        assert_eq!(guess.candidates().len(), 2);
+        assert_eq!(guess.best().unwrap().score, 0.0);
+    }
+}
@@ -0,0 +1,215 @@
+//! # Memory diffs
+//! Compare two blocks of 1750A memory words field by field and report the values that changed.
+//!
+//! A field has changed when any of its words differ. Each [`Change`] carries both values, the
+//! difference between them and that difference in ULPs: the value of the least significant
+//! mantissa bit of the `before` word for floating point fields, or of the LSB for fixed-point
+//! and integer fields. Spare fields are not compared.
+//!
+//! ```
+//! use mil1750a_converter::diff;
+//! use mil1750a_converter::schema::FieldFormat;
+//!
+//! let before = [0x4000, 0x0001, 0x6000, 0x0001];
+//! let after = [0x4000, 0x0001, 0x6000, 0x0101];
+//! let diff = diff::diff_strided(FieldFormat::F32, 2, &before, &after).unwrap();
+//! assert_eq!(diff.len(), 1);
+//! assert_eq!(diff.changes()[0].offset, 2);
+//! assert_eq!(diff.changes()[0].ulps, 1.0);
+//! ```
+
+use crate::arith;
+use crate::schema::{FieldFormat, Schema, SchemaError, Value};
+use std::fmt;
+
+/// A field whose words differ between the two blocks
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// Name of the field, or `[n]` for element `n` of a strided diff
+    pub name: String,
+    /// Word offset of the field
+    pub offset: usize,
+    /// The field's words in the first block, most significant first, in the low bits
+    pub before_word: u64,
+    /// The field's words in the second block
+    pub after_word: u64,
+    /// The value in the first block
+    pub before: Value,
+    /// The value in the second block
+    pub after: Value,
+    /// `after - before`
+    pub delta: f64,
+    /// `delta` in units of the least significant bit of `before`
+    pub ulps: f64,
+}
+
+/// The changed fields of two blocks, in schema order
+///
+/// [`Display`](fmt::Display) prints one aligned line per change.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+    changes: Vec<Change>,
+}
+
+impl Diff {
+    /// The changed fields
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Number of changed fields
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Whether no field changed
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .changes
+            .iter()
+            .map(|change| change.name.len())
+            .max()
+            .unwrap_or(0);
+        for change in &self.changes {
+            writeln!(
+                f,
+                "{:width$}  @{:<5} {:#x} -> {:#x}  {} -> {}  ({:+}, {:+} ulp)",
+                change.name,
+                change.offset,
+                change.before_word,
+                change.after_word,
+                change.before.to_f64(),
+                change.after.to_f64(),
+                change.delta,
+                change.ulps,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare the fields of `schema` in two blocks
+pub fn diff(schema: &Schema, before: &[u16], after: &[u16]) -> Result<Diff, SchemaError> {
+    let (before_frame, after_frame) = (schema.decode(before)?, schema.decode(after)?);
+    let join = |words: &[u16]| {
+        words
+            .iter()
+            .fold(0u64, |acc, &word| acc << 16 | u64::from(word))
+    };
+
+    let mut changes = Vec::new();
+    for field in schema.fields() {
+        let range = field.offset..field.offset + field.format.words();
+        let (before_word, after_word) = (join(&before[range.clone()]), join(&after[range]));
+        if before_word == after_word {
+            continue;
+        }
+        let (Some(before), Some(after)) =
+            (before_frame.get(&field.name), after_frame.get(&field.name))
+        else {
+            continue;
+        };
+
+        let lsb = match field.format {
+            #[cfg(feature = "half")]
+            FieldFormat::F16 => arith::ulp16(before_word as u16),
+            FieldFormat::F32 => arith::ulp32(before_word as u32),
+            FieldFormat::F48 => arith::ulp48(before_word),
+            FieldFormat::Fixed { bscale } => 2f64.powi(bscale - 15),
+            FieldFormat::Fixed32 { bscale } => 2f64.powi(bscale - 31),
+            _ => 1.0,
+        };
+        // Calibrated values change by the gain for every raw step
+        let lsb = match &field.calibration {
+            Some(calibration) => lsb * calibration.gain.abs(),
+            None => lsb,
+        };
+        let delta = after.to_f64() - before.to_f64();
+        changes.push(Change {
+            name: field.name.clone(),
+            offset: field.offset,
+            before_word,
+            after_word,
+            before,
+            after,
+            delta,
+            ulps: delta / lsb,
+        });
+    }
+    Ok(Diff { changes })
+}
+
+/// Compare two blocks read as `format` values every `stride` words, until the shorter block
+/// runs out
+pub fn diff_strided(
+    format: FieldFormat,
+    stride: usize,
+    before: &[u16],
+    after: &[u16],
+) -> Result<Diff, SchemaError> {
+    let len = before.len().min(after.len());
+    let schema = (0..)
+        .map(|index| index * stride.max(1))
+        .take_while(|offset| offset + format.words() <= len)
+        .enumerate()
+        .fold(Schema::new(), |schema, (index, offset)| {
+            schema.field(format!("[{index}]"), offset, format)
+        });
+    diff(&schema, before, after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let schema = Schema::new()
+            .field("single", 0, FieldFormat::F32)
+            .field("count", 2, FieldFormat::Int16)
+            .field("spare", 3, FieldFormat::Spare { words: 1 })
+            .field("fixed", 4, FieldFormat::Fixed { bscale: 4 });
+        let before = [0x4000, 0x0001, 0x0005, 0xDEAD, 0x6200];
+        let after = [0x4000, 0x0002, 0xFFFF, 0xBEEF, 0x6200];
+
+        let diff = diff(&schema, &before, &after).unwrap();
+        assert_eq!(diff.len(), 2);
+        let single = &diff.changes()[0];
+        assert_eq!((single.name.as_str(), single.offset), ("single", 0));
+        assert_eq!(
+            (single.before_word, single.after_word),
+            (0x40000001, 0x40000002)
+        );
+        assert_eq!((single.delta, single.ulps), (1.0, 2f64.powi(22)));
+        let count = &diff.changes()[1];
+        assert_eq!(
+            (count.before, count.after),
+            (Value::Int16(5), Value::Int16(-1))
+        );
+        assert_eq!(count.ulps, -6.0);
+
+        assert_eq!(
+            diff.to_string(),
+            "single  @0     0x40000001 -> 0x40000002  1 -> 2  (+1, +4194304 ulp)\n\
+             count   @2     0x5 -> 0xffff  5 -> -1  (-6, -6 ulp)\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_strided() {
+        let before = [0x6344, 0x4001, 0x0000];
+        let after = [0x6344, 0x4041, 0x0000];
+        let diff = diff_strided(FieldFormat::Int16, 1, &before, &after[..2]).unwrap();
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff.changes()[0].name, "[1]");
+        assert!(diff_strided(FieldFormat::F48, 3, &[], &[])
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,301 @@
+//! # Errors
+//! The error types shared by the fallible conversions and parsers.
+
+use crate::arith::Form;
+use std::fmt;
+use std::num::ParseFloatError;
+
+/// The format a value or word was being converted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum TargetFormat {
+    /// The 16-bit floating point format
+    Float16,
+    /// The 32-bit floating point format
+    Float32,
+    /// The 48-bit extended floating point format
+    Float48,
+    /// A 16-bit fixed point word, see [`fixed`](crate::fixed)
+    Fixed16,
+    /// A 32-bit fixed point word, see [`fixed`](crate::fixed)
+    Fixed32,
+    /// An integer or bitfield of a [`schema`](crate::schema) field
+    Integer {
+        /// Width of the field in bits
+        bits: u32,
+        /// Whether the field is two's complement
+        signed: bool,
+    },
+}
+
+impl TargetFormat {
+    /// Width of a word of the format in bits
+    pub const fn bits(self) -> u32 {
+        match self {
+            TargetFormat::Float16 | TargetFormat::Fixed16 => 16,
+            TargetFormat::Float32 | TargetFormat::Fixed32 => 32,
+            TargetFormat::Float48 => 48,
+            TargetFormat::Integer { bits, .. } => bits,
+        }
+    }
+}
+
+impl fmt::Display for TargetFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits = self.bits();
+        match self {
+            TargetFormat::Float16 | TargetFormat::Float32 | TargetFormat::Float48 => {
+                write!(f, "{bits}-bit MIL-1750A float")
+            }
+            TargetFormat::Fixed16 | TargetFormat::Fixed32 => {
+                write!(f, "{bits}-bit MIL-1750A fixed point word")
+            }
+            TargetFormat::Integer { signed: true, .. } => write!(f, "{bits}-bit signed integer"),
+            TargetFormat::Integer { signed: false, .. } => {
+                write!(f, "{bits}-bit unsigned integer")
+            }
+        }
+    }
+}
+
+/// Reasons a value or word cannot be converted, with the input and the format it was being
+/// converted to
+///
+/// ```
+/// use mil1750a_converter::{Mil1750Error, Mil32, TargetFormat};
+///
+/// match Mil32::try_from_f32(f32::MAX) {
+///     Err(Mil1750Error::OutOfRange { value, target }) => {
+///         assert_eq!(value, f32::MAX as f64);
+///         assert_eq!(target, TargetFormat::Float32);
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum Mil1750Error {
+    /// The value is NaN or infinite, which MIL-1750A cannot represent
+    #[error("{value} cannot be represented as a {target}")]
+    NotFinite {
+        /// The offending value
+        value: f64,
+        /// The format it was being converted to
+        target: TargetFormat,
+    },
+    /// The value's magnitude is too large or too small for the target format
+    #[error("{value} is outside the range of a {target}")]
+    OutOfRange {
+        /// The offending value
+        value: f64,
+        /// The format it was being converted to
+        target: TargetFormat,
+    },
+    /// The word has bits set above the width of the format
+    #[error("{word:#X} does not fit in a {target}")]
+    WordTooWide {
+        /// The offending word
+        word: u64,
+        /// The format it was being wrapped as
+        target: TargetFormat,
+    },
+    /// A mantissa is too wide for the mantissa field of the format
+    #[error("mantissa {mantissa} does not fit in a {target}")]
+    MantissaTooWide {
+        /// The offending mantissa, as a signed integer
+        mantissa: i64,
+        /// The format of the word it was being set in
+        target: TargetFormat,
+    },
+    /// An exponent is outside the range of the exponent field of the format
+    #[error("exponent {exponent} does not fit in a {target}")]
+    ExponentTooWide {
+        /// The offending exponent
+        exponent: i8,
+        /// The format of the word it was being set in
+        target: TargetFormat,
+    },
+    /// A slice of bytes or 16-bit words is not the length of a word of the format
+    #[error("{len} elements cannot hold a {target}, which takes {expected}")]
+    WrongLength {
+        /// Length of the slice
+        len: usize,
+        /// Length a word of the format takes, in the slice's elements
+        expected: usize,
+        /// The format it was being read as
+        target: TargetFormat,
+    },
+}
+
+impl Mil1750Error {
+    /// The format the value or word was being converted to
+    pub fn target(&self) -> TargetFormat {
+        match *self {
+            Mil1750Error::NotFinite { target, .. }
+            | Mil1750Error::OutOfRange { target, .. }
+            | Mil1750Error::WordTooWide { target, .. }
+            | Mil1750Error::MantissaTooWide { target, .. }
+            | Mil1750Error::ExponentTooWide { target, .. }
+            | Mil1750Error::WrongLength { target, .. } => target,
+        }
+    }
+
+    /// The value that could not be converted, if the input was a value rather than a word
+    pub fn value(&self) -> Option<f64> {
+        match *self {
+            Mil1750Error::NotFinite { value, .. } | Mil1750Error::OutOfRange { value, .. } => {
+                Some(value)
+            }
+            Mil1750Error::WordTooWide { .. }
+            | Mil1750Error::MantissaTooWide { .. }
+            | Mil1750Error::ExponentTooWide { .. }
+            | Mil1750Error::WrongLength { .. } => None,
+        }
+    }
+}
+
+/// Reasons a word is rejected by a strict decoder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The mantissa is not zero, but its two most significant bits are equal
+    #[error("{bits}-bit MIL-1750A word {word:#X} is not normalized")]
+    Unnormalized {
+        /// The offending word
+        word: u64,
+        /// Width of the format in bits
+        bits: u32,
+    },
+    /// The mantissa is zero but the exponent is not; zero must be encoded as all-zero bits
+    #[error("{bits}-bit MIL-1750A word {word:#X} is a zero with a non-zero exponent")]
+    NonZeroExponent {
+        /// The offending word
+        word: u64,
+        /// Width of the format in bits
+        bits: u32,
+    },
+}
+
+impl DecodeError {
+    /// Check the form of a `bits`-wide word
+    pub(crate) fn check(form: Form, word: u64, bits: u32) -> Result<(), DecodeError> {
+        match form {
+            Form::Normalized | Form::Zero => Ok(()),
+            Form::Unnormalized => Err(DecodeError::Unnormalized { word, bits }),
+            Form::DirtyZero => Err(DecodeError::NonZeroExponent { word, bits }),
+        }
+    }
+}
+
+/// Reasons a buffer of words cannot be decoded, with the position of the offending word
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum BufferError {
+    /// A word is rejected by the strict decoder
+    #[error("word {index} at byte offset {byte_offset}: {error}")]
+    Decode {
+        /// Index of the word in the buffer
+        index: usize,
+        /// Byte offset of the word in the buffer
+        byte_offset: usize,
+        /// Why the word was rejected
+        #[source]
+        error: DecodeError,
+    },
+    /// The buffer ends partway through a word
+    #[error("word {index} at byte offset {byte_offset} is truncated to {len} bytes")]
+    Truncated {
+        /// Index the incomplete word would have
+        index: usize,
+        /// Byte offset of the incomplete word
+        byte_offset: usize,
+        /// Number of bytes left for it
+        len: usize,
+    },
+}
+
+impl BufferError {
+    /// Index of the offending word in the buffer
+    pub fn index(&self) -> usize {
+        match *self {
+            BufferError::Decode { index, .. } | BufferError::Truncated { index, .. } => index,
+        }
+    }
+
+    /// Byte offset of the offending word in the buffer
+    pub fn byte_offset(&self) -> usize {
+        match *self {
+            BufferError::Decode { byte_offset, .. }
+            | BufferError::Truncated { byte_offset, .. } => byte_offset,
+        }
+    }
+
+    /// The same error for a buffer that starts `words` words and `bytes` bytes earlier
+    pub(crate) fn offset_by(self, words: usize, bytes: usize) -> Self {
+        match self {
+            BufferError::Decode {
+                index,
+                byte_offset,
+                error,
+            } => BufferError::Decode {
+                index: index + words,
+                byte_offset: byte_offset + bytes,
+                error,
+            },
+            BufferError::Truncated {
+                index,
+                byte_offset,
+                len,
+            } => BufferError::Truncated {
+                index: index + words,
+                byte_offset: byte_offset + bytes,
+                len,
+            },
+        }
+    }
+}
+
+/// Reasons a string cannot be parsed as a MIL-1750A word
+///
+/// Decimal strings are values to encode; strings prefixed with `0x` or `0b` are raw words.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The string, or the digits after a `0x` or `0b` prefix, is empty
+    #[error("cannot parse a MIL-1750A word from an empty string")]
+    Empty,
+    /// A character of a hexadecimal or binary word is not a digit in that notation
+    #[error("invalid {} digit {digit:?} at offset {index}", notation(*radix))]
+    InvalidDigit {
+        /// The offending character
+        digit: char,
+        /// Its byte offset in the string
+        index: usize,
+        /// Radix of the notation, 16 or 2
+        radix: u32,
+    },
+    /// A hexadecimal or binary word has more significant bits than the format
+    #[error("word does not fit in a {bits}-bit MIL-1750A word")]
+    TooWide {
+        /// Width of the format in bits
+        bits: u32,
+    },
+    /// A decimal string is not a valid number
+    #[error("invalid decimal number: {0}")]
+    InvalidNumber(#[from] ParseFloatError),
+    /// A decimal number cannot be encoded in the format
+    #[error("{0}")]
+    Value(#[from] Mil1750Error),
+}
+
+/// The name of the notation of a `radix`, for messages
+fn notation(radix: u32) -> &'static str {
+    if radix == 16 {
+        "hexadecimal"
+    } else {
+        "binary"
+    }
+}
@@ -0,0 +1,287 @@
+//! # Word explanations
+//! Take a word apart into its bit fields and show how its value is built from them, for
+//! teaching the format and for triaging encodings that disagree.
+//!
+//! [`explain16`], [`explain32`] and [`explain48`] give an [`Explanation`]: the [`BitField`]s of
+//! the word, numbered from the most significant bit as bit 0, as MIL-STD-1750A numbers them,
+//! with the raw bits of each, so a front end can colour them; the mantissa and exponent they
+//! hold, both two's complement; and the [steps](Explanation::steps) from those to the value.
+//! The sign is the top bit of the mantissa, and in the 48-bit format the mantissa continues in
+//! the extension word after the exponent. [`Display`](fmt::Display) prints the bits grouped by
+//! field, then the steps.
+//!
+//! ```
+//! use mil1750a_converter::explain::{explain32, FieldKind};
+//!
+//! let explanation = explain32(0x9FFFFF04);
+//! assert_eq!(explanation.fields[2].kind, FieldKind::Exponent);
+//! assert_eq!((explanation.fields[2].first, explanation.fields[2].last), (24, 31));
+//! assert_eq!(explanation.exponent, 4);
+//! assert_eq!(explanation.fraction(), -0.7500001192092896);
+//! assert_eq!(
+//!     explanation.to_string(),
+//!     "1|00111111111111111111111|00000100\n\
+//!      mantissa: bits 0-23 = 0x9FFFFF = -6291457, as a fraction -6291457 / 2^23 = -0.7500001192092896\n\
+//!      exponent: bits 24-31 = 0x04 = 4\n\
+//!      value: -0.7500001192092896 x 2^4 = -12.000001907348633\n"
+//! );
+//! ```
+
+use crate::arith::{self, Unpacked};
+use std::fmt;
+
+/// What a [`BitField`] holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldKind {
+    /// The sign, the most significant bit of the mantissa
+    Sign,
+    /// The rest of the mantissa, or its most significant part in the 48-bit format
+    Mantissa,
+    /// The exponent
+    Exponent,
+    /// The least significant 16 bits of the 48-bit mantissa
+    Extension,
+}
+
+/// A run of bits of a word
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitField {
+    /// What the bits hold
+    pub kind: FieldKind,
+    /// The first bit, counting from the most significant bit of the word as bit 0
+    pub first: u32,
+    /// The last bit, inclusive
+    pub last: u32,
+    /// The bits, in the low bits
+    pub raw: u64,
+}
+
+impl BitField {
+    /// Number of bits in the field
+    pub const fn width(&self) -> u32 {
+        self.last - self.first + 1
+    }
+}
+
+/// A word taken apart, see the [module documentation](self)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// The word, in the low bits
+    pub word: u64,
+    /// Width of the word in bits
+    pub bits: u32,
+    /// The fields of the word, most significant first
+    pub fields: Vec<BitField>,
+    /// The whole mantissa as a two's-complement integer, sign and extension included
+    pub mantissa: i64,
+    /// Number of fraction bits of the mantissa, so it is worth `mantissa / 2^fraction_bits`
+    pub fraction_bits: u32,
+    /// The exponent
+    pub exponent: i32,
+    /// The value of the word, `fraction() * 2^exponent`
+    pub value: f64,
+}
+
+impl Explanation {
+    fn new(word: u64, bits: u32, layout: &[(FieldKind, u32)], unpacked: Unpacked) -> Self {
+        let mut first = 0;
+        let fields = layout
+            .iter()
+            .map(|&(kind, width)| {
+                let field = BitField {
+                    kind,
+                    first,
+                    last: first + width - 1,
+                    raw: word >> (bits - first - width) & ((1 << width) - 1),
+                };
+                first += width;
+                field
+            })
+            .collect();
+        let fraction_bits = layout
+            .iter()
+            .filter(|(kind, _)| *kind != FieldKind::Exponent)
+            .map(|&(_, width)| width)
+            .sum::<u32>()
+            - 1;
+        Explanation {
+            word,
+            bits,
+            fields,
+            mantissa: unpacked.mantissa,
+            fraction_bits,
+            exponent: unpacked.exponent,
+            value: unpacked.mantissa as f64 * 2f64.powi(unpacked.exponent - fraction_bits as i32),
+        }
+    }
+
+    /// The mantissa as a fraction, from -1 up to but not including 1
+    pub fn fraction(&self) -> f64 {
+        self.mantissa as f64 / 2f64.powi(self.fraction_bits as i32)
+    }
+
+    /// The reconstruction of the value from the fields, one line per step: the mantissa, the
+    /// exponent and their product
+    pub fn steps(&self) -> Vec<String> {
+        let ranges = |kinds: &[FieldKind]| {
+            self.fields
+                .iter()
+                .filter(|field| kinds.contains(&field.kind))
+                .map(|field| (field.first, field.last))
+                .fold(Vec::<(u32, u32)>::new(), |mut ranges, (first, last)| {
+                    match ranges.last_mut() {
+                        Some(range) if range.1 + 1 == first => range.1 = last,
+                        _ => ranges.push((first, last)),
+                    }
+                    ranges
+                })
+                .iter()
+                .map(|(first, last)| format!("{first}-{last}"))
+                .collect::<Vec<_>>()
+                .join(" and ")
+        };
+        let mantissa_bits = self.fraction_bits + 1;
+        let exponent_bits = self.bits - mantissa_bits;
+        let mantissa_digits = mantissa_bits.div_ceil(4) as usize;
+        let exponent_digits = exponent_bits.div_ceil(4) as usize;
+        let mantissa_raw = self.mantissa as u64 & ((1 << mantissa_bits) - 1);
+        let exponent_raw = self.exponent as u64 & ((1 << exponent_bits) - 1);
+        vec![
+            format!(
+                "mantissa: bits {} = 0x{mantissa_raw:0mantissa_digits$X} = {}, as a fraction {} / \
+                 2^{} = {}",
+                ranges(&[FieldKind::Sign, FieldKind::Mantissa, FieldKind::Extension]),
+                self.mantissa,
+                self.mantissa,
+                self.fraction_bits,
+                self.fraction(),
+            ),
+            format!(
+                "exponent: bits {} = 0x{exponent_raw:0exponent_digits$X} = {}",
+                ranges(&[FieldKind::Exponent]),
+                self.exponent,
+            ),
+            format!(
+                "value: {} x 2^{} = {}",
+                self.fraction(),
+                self.exponent,
+                self.value
+            ),
+        ]
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, field) in self.fields.iter().enumerate() {
+            if index > 0 {
+                write!(f, "|")?;
+            }
+            write!(f, "{:0width$b}", field.raw, width = field.width() as usize)?;
+        }
+        writeln!(f)?;
+        for step in self.steps() {
+            writeln!(f, "{step}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Take a 16-bit word apart: sign in bit 0, mantissa in bits 1 to 9, exponent in bits 10 to 15
+pub fn explain16(word: u16) -> Explanation {
+    let layout = [
+        (FieldKind::Sign, 1),
+        (FieldKind::Mantissa, 9),
+        (FieldKind::Exponent, 6),
+    ];
+    Explanation::new(word.into(), 16, &layout, arith::unpack16(word))
+}
+
+/// Take a 32-bit word apart: sign in bit 0, mantissa in bits 1 to 23, exponent in bits 24 to 31
+pub fn explain32(word: u32) -> Explanation {
+    let layout = [
+        (FieldKind::Sign, 1),
+        (FieldKind::Mantissa, 23),
+        (FieldKind::Exponent, 8),
+    ];
+    Explanation::new(word.into(), 32, &layout, arith::unpack32(word))
+}
+
+/// Take the low 48 bits of a word apart: sign in bit 0, mantissa in bits 1 to 23, exponent in
+/// bits 24 to 31 and the rest of the mantissa in bits 32 to 47
+pub fn explain48(word: u64) -> Explanation {
+    let word = word & 0xFFFF_FFFF_FFFF;
+    let layout = [
+        (FieldKind::Sign, 1),
+        (FieldKind::Mantissa, 23),
+        (FieldKind::Exponent, 8),
+        (FieldKind::Extension, 16),
+    ];
+    Explanation::new(word, 48, &layout, arith::unpack48(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain32() {
+        // Examples of the standard
+        for word in [
+            0x7FFFFF7F, 0x4000007F, 0x50000004, 0x40000001, 0x40000000, 0x400000FF, 0x40000080,
+            0x00000000, 0x80000000, 0xBFFFFF80, 0x9FFFFF04,
+        ] {
+            let explanation = explain32(word);
+            assert_eq!(explanation.value, arith::value32(word), "{word:#010X}");
+            let fields: Vec<_> = explanation.fields.iter().map(|field| field.raw).collect();
+            let joined = fields[0] << 31 | fields[1] << 8 | fields[2];
+            assert_eq!(joined, u64::from(word));
+        }
+        let explanation = explain32(0x50000004);
+        assert_eq!((explanation.mantissa, explanation.exponent), (0x500000, 4));
+        assert_eq!(explanation.fraction(), 0.625);
+        assert_eq!(explanation.value, 10.0);
+        assert_eq!(explain32(0x400000FF).exponent, -1);
+        assert_eq!(explain32(0x80000000).fields[0].raw, 1);
+    }
+
+    #[test]
+    fn test_explain48() {
+        let explanation = explain48(0xA00000FF0000);
+        assert_eq!(explanation.fraction(), -0.75);
+        assert_eq!(explanation.exponent, -1);
+        assert_eq!(explanation.value, -0.375);
+        assert_eq!(explanation.fields[3].kind, FieldKind::Extension);
+        assert_eq!(
+            (explanation.fields[3].first, explanation.fields[3].last),
+            (32, 47)
+        );
+        assert_eq!(
+            explanation.steps()[0],
+            "mantissa: bits 0-23 and 32-47 = 0xA000000000 = -412316860416, as a fraction \
+             -412316860416 / 2^39 = -0.75"
+        );
+        assert_eq!(explanation.steps()[1], "exponent: bits 24-31 = 0xFF = -1");
+        let explanation = explain48(0x69A3B50754AB);
+        assert_eq!(explanation.value, 105.63948563742451);
+        assert_eq!(explanation.fields[3].raw, 0x54AB);
+    }
+
+    #[test]
+    fn test_explain16() {
+        let explanation = explain16(0x6344);
+        assert_eq!(explanation.value, 12.40625);
+        assert_eq!(
+            (explanation.fields[2].first, explanation.fields[2].last),
+            (10, 15)
+        );
+        assert_eq!(
+            explanation.to_string(),
+            "0|110001101|000100\n\
+             mantissa: bits 0-9 = 0x18D = 397, as a fraction 397 / 2^9 = 0.775390625\n\
+             exponent: bits 10-15 = 0x04 = 4\n\
+             value: 0.775390625 x 2^4 = 12.40625\n"
+        );
+        assert_eq!(explain16(0x8000).value, -1.0);
+    }
+}
@@ -0,0 +1,326 @@
+//! # Extended precision
+//! The 48-bit MIL-1750A floating point format: the upper 24 mantissa bits, the 8-bit exponent,
+//! then the lower 16 mantissa bits.
+//!
+//! The 40 mantissa bits are a single two's-complement fraction, its sign in the top bit of the
+//! first 16-bit word and its least significant bits in the third, so the lower bits of a
+//! negative mantissa are not a magnitude of their own. [`decode`] keeps the original reading of
+//! them as unsigned; [`decode_with`] in [`DecodeMode::TwosComplement`] and the `strict` and
+//! `lenient` decoders follow the standard, as does [`encode_with`] in
+//! [`EncodeMode::TwosComplement`].
+
+use crate::{arith, DecodeError, DecodeMode, Decoded, EncodeMode, Lenient};
+
+/// Transform 48-bit floating point number to MIL-1750A Hex
+///
+/// # Arguments
+///
+/// * `input`: number as 48-bit (actually f64) floating point
+///
+/// returns: u64 interpret as hex
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::extended;
+/// assert_eq!(extended::encode(105.639485637361), 0x69A3B50754AB);
+/// ```
+pub fn encode(input: f64) -> u64 {
+    if input == 0.0 {
+        return 0;
+    }
+
+    let mut exponent = input.abs().log2().ceil() as i32;
+    let mut mantissa = (input * 2f64.powi(39 - exponent)).round() as i64;
+
+    // Boundary check
+    if mantissa == 549755813888 {
+        mantissa /= 2;
+        exponent += 1;
+    }
+
+    let mantissa1 = ((mantissa >> 16) & 0xFFFFFF) as u32;
+    let mantissa2 = (mantissa & 0xFFFF) as u16;
+    let exponent = exponent as u8;
+
+    let mut result = (mantissa1 as u64) << 24;
+    result |= (exponent as u64) << 16;
+    result |= mantissa2 as u64;
+
+    if input.is_sign_negative() {
+        result |= 0x800000000000;
+    }
+
+    result
+}
+
+/// Transform a f64 to 48-bit MIL-1750A in the given [`EncodeMode`]
+///
+/// [`EncodeMode::Legacy`] gives the same word as [`encode`], whose `f64` scaling and exponent
+/// estimate can miss the nearest word. [`EncodeMode::TwosComplement`] shifts and rounds the
+/// `f64` significand in 128-bit integer arithmetic, so every input is rounded exactly once to
+/// the nearest normalized word, ties away from zero, and saturates values too large for the
+/// format.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{extended, EncodeMode};
+/// assert_eq!(extended::encode_with(-2.0, EncodeMode::TwosComplement), 0x800000010000);
+/// assert_eq!(extended::encode_with(-0.75, EncodeMode::TwosComplement), 0xA00000000000);
+/// ```
+pub fn encode_with(input: f64, mode: EncodeMode) -> u64 {
+    match mode {
+        EncodeMode::TwosComplement => arith::round48(input),
+        EncodeMode::Legacy => encode(input),
+    }
+}
+
+/// Transform a f64 to 48-bit MIL-1750A, rounding up or down at random
+///
+/// The value rounds away from zero with probability equal to its distance from the nearest
+/// word towards zero, as a fraction of the gap to the next word out, so on average the words
+/// are worth the value itself. `random` is called once per value for a uniformly distributed
+/// `u64`; a seeded generator makes runs reproducible. Values too large for the format
+/// saturate, as in [`EncodeMode::TwosComplement`].
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::extended;
+///
+/// // A xorshift generator, seeded
+/// let mut state = 0x2545F4914F6CDD1Du64;
+/// let mut random = move || {
+///     state ^= state << 13;
+///     state ^= state >> 7;
+///     state ^= state << 17;
+///     state
+/// };
+/// assert_eq!(extended::encode_stochastic(1.0, &mut random), 0x400000010000);
+/// // Halfway between 0x400000010000 and 0x400000010001
+/// let up = (0..1000)
+///     .filter(|_| extended::encode_stochastic(1.0 + 2f64.powi(-39), &mut random) & 1 == 1)
+///     .count();
+/// assert!((400..600).contains(&up));
+/// ```
+pub fn encode_stochastic(input: f64, random: &mut impl FnMut() -> u64) -> u64 {
+    arith::round48_stochastic(input, random())
+}
+
+/// Convert MIL-1750A hex (interpreted as u64) to f48 (as f64)
+///
+/// # Arguments
+///
+/// * `input`:  MIL-1750A hex (interpreted as u64)
+///
+/// returns: f48 representation of the input
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::extended;
+/// assert_eq!(extended::decode(0x69A3B50754AB), 105.63948563742451);
+/// ```
+pub fn decode(input: u64) -> f64 {
+    // The 40 mantissa bits, read unsigned as the original library does, joined into one integer
+    let mantissa = (input >> 8) & 0xFF_FFFF_0000 | input & 0xFFFF;
+    let exponent = (input >> 16) & 0xFF;
+
+    // 2^(exponent - 39) built directly from its bits, always a normal f64
+    let scale = f64::from_bits((exponent + 1023 - 39) << 52);
+    mantissa as f64 * scale
+}
+
+/// Convert a 48-bit MIL-1750A word to f64 in the given [`DecodeMode`]
+///
+/// [`DecodeMode::Legacy`] gives the same value as [`decode`]. [`DecodeMode::TwosComplement`]
+/// reads the 40-bit mantissa and the exponent as two's complement, like [`decode_lenient`].
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{extended, DecodeMode};
+/// // -1 + 2^-39, with the lowest mantissa bit set in the third 16-bit word
+/// let word = 0x800000000001;
+/// assert_eq!(extended::decode_with(word, DecodeMode::TwosComplement), -1.0 + 2f64.powi(-39));
+/// assert_eq!(extended::decode_with(word, DecodeMode::Legacy), 1.0 + 2f64.powi(-39));
+/// ```
+pub fn decode_with(input: u64, mode: DecodeMode) -> f64 {
+    match mode {
+        DecodeMode::TwosComplement => arith::value48(input),
+        DecodeMode::Legacy => decode(input),
+    }
+}
+
+/// Convert a 48-bit MIL-1750A word to f64, rejecting words that are not normalized
+///
+/// Unlike [`decode`], the mantissa and exponent are read as two's complement, as the standard
+/// defines them, and zero must be all-zero bits.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{extended, DecodeError};
+/// assert_eq!(extended::decode_strict(0x800000000000), Ok(-1.0));
+/// assert_eq!(
+///     extended::decode_strict(0x000000010000),
+///     Err(DecodeError::NonZeroExponent { word: 0x000000010000, bits: 48 })
+/// );
+/// ```
+pub fn decode_strict(input: u64) -> Result<f64, DecodeError> {
+    DecodeError::check(arith::form48(input), input, 48)?;
+    Ok(arith::value48(input))
+}
+
+/// Convert a 48-bit MIL-1750A word to f64 as the hardware does, accepting unnormalized words
+///
+/// The value is computed exactly like [`decode_strict`], and [`Lenient::normalized`] records
+/// whether the word would have passed it.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::extended;
+/// let decoded = extended::decode_lenient(0x000000000001);
+/// assert_eq!(decoded.value, 2f64.powi(-39));
+/// assert!(!decoded.normalized);
+/// ```
+pub fn decode_lenient(input: u64) -> Lenient<f64> {
+    Lenient::new(arith::value48(input), arith::form48(input))
+}
+
+/// Convert a 48-bit MIL-1750A word to f64 as [`decode_lenient`] does, keeping the word with the
+/// value
+pub fn decode_traced(input: u64) -> Decoded<u64, f64> {
+    decode_lenient(input).with_raw(input)
+}
+
+#[cfg(test)]
+#[allow(clippy::excessive_precision, clippy::unusual_byte_groupings)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(105.639485637361), 0x69A3B50754AB);
+        assert_eq!(encode(std::f64::consts::PI), 0x6487ED025111);
+        assert_eq!(encode(-std::f64::consts::PI), 0x9B781202AEEF);
+        assert_eq!(encode(-1.0), 0x800000_00_0000);
+        assert_eq!(encode(0.0), 0x000000_00_0000);
+    }
+
+    #[test]
+    fn test_encode_stochastic() {
+        // A fixed sequence, evenly spread over the range of u64
+        let mut counter = 0u64;
+        let mut random = || {
+            counter += 1;
+            counter.wrapping_mul(0x9E3779B97F4A7C15)
+        };
+        // A quarter of the way from -1.0 to the next word out
+        let value = -1.0 - 2f64.powi(-40);
+        let words: Vec<_> = (0..4000)
+            .map(|_| encode_stochastic(value, &mut random))
+            .collect();
+        let down = words.iter().filter(|&&word| word == 0x800000000000).count();
+        let up = words.iter().filter(|&&word| word == 0xBFFFFF01FFFF).count();
+        assert_eq!(down + up, 4000);
+        assert!((900..1100).contains(&up), "{up}");
+
+        assert_eq!(
+            encode_stochastic(f64::INFINITY, &mut random),
+            0x7FFFFF7FFFFF
+        );
+        assert_eq!(encode_stochastic(0.0, &mut random), 0);
+        assert_eq!(
+            crate::half::encode_f32_stochastic(12.40625, &mut random),
+            0x6344
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(0x69A3B50754AB), 105.63948563742451);
+        assert_eq!(decode(0x64A3F4275AAB), 432247429803.0);
+    }
+    #[test]
+    fn test_decode_matches_reference() {
+        let reference = |input: u64| {
+            let mantissa1 = ((input >> 24) & 0xFFFFFF) as u32;
+            let mantissa2 = (input & 0xFFFF) as u16;
+            let exponent = ((input >> 16) & 0xFF) as i32;
+            (mantissa1 as f64) * 2f64.powi(exponent - 23)
+                + (mantissa2 as f64) * 2f64.powi(exponent - 39)
+        };
+        for word in (0..1u64 << 48).step_by(0x1_0000_0001_F) {
+            assert_eq!(
+                decode(word).to_bits(),
+                reference(word).to_bits(),
+                "{word:#X}"
+            );
+        }
+    }
+    #[test]
+    fn test_decode_strict() {
+        assert_eq!(decode_strict(0x69A3B50754AB), Ok(105.63948563742451));
+        assert_eq!(decode_strict(0x9B781202AEEF), Ok(-decode(0x6487ED025111)));
+        assert_eq!(
+            decode_strict(0x100000020000),
+            Err(DecodeError::Unnormalized {
+                word: 0x100000020000,
+                bits: 48
+            })
+        );
+    }
+    #[test]
+    fn test_decode_lenient() {
+        assert_eq!(
+            decode_lenient(0x69A3B50754AB),
+            Lenient {
+                value: 105.63948563742451,
+                normalized: true
+            }
+        );
+        assert_eq!(decode_lenient(0xC00000010000).value, -1.0);
+        assert!(!decode_lenient(0xC00000010000).normalized);
+    }
+    #[test]
+    fn test_decode_with() {
+        for value in [
+            -105.639485637361,
+            -std::f64::consts::PI,
+            -1.5,
+            -2f64.powi(-100),
+        ] {
+            let word = encode_with(value, EncodeMode::TwosComplement);
+            let decoded = decode_with(word, DecodeMode::TwosComplement);
+            assert!(
+                (decoded - value).abs() <= value.abs() * 2f64.powi(-39),
+                "{value}"
+            );
+            assert_ne!(decode_with(word, DecodeMode::Legacy), decoded, "{value}");
+        }
+        assert_eq!(
+            decode_with(0x69A3B50754AB, DecodeMode::TwosComplement),
+            decode(0x69A3B50754AB)
+        );
+    }
+
+    #[test]
+    fn test_encode_with() {
+        let beyond_minus_one = -1.0 - 2f64.powi(-45);
+        assert_eq!(
+            encode_with(beyond_minus_one, EncodeMode::Legacy),
+            0xC00000010000
+        );
+        assert_eq!(
+            encode_with(beyond_minus_one, EncodeMode::TwosComplement),
+            0x800000000000
+        );
+        assert_eq!(
+            encode_with(-std::f64::consts::PI, EncodeMode::TwosComplement),
+            0x9B781202AEEF
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! # C API
+//! `extern "C"` functions for linking the converter from C and C++.
+//!
+//! The `mil1750a-ffi` crate in `ffi/` builds them as a shared and a static library,
+//! `libmil1750a`, with `cargo build --release -p mil1750a-ffi`, and `include/mil1750a.h`
+//! declares them. The header is generated by [cbindgen](https://github.com/mozilla/cbindgen)
+//! with the `cbindgen.toml` in the repository root:
+//! `cbindgen --crate MIL1750A-Converter --output include/mil1750a.h`. Half precision values
+//! cross the boundary as their IEEE 754 bit patterns (`uint16_t`).
+
+#[cfg(feature = "half")]
+use crate::F16;
+use crate::{MilFormat, F32, F48};
+#[cfg(feature = "half")]
+use half::f16;
+use std::slice;
+
+/// Result of a batch conversion
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mil1750aStatus {
+    /// The conversion succeeded
+    Ok = 0,
+    /// An input or output pointer was null while `len` was not zero
+    NullPointer = 1,
+}
+
+/// Transform an IEEE half precision value, given as its bits, to 16-bit MIL-1750A
+#[cfg(feature = "half")]
+#[no_mangle]
+pub extern "C" fn mil1750a_f16_to_1750a(bits: u16) -> u16 {
+    F16::encode(f16::from_bits(bits))
+}
+
+/// Transform a 32-bit floating point number to 32-bit MIL-1750A
+#[no_mangle]
+pub extern "C" fn mil1750a_f32_to_1750a(value: f32) -> u32 {
+    F32::encode(value)
+}
+
+/// Transform a 64-bit floating point number to 48-bit MIL-1750A, in the low 48 bits
+#[no_mangle]
+pub extern "C" fn mil1750a_f48_to_1750a(value: f64) -> u64 {
+    F48::encode(value)
+}
+
+/// Convert a 16-bit MIL-1750A word to the bits of an IEEE half precision value
+#[cfg(feature = "half")]
+#[no_mangle]
+pub extern "C" fn mil1750a_to_16flt(word: u16) -> u16 {
+    F16::decode(word).to_bits()
+}
+
+/// Convert a 32-bit MIL-1750A word to a 32-bit floating point number
+#[no_mangle]
+pub extern "C" fn mil1750a_to_32flt(word: u32) -> f32 {
+    F32::decode(word)
+}
+
+/// Convert a 48-bit MIL-1750A word, in the low 48 bits, to a 64-bit floating point number
+#[no_mangle]
+pub extern "C" fn mil1750a_to_48flt(word: u64) -> f64 {
+    F48::decode(word)
+}
+
+/// Apply `convert` element-wise from `input` to `output`
+///
+/// # Safety
+///
+/// Unless `len` is zero, `input` and `output` must be valid for `len` elements and must not
+/// overlap.
+unsafe fn batch<I: Copy, O>(
+    input: *const I,
+    output: *mut O,
+    len: usize,
+    convert: impl Fn(I) -> O,
+) -> Mil1750aStatus {
+    if len == 0 {
+        return Mil1750aStatus::Ok;
+    }
+    if input.is_null() || output.is_null() {
+        return Mil1750aStatus::NullPointer;
+    }
+
+    let input = slice::from_raw_parts(input, len);
+    let output = slice::from_raw_parts_mut(output, len);
+    for (value, out) in input.iter().zip(output) {
+        *out = convert(*value);
+    }
+    Mil1750aStatus::Ok
+}
+
+/// Transform `len` IEEE half precision values, given as bits, to 16-bit MIL-1750A
+///
+/// # Safety
+///
+/// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+/// elements.
+#[cfg(feature = "half")]
+#[no_mangle]
+pub unsafe extern "C" fn mil1750a_f16_to_1750a_batch(
+    input: *const u16,
+    output: *mut u16,
+    len: usize,
+) -> Mil1750aStatus {
+    batch(input, output, len, |bits| F16::encode(f16::from_bits(bits)))
+}
+
+/// Transform `len` 32-bit floating point numbers to 32-bit MIL-1750A
+///
+/// # Safety
+///
+/// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+/// elements.
+#[no_mangle]
+pub unsafe extern "C" fn mil1750a_f32_to_1750a_batch(
+    input: *const f32,
+    output: *mut u32,
+    len: usize,
+) -> Mil1750aStatus {
+    batch(input, output, len, F32::encode)
+}
+
+/// Transform `len` 64-bit floating point numbers to 48-bit MIL-1750A
+///
+/// # Safety
+///
+/// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+/// elements.
+#[no_mangle]
+pub unsafe extern "C" fn mil1750a_f48_to_1750a_batch(
+    input: *const f64,
+    output: *mut u64,
+    len: usize,
+) -> Mil1750aStatus {
+    batch(input, output, len, F48::encode)
+}
+
+/// Convert `len` 16-bit MIL-1750A words to IEEE half precision bits
+///
+/// # Safety
+///
+/// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+/// elements.
+#[cfg(feature = "half")]
+#[no_mangle]
+pub unsafe extern "C" fn mil1750a_to_16flt_batch(
+    input: *const u16,
+    output: *mut u16,
+    len: usize,
+) -> Mil1750aStatus {
+    batch(input, output, len, |word| F16::decode(word).to_bits())
+}
+
+/// Convert `len` 32-bit MIL-1750A words to 32-bit floating point numbers
+///
+/// # Safety
+///
+/// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+/// elements.
+#[no_mangle]
+pub unsafe extern "C" fn mil1750a_to_32flt_batch(
+    input: *const u32,
+    output: *mut f32,
+    len: usize,
+) -> Mil1750aStatus {
+    batch(input, output, len, F32::decode)
+}
+
+/// Convert `len` 48-bit MIL-1750A words to 64-bit floating point numbers
+///
+/// # Safety
+///
+/// Unless `len` is zero, `input` and `output` must point to `len` valid, non-overlapping
+/// elements.
+#[no_mangle]
+pub unsafe extern "C" fn mil1750a_to_48flt_batch(
+    input: *const u64,
+    output: *mut f64,
+    len: usize,
+) -> Mil1750aStatus {
+    batch(input, output, len, F48::decode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn test_scalar() {
+        assert_eq!(mil1750a_f32_to_1750a(5.234), 0x53BE7703);
+        assert_eq!(mil1750a_to_32flt(0x40000001), 1.0);
+        assert_eq!(mil1750a_f48_to_1750a(105.639485637361), 0x69A3B50754AB);
+        assert_eq!(mil1750a_to_48flt(0x69A3B50754AB), 105.63948563742451);
+        assert_eq!(mil1750a_to_32flt(0x400000FF), 0.25);
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_scalar_16() {
+        assert_eq!(mil1750a_f16_to_1750a(f16::from_f32(12.4).to_bits()), 0x6344);
+        assert_eq!(mil1750a_to_16flt(0x6344), f16::from_f32(12.40625).to_bits());
+        assert_eq!(mil1750a_f16_to_1750a(f16::ONE.to_bits()), 0x4001);
+        assert_eq!(mil1750a_to_16flt(0x8000), f16::NEG_ONE.to_bits());
+    }
+
+    #[test]
+    fn test_header() {
+        let header = include_str!("../include/mil1750a.h");
+        for function in [
+            "mil1750a_f16_to_1750a(",
+            "mil1750a_f32_to_1750a(",
+            "mil1750a_f48_to_1750a(",
+            "mil1750a_to_16flt(",
+            "mil1750a_to_32flt(",
+            "mil1750a_to_48flt(",
+            "mil1750a_f16_to_1750a_batch(",
+            "mil1750a_f32_to_1750a_batch(",
+            "mil1750a_f48_to_1750a_batch(",
+            "mil1750a_to_16flt_batch(",
+            "mil1750a_to_32flt_batch(",
+            "mil1750a_to_48flt_batch(",
+        ] {
+            assert!(header.contains(function), "{function}");
+        }
+        assert!(header.contains("Mil1750aStatus_NullPointer = 1"));
+    }
+
+    #[test]
+    fn test_batch() {
+        let input = [1.0f32, -1.0, 5.234];
+        let mut output = [0u32; 3];
+        let status = unsafe { mil1750a_f32_to_1750a_batch(input.as_ptr(), output.as_mut_ptr(), 3) };
+        assert_eq!(status, Mil1750aStatus::Ok);
+        assert_eq!(output, [0x40000001, 0x80000000, 0x53BE7703]);
+
+        let mut decoded = [0f32; 3];
+        let status = unsafe { mil1750a_to_32flt_batch(output.as_ptr(), decoded.as_mut_ptr(), 3) };
+        assert_eq!(status, Mil1750aStatus::Ok);
+        assert_eq!(decoded[..2], [1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_batch_null_pointers() {
+        let mut output = [0f64; 1];
+        let status = unsafe { mil1750a_to_48flt_batch(ptr::null(), output.as_mut_ptr(), 1) };
+        assert_eq!(status, Mil1750aStatus::NullPointer);
+        let status = unsafe { mil1750a_to_48flt_batch(ptr::null(), ptr::null_mut(), 0) };
+        assert_eq!(status, Mil1750aStatus::Ok);
+    }
+}
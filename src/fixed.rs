@@ -0,0 +1,133 @@
+//! # Fixed point
+//! MIL-1750A single (16-bit) and double (32-bit) precision two's-complement integers read as
+//! fixed-point values with a binary scale.
+//!
+//! A binary scale `B` puts the binary point `B` bits to the right of the sign bit, so the least
+//! significant bit is worth `2^(B - 15)` in a 16-bit word and `2^(B - 31)` in a 32-bit one, and
+//! the word covers `[-2^B, 2^B)`. `B = 15` (or `31`) gives plain integers.
+
+use crate::{Mil1750Error, TargetFormat};
+
+/// Decode a 16-bit fixed-point word with binary scale `bscale`
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::fixed;
+/// assert_eq!(fixed::decode16(0x4000, 0), 0.5);
+/// assert_eq!(fixed::decode16(0xFFFF, 15), -1.0);
+/// ```
+pub fn decode16(word: u16, bscale: i32) -> f64 {
+    word as i16 as f64 * 2f64.powi(bscale - 15)
+}
+
+/// Encode a value as a 16-bit fixed-point word with binary scale `bscale`, rounding to the
+/// nearest LSB
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{fixed, Mil1750Error};
+/// assert_eq!(fixed::encode16(-0.5, 0), Ok(0xC000));
+/// assert!(matches!(fixed::encode16(1.0, 0), Err(Mil1750Error::OutOfRange { .. })));
+/// ```
+pub fn encode16(value: f64, bscale: i32) -> Result<u16, Mil1750Error> {
+    encode(
+        value,
+        TargetFormat::Fixed16,
+        bscale - 15,
+        i16::MIN.into(),
+        i16::MAX.into(),
+    )
+    .map(|raw| raw as i16 as u16)
+}
+
+/// Decode a 32-bit fixed-point word with binary scale `bscale`
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::fixed;
+/// assert_eq!(fixed::decode32(0x00010000, 15), 1.0);
+/// ```
+pub fn decode32(word: u32, bscale: i32) -> f64 {
+    word as i32 as f64 * 2f64.powi(bscale - 31)
+}
+
+/// Encode a value as a 32-bit fixed-point word with binary scale `bscale`, rounding to the
+/// nearest LSB
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::fixed;
+/// assert_eq!(fixed::encode32(-1.0, 15), Ok(0xFFFF0000));
+/// ```
+pub fn encode32(value: f64, bscale: i32) -> Result<u32, Mil1750Error> {
+    encode(
+        value,
+        TargetFormat::Fixed32,
+        bscale - 31,
+        i32::MIN.into(),
+        i32::MAX.into(),
+    )
+    .map(|raw| raw as i32 as u32)
+}
+
+/// Round `value / 2^lsb` to an integer in `min..=max` for a `target` word
+fn encode(
+    value: f64,
+    target: TargetFormat,
+    lsb: i32,
+    min: i64,
+    max: i64,
+) -> Result<i64, Mil1750Error> {
+    if !value.is_finite() {
+        return Err(Mil1750Error::NotFinite { value, target });
+    }
+    let raw = (value * 2f64.powi(-lsb)).round();
+    if raw < min as f64 || raw > max as f64 {
+        return Err(Mil1750Error::OutOfRange { value, target });
+    }
+    Ok(raw as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_16() {
+        assert_eq!(encode16(12.25, 4), Ok(0x6200));
+        assert_eq!(decode16(0x6200, 4), 12.25);
+        assert_eq!(encode16(-16.0, 4), Ok(0x8000));
+        assert_eq!(encode16(-32768.0, 15), Ok(0x8000));
+        assert_eq!(encode16(32767.4, 15), Ok(0x7FFF));
+        assert_eq!(
+            encode16(32767.5, 15),
+            Err(Mil1750Error::OutOfRange {
+                value: 32767.5,
+                target: TargetFormat::Fixed16
+            })
+        );
+        assert!(matches!(
+            encode16(f64::NAN, 0),
+            Err(Mil1750Error::NotFinite { .. })
+        ));
+    }
+
+    #[test]
+    fn test_32() {
+        assert_eq!(encode32(3.0, 31), Ok(3));
+        assert_eq!(decode32(0x80000000, 0), -1.0);
+        assert_eq!(encode32(2f64.powi(-31), 0), Ok(1));
+        assert_eq!(encode32(-1.0, 0), Ok(0x80000000));
+        assert_eq!(
+            encode32(1.0, 0),
+            Err(Mil1750Error::OutOfRange {
+                value: 1.0,
+                target: TargetFormat::Fixed32
+            })
+        );
+    }
+}
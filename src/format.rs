@@ -0,0 +1,464 @@
+//! # Formats
+//! The [`MilFormat`] trait and its marker types [`F16`], [`F32`] and [`F48`], for writing
+//! conversion code once and instantiating it for each MIL-1750A format.
+
+use crate::profile::TargetProfile;
+#[cfg(feature = "half")]
+use crate::Mil16;
+use crate::{extended, single};
+use crate::{DecodeError, Decoded, Mil1750Error, Mil32, Mil48};
+#[cfg(feature = "half")]
+use half::f16;
+use std::fmt::Debug;
+
+/// A MIL-1750A word format, linking its raw word type to the IEEE type it converts to
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{MilFormat, F32, F48};
+///
+/// fn decode_all<F: MilFormat>(bytes: &[u8]) -> Vec<F::Ieee> {
+///     bytes.chunks_exact(F::BYTES).map(|word| F::decode(F::read_be(word))).collect()
+/// }
+///
+/// assert_eq!(decode_all::<F32>(&[0x40, 0x00, 0x00, 0x01]), [1.0]);
+/// assert_eq!(decode_all::<F48>(&[0x40, 0x00, 0x00, 0x01, 0x00, 0x00]), [1.0]);
+/// ```
+pub trait MilFormat {
+    /// The raw word, in the low bits of an unsigned integer
+    type Bits: Copy + Eq + Debug;
+    /// The IEEE floating point type the format converts to and from
+    type Ieee: Copy + PartialEq + Debug;
+    /// The typed wrapper for a word of this format
+    type Word: Copy + Eq + Debug;
+
+    /// Width of a word in bits
+    const BITS: u32;
+    /// Width of a word in bytes
+    const BYTES: usize;
+
+    /// Encode a value, rounding to the nearest normalized word and saturating, as
+    /// [`EncodeMode::TwosComplement`] does
+    fn encode(value: Self::Ieee) -> Self::Bits {
+        Self::encode_with(value, EncodeMode::TwosComplement)
+    }
+
+    /// Decode a word as the standard defines it, accepting unnormalized words, as
+    /// [`DecodeMode::TwosComplement`] does
+    fn decode(bits: Self::Bits) -> Self::Ieee {
+        Self::decode_with(bits, DecodeMode::TwosComplement)
+    }
+
+    /// Decode a word, rejecting those that are not normalized
+    fn decode_strict(bits: Self::Bits) -> Result<Self::Ieee, DecodeError>;
+
+    /// Decode a word as [`decode`](Self::decode) does, keeping the word with the value and
+    /// noting whether [`decode_strict`](Self::decode_strict) would accept it
+    fn decode_traced(bits: Self::Bits) -> Decoded<Self::Bits, Self::Ieee> {
+        Decoded {
+            raw: bits,
+            value: Self::decode(bits),
+            valid: Self::decode_strict(bits).is_ok(),
+        }
+    }
+
+    /// Encode a value in the given mode
+    fn encode_with(value: Self::Ieee, mode: EncodeMode) -> Self::Bits;
+
+    /// Decode a word in the given mode
+    fn decode_with(bits: Self::Bits, mode: DecodeMode) -> Self::Ieee;
+
+    /// Encode a value, rejecting those the format cannot represent
+    fn try_encode(value: Self::Ieee) -> Result<Self::Bits, Mil1750Error>;
+
+    /// Encode a value for a target, rounding it and handling overflow as `profile` says
+    fn encode_for(value: Self::Ieee, profile: &TargetProfile) -> Result<Self::Bits, Mil1750Error>;
+
+    /// Decode a word of a target, reading its mantissa as the `profile`'s dialect does
+    fn decode_for(bits: Self::Bits, profile: &TargetProfile) -> Self::Ieee;
+
+    /// Wrap a raw word
+    fn to_word(bits: Self::Bits) -> Self::Word;
+
+    /// The raw word of a wrapper
+    fn from_word(word: Self::Word) -> Self::Bits;
+
+    /// Read a word from the first [`BYTES`](Self::BYTES) bytes of `bytes`, big-endian
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than a word.
+    fn read_be(bytes: &[u8]) -> Self::Bits;
+
+    /// Write a word to the first [`BYTES`](Self::BYTES) bytes of `out`, big-endian
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is shorter than a word.
+    fn write_be(bits: Self::Bits, out: &mut [u8]);
+
+    /// Reverse the order of the 16-bit words making up a word
+    fn swap_words(bits: Self::Bits) -> Self::Bits;
+
+    /// Read a word from the first [`BYTES`](Self::BYTES) bytes of `bytes`, its 16-bit words in
+    /// `order` and each big-endian
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than a word.
+    fn read_ordered(bytes: &[u8], order: WordOrder) -> Self::Bits {
+        order.arrange::<Self>(Self::read_be(bytes))
+    }
+
+    /// Write a word to the first [`BYTES`](Self::BYTES) bytes of `out`, its 16-bit words in
+    /// `order` and each big-endian
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is shorter than a word.
+    fn write_ordered(bits: Self::Bits, order: WordOrder, out: &mut [u8]) {
+        Self::write_be(order.arrange::<Self>(bits), out)
+    }
+}
+
+/// The order of the 16-bit words of a 32- or 48-bit word in memory or on the bus
+///
+/// The 1750A itself keeps the most significant word at the lower address, but some remote
+/// terminals and recorders transmit the words the other way round. Each 16-bit word is
+/// big-endian either way, and 16-bit values are unaffected.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{MilFormat, WordOrder, F32};
+/// assert_eq!(F32::read_ordered(&[0x00, 0x01, 0x40, 0x00], WordOrder::LsWordFirst), 0x40000001);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum WordOrder {
+    /// Most significant word first, as the 1750A stores words
+    #[default]
+    MsWordFirst,
+    /// Least significant word first
+    LsWordFirst,
+}
+
+/// The order of the two bytes of each 16-bit word
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    /// High byte first, as the 1750A stores words
+    #[default]
+    BigEndian,
+    /// Low byte first
+    LittleEndian,
+}
+
+impl WordOrder {
+    /// Convert a word between this order and the 1750A's, which is the same in both directions
+    fn arrange<F: MilFormat + ?Sized>(self, bits: F::Bits) -> F::Bits {
+        match self {
+            WordOrder::MsWordFirst => bits,
+            WordOrder::LsWordFirst => F::swap_words(bits),
+        }
+    }
+}
+
+/// How an encoder forms the mantissa
+///
+/// The legacy encoders of the original library pick the exponent from the magnitude of the value
+/// alone, which can leave the mantissa one bit too wide or unnormalized: in the 16-bit format `1.0`
+/// and `-1.0` both encode to `0x8000`, and values just beyond a negative power of two lose
+/// normalization in the 48-bit one. The corrected mode rounds the exact value once to the nearest
+/// word, ties away from zero, so every negative value gets its proper two's-complement mantissa,
+/// and saturates values that are too large. It is also monotone: a larger input never gives a word
+/// of smaller value, see [monotonicity](crate::sort#monotonicity).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EncodeMode {
+    /// Always produce a normalized two's-complement word
+    #[default]
+    TwosComplement,
+    /// Reproduce the words of the original encoders, boundary bugs included
+    Legacy,
+}
+
+/// How a decoder reads the mantissa and exponent
+///
+/// The legacy decoders of the original library read the exponent as unsigned, so every value
+/// below `0.5` in magnitude decodes far too large, and the 48-bit one also reads the lower 16
+/// mantissa bits as a magnitude added regardless of the sign, so negative extended precision
+/// values decode to the wrong number. The corrected mode reads the mantissa as one
+/// two's-complement quantity and the exponent as two's complement, as MIL-STD-1750A defines
+/// them, and accepts unnormalized words as the hardware does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DecodeMode {
+    /// Read the word as the standard defines it
+    #[default]
+    TwosComplement,
+    /// Reproduce the values of the original decoders
+    Legacy,
+}
+
+/// The 16-bit format, decoded to [`f16`](struct@f16)
+#[cfg(feature = "half")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct F16;
+
+/// The 32-bit format, decoded to [`f32`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct F32;
+
+/// The 48-bit extended precision format, decoded to [`f64`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct F48;
+
+#[cfg(feature = "half")]
+impl MilFormat for F16 {
+    type Bits = u16;
+    type Ieee = f16;
+    type Word = Mil16;
+
+    const BITS: u32 = 16;
+    const BYTES: usize = 2;
+
+    fn decode_strict(bits: u16) -> Result<f16, DecodeError> {
+        crate::half::decode_strict(bits)
+    }
+
+    fn encode_with(value: f16, mode: EncodeMode) -> u16 {
+        crate::half::encode_with(value, mode)
+    }
+
+    fn decode_with(bits: u16, mode: DecodeMode) -> f16 {
+        crate::half::decode_with(bits, mode)
+    }
+
+    fn try_encode(value: f16) -> Result<u16, Mil1750Error> {
+        Mil16::try_from_f16(value).map(Mil16::to_bits)
+    }
+
+    fn encode_for(value: f16, profile: &TargetProfile) -> Result<u16, Mil1750Error> {
+        profile.encode16(value.into())
+    }
+
+    fn decode_for(bits: u16, profile: &TargetProfile) -> f16 {
+        f16::from_f32(profile.decode16(bits))
+    }
+
+    fn to_word(bits: u16) -> Mil16 {
+        Mil16::from_bits(bits)
+    }
+
+    fn from_word(word: Mil16) -> u16 {
+        word.to_bits()
+    }
+
+    fn read_be(bytes: &[u8]) -> u16 {
+        u16::from_be_bytes([bytes[0], bytes[1]])
+    }
+
+    fn write_be(bits: u16, out: &mut [u8]) {
+        out[..2].copy_from_slice(&bits.to_be_bytes());
+    }
+
+    fn swap_words(bits: u16) -> u16 {
+        bits
+    }
+}
+
+impl MilFormat for F32 {
+    type Bits = u32;
+    type Ieee = f32;
+    type Word = Mil32;
+
+    const BITS: u32 = 32;
+    const BYTES: usize = 4;
+
+    fn decode_strict(bits: u32) -> Result<f32, DecodeError> {
+        single::decode_strict(bits)
+    }
+
+    fn encode_with(value: f32, mode: EncodeMode) -> u32 {
+        single::encode_with(value, mode)
+    }
+
+    fn decode_with(bits: u32, mode: DecodeMode) -> f32 {
+        single::decode_with(bits, mode)
+    }
+
+    fn try_encode(value: f32) -> Result<u32, Mil1750Error> {
+        Mil32::try_from_f32(value).map(Mil32::to_bits)
+    }
+
+    fn encode_for(value: f32, profile: &TargetProfile) -> Result<u32, Mil1750Error> {
+        profile.encode32(value)
+    }
+
+    fn decode_for(bits: u32, profile: &TargetProfile) -> f32 {
+        profile.decode32(bits)
+    }
+
+    fn to_word(bits: u32) -> Mil32 {
+        Mil32::from_bits(bits)
+    }
+
+    fn from_word(word: Mil32) -> u32 {
+        word.to_bits()
+    }
+
+    fn read_be(bytes: &[u8]) -> u32 {
+        u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    fn write_be(bits: u32, out: &mut [u8]) {
+        out[..4].copy_from_slice(&bits.to_be_bytes());
+    }
+
+    fn swap_words(bits: u32) -> u32 {
+        bits.rotate_left(16)
+    }
+}
+
+impl MilFormat for F48 {
+    type Bits = u64;
+    type Ieee = f64;
+    type Word = Mil48;
+
+    const BITS: u32 = 48;
+    const BYTES: usize = 6;
+
+    fn decode_strict(bits: u64) -> Result<f64, DecodeError> {
+        extended::decode_strict(bits)
+    }
+
+    fn encode_with(value: f64, mode: EncodeMode) -> u64 {
+        extended::encode_with(value, mode)
+    }
+
+    fn decode_with(bits: u64, mode: DecodeMode) -> f64 {
+        extended::decode_with(bits, mode)
+    }
+
+    fn try_encode(value: f64) -> Result<u64, Mil1750Error> {
+        Mil48::try_from_f64(value).map(Mil48::to_bits)
+    }
+
+    fn encode_for(value: f64, profile: &TargetProfile) -> Result<u64, Mil1750Error> {
+        profile.encode48(value)
+    }
+
+    fn decode_for(bits: u64, profile: &TargetProfile) -> f64 {
+        profile.decode48(bits)
+    }
+
+    fn to_word(bits: u64) -> Mil48 {
+        Mil48::from_bits(bits)
+    }
+
+    fn from_word(word: Mil48) -> u64 {
+        word.to_bits()
+    }
+
+    fn read_be(bytes: &[u8]) -> u64 {
+        let mut word = [0; 8];
+        word[2..].copy_from_slice(&bytes[..6]);
+        u64::from_be_bytes(word)
+    }
+
+    fn write_be(bits: u64, out: &mut [u8]) {
+        out[..6].copy_from_slice(&bits.to_be_bytes()[2..]);
+    }
+
+    fn swap_words(bits: u64) -> u64 {
+        (bits & 0xFFFF) << 32 | bits & 0xFFFF_0000 | bits >> 32 & 0xFFFF
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<F: MilFormat>(value: F::Ieee) -> F::Ieee {
+        let mut bytes = [0; 8];
+        F::write_be(F::encode(value), &mut bytes);
+        F::decode(F::read_be(&bytes))
+    }
+
+    #[test]
+    fn test_generic_round_trip() {
+        assert_eq!(round_trip::<F32>(1.0), 1.0);
+        assert_eq!(round_trip::<F48>(105.639485637361), 105.63948563742451);
+        assert_eq!(round_trip::<F32>(0.25), 0.25);
+        assert_eq!(round_trip::<F48>(-1.5), -1.5);
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_f16() {
+        assert_eq!(
+            round_trip::<F16>(f16::from_f32(12.4)),
+            f16::from_f32(12.40625)
+        );
+        assert_eq!(round_trip::<F16>(f16::NEG_ONE), f16::NEG_ONE);
+        assert_eq!(F16::decode(0x8000), f16::NEG_ONE);
+        assert_eq!(F16::encode(f16::ONE), 0x4001);
+        assert_eq!(F16::encode_with(f16::ONE, EncodeMode::Legacy), 0x8000);
+        assert_eq!(
+            F16::encode_with(f16::ONE, EncodeMode::TwosComplement),
+            0x4001
+        );
+        assert_eq!(F16::from_word(Mil16::from_bits(0x6344)), 0x6344);
+        assert_eq!(
+            F16::read_ordered(&[0x63, 0x44], WordOrder::LsWordFirst),
+            0x6344
+        );
+    }
+
+    #[test]
+    fn test_decode_modes() {
+        assert_eq!(F32::decode(0x400000FF), 0.25);
+        assert!(F32::decode_with(0x400000FF, DecodeMode::Legacy).is_infinite());
+        assert_eq!(F48::decode(0x800000FF0000), -0.5);
+    }
+
+    #[test]
+    fn test_decode_traced() {
+        assert_eq!(
+            F32::decode_traced(0x400000FF),
+            single::decode_traced(0x400000FF)
+        );
+        assert_eq!(
+            F48::decode_traced(0x200000010000),
+            Decoded {
+                raw: 0x200000010000,
+                value: 0.5,
+                valid: false
+            }
+        );
+        assert!(F48::decode_traced(0x69A3B50754AB).valid);
+    }
+
+    #[test]
+    fn test_bytes_and_words() {
+        let mut bytes = [0xFF; 7];
+        F48::write_be(0x69A3B50754AB, &mut bytes);
+        assert_eq!(bytes, [0x69, 0xA3, 0xB5, 0x07, 0x54, 0xAB, 0xFF]);
+        assert_eq!(F48::read_be(&bytes), 0x69A3B50754AB);
+        assert_eq!(F32::to_word(0x53BE7703), Mil32::from_f32(5.234));
+        assert_eq!(F48::swap_words(0x69A3B50754AB), 0x54ABB50769A3);
+        F48::write_ordered(0x69A3B50754AB, WordOrder::LsWordFirst, &mut bytes);
+        assert_eq!(bytes[..6], [0x54, 0xAB, 0xB5, 0x07, 0x69, 0xA3]);
+        assert_eq!(
+            F48::read_ordered(&bytes, WordOrder::LsWordFirst),
+            0x69A3B50754AB
+        );
+        assert!(matches!(
+            F32::try_encode(f32::NAN),
+            Err(Mil1750Error::NotFinite { .. })
+        ));
+    }
+}
@@ -0,0 +1,424 @@
+//! # Half precision
+//! The non-standard 16-bit format of the original Perl library: a 10-bit two's-complement
+//! mantissa followed by a 6-bit two's-complement exponent, converted to and from
+//! [`f16`](struct@f16).
+//!
+//! The `f16` functions need the `half` feature, on by default. The `_f32` functions convert
+//! without it, and every 16-bit value is exact in an `f32`.
+//!
+//! The original [`encode`] and [`decode`] are deprecated: the encoder never forms a
+//! two's-complement mantissa, so `1.0` and `-1.0` both give `0x8000` and no negative value
+//! survives a round trip, and the decoder reads the exponent as unsigned. [`encode_f32`] and
+//! [`decode_f32`], and [`encode_with`] and [`decode_with`] in their default modes, handle the
+//! sign in the mantissa as the 32- and 48-bit formats do; the `Legacy` modes keep the old words
+//! and values.
+//!
+//! ```
+//! use mil1750a_converter::half::{decode_f32, encode_f32, encode_f32_with};
+//! use mil1750a_converter::EncodeMode;
+//!
+//! let word = encode_f32(-12.40625);
+//! assert_eq!(word, 0x9CC4);
+//! assert_eq!(decode_f32(word), -12.40625);
+//! assert_eq!(encode_f32(1.0), 0x4001);
+//! assert_eq!(encode_f32_with(1.0, EncodeMode::Legacy), 0x8000);
+//! ```
+
+#[cfg(feature = "half")]
+use crate::Decoded;
+use crate::{arith, DecodeError, DecodeMode, EncodeMode, Lenient};
+#[cfg(feature = "half")]
+use half::f16;
+
+/// Transform 16-bit floating point number to MIL-1750A Hex
+///
+/// # Arguments
+///
+/// * `input`: number as 16-bit floating point
+///
+/// returns: u16 interpret as hex
+///
+/// # Examples
+///
+/// ```
+/// # #![allow(deprecated)]
+/// use half::f16;
+/// use mil1750a_converter::half::encode;
+/// assert_eq!(encode(f16::from_f32(25.63)), 0x6685);
+/// ```
+#[cfg(feature = "half")]
+#[deprecated(note = "gives 0x8000 for both 1.0 and -1.0; use `half::encode_with`")]
+pub fn encode(input: f16) -> u16 {
+    legacy_encode(input.into())
+}
+
+/// Transform an `f32` to 16-bit MIL-1750A, rounding to the nearest normalized word and
+/// saturating, as [`EncodeMode::TwosComplement`] does
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::half::encode_f32;
+/// assert_eq!(encode_f32(25.63), 0x6685);
+/// assert_eq!(encode_f32(-1.0), 0x8000);
+/// ```
+pub fn encode_f32(input: f32) -> u16 {
+    encode_f32_with(input, EncodeMode::TwosComplement)
+}
+
+/// The original 16-bit encoder, which sets no sign and lets the mantissa overflow
+fn legacy_encode(input: f32) -> u16 {
+    let mut exponent = input.abs().log2().ceil() as i32;
+    let mut mantissa = (input * 2f32.powi(9 - exponent)).round() as i32;
+
+    // Boundary check
+    if mantissa == 32768 {
+        mantissa /= 2;
+        exponent += 1;
+    }
+
+    let mantissa_bits = ((mantissa as u16) & 0x3FF) << 6;
+    let exponent_bits = (exponent as u16) & 0x3F;
+
+    mantissa_bits | exponent_bits
+}
+
+/// Transform a number to 16-bit MIL-1750A as the original encoder of [`encode`] does, but
+/// computing in `f64`, as the Perl library does
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::half::encode_f64_math;
+/// assert_eq!(encode_f64_math(12.4), 0x6344);
+/// ```
+pub fn encode_f64_math(input: f64) -> u16 {
+    let mut exponent = input.abs().log2().ceil() as i32;
+    let mut mantissa = (input * 2f64.powi(9 - exponent)).round() as i32;
+
+    // Boundary check
+    if mantissa == 32768 {
+        mantissa /= 2;
+        exponent += 1;
+    }
+
+    let mantissa_bits = ((mantissa as u16) & 0x3FF) << 6;
+    let exponent_bits = (exponent as u16) & 0x3F;
+
+    mantissa_bits | exponent_bits
+}
+
+/// Transform a f16 to 16-bit MIL-1750A in the given [`EncodeMode`]
+///
+/// [`EncodeMode::Legacy`] gives the same word as [`encode`]. [`EncodeMode::TwosComplement`]
+/// rounds to the nearest normalized word and saturates values too large for the format.
+///
+/// # Examples
+///
+/// ```
+/// use half::f16;
+/// use mil1750a_converter::half::encode_with;
+/// use mil1750a_converter::EncodeMode;
+/// assert_eq!(encode_with(f16::from_f32(-1.0), EncodeMode::TwosComplement), 0x8000);
+/// assert_eq!(encode_with(f16::ONE, EncodeMode::TwosComplement), 0x4001);
+/// ```
+#[cfg(feature = "half")]
+pub fn encode_with(input: f16, mode: EncodeMode) -> u16 {
+    encode_f32_with(input.into(), mode)
+}
+
+/// Transform an `f32` to 16-bit MIL-1750A in the given [`EncodeMode`], as [`encode_with`] does
+pub fn encode_f32_with(input: f32, mode: EncodeMode) -> u16 {
+    match mode {
+        EncodeMode::TwosComplement => arith::round16(input.into()),
+        EncodeMode::Legacy => legacy_encode(input),
+    }
+}
+
+/// Transform a f16 to 16-bit MIL-1750A, rounding up or down at random, as
+/// [`extended::encode_stochastic`](crate::extended::encode_stochastic) does
+#[cfg(feature = "half")]
+pub fn encode_stochastic(input: f16, random: &mut impl FnMut() -> u64) -> u16 {
+    encode_f32_stochastic(input.into(), random)
+}
+
+/// Transform an `f32` to 16-bit MIL-1750A, rounding up or down at random, as
+/// [`encode_stochastic`] does
+pub fn encode_f32_stochastic(input: f32, random: &mut impl FnMut() -> u64) -> u16 {
+    arith::round16_stochastic(input.into(), random())
+}
+
+/// Convert MIL-1750A hex (interpreted as u16) to f16
+///
+/// # Arguments
+///
+/// * `input`:  MIL-1750A hex (interpreted as u16)
+///
+/// returns: f16 representation of the input
+///
+/// # Examples
+///
+/// ```
+/// # #![allow(deprecated)]
+/// use half::f16;
+/// use mil1750a_converter::half::decode;
+/// assert_eq!(decode(0x6344), f16::from_f32(12.40625));
+/// ```
+#[cfg(feature = "half")]
+#[deprecated(note = "reads the exponent as unsigned; use `half::decode_with`")]
+pub fn decode(input: u16) -> f16 {
+    f16::from_f32(legacy_decode(input))
+}
+
+/// Convert a 16-bit MIL-1750A word to `f32`, exactly, as the standard defines it and as
+/// [`DecodeMode::TwosComplement`] reads it
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::half::decode_f32;
+/// assert_eq!(decode_f32(0x6344), 12.40625);
+/// assert_eq!(decode_f32(0x403F), 0.25);
+/// ```
+pub fn decode_f32(input: u16) -> f32 {
+    decode_f32_with(input, DecodeMode::TwosComplement)
+}
+
+/// The original 16-bit decoder, which reads the exponent as unsigned
+fn legacy_decode(input: u16) -> f32 {
+    let mantissa = ((input >> 6) & 0x3FF) as f32;
+    let exponent = (input & 0x3F) as i32;
+
+    mantissa * 2f32.powi(exponent - 9)
+}
+
+/// Convert a 16-bit MIL-1750A word to `f64` as the original decoder of [`decode`] does, but
+/// computing in `f64` and without rounding to `f16`
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::half::decode_f64_math;
+/// assert_eq!(decode_f64_math(0x6344), 12.40625);
+/// assert_eq!(decode_f64_math(0x7FFF), 511.0 * 2f64.powi(54));
+/// ```
+pub fn decode_f64_math(input: u16) -> f64 {
+    let mantissa = f64::from((input >> 6) & 0x3FF);
+    let exponent = (input & 0x3F) as i32;
+
+    mantissa * 2f64.powi(exponent - 9)
+}
+
+/// Convert a 16-bit MIL-1750A word to f16 in the given [`DecodeMode`]
+///
+/// [`DecodeMode::Legacy`] gives the same value as [`decode`]. [`DecodeMode::TwosComplement`]
+/// reads the exponent as two's complement, like [`decode_lenient`].
+///
+/// # Examples
+///
+/// ```
+/// use half::f16;
+/// use mil1750a_converter::half::decode_with;
+/// use mil1750a_converter::DecodeMode;
+/// assert_eq!(decode_with(0x8000, DecodeMode::TwosComplement), f16::NEG_ONE);
+/// assert_eq!(decode_with(0x403F, DecodeMode::TwosComplement), f16::from_f32(0.25));
+/// ```
+#[cfg(feature = "half")]
+pub fn decode_with(input: u16, mode: DecodeMode) -> f16 {
+    f16::from_f32(decode_f32_with(input, mode))
+}
+
+/// Convert a 16-bit MIL-1750A word to `f32` in the given [`DecodeMode`], as [`decode_with`]
+/// does
+pub fn decode_f32_with(input: u16, mode: DecodeMode) -> f32 {
+    match mode {
+        DecodeMode::TwosComplement => arith::value16(input) as f32,
+        DecodeMode::Legacy => legacy_decode(input),
+    }
+}
+
+/// Convert a 16-bit MIL-1750A word to f16, rejecting words that are not normalized
+///
+/// Unlike [`decode`], the mantissa and exponent are read as two's complement, and zero must be
+/// all-zero bits. Values beyond the range of `f16` decode to infinity.
+///
+/// # Examples
+///
+/// ```
+/// use half::f16;
+/// use mil1750a_converter::half::decode_strict;
+/// assert_eq!(decode_strict(0x6344), Ok(f16::from_f32(12.40625)));
+/// assert!(decode_strict(0x3002).is_err());
+/// ```
+#[cfg(feature = "half")]
+pub fn decode_strict(input: u16) -> Result<f16, DecodeError> {
+    DecodeError::check(arith::form16(input), input.into(), 16)?;
+    Ok(f16::from_f64(arith::value16(input)))
+}
+
+/// Convert a 16-bit MIL-1750A word to `f32`, rejecting words that are not normalized, as
+/// [`decode_strict`] does; every value is exact
+pub fn decode_strict_f32(input: u16) -> Result<f32, DecodeError> {
+    DecodeError::check(arith::form16(input), input.into(), 16)?;
+    Ok(arith::value16(input) as f32)
+}
+
+/// Convert a 16-bit MIL-1750A word to f16 as the hardware does, accepting unnormalized words
+///
+/// The value is computed exactly like [`decode_strict`], and [`Lenient::normalized`] records
+/// whether the word would have passed it.
+///
+/// # Examples
+///
+/// ```
+/// use half::f16;
+/// use mil1750a_converter::half::decode_lenient;
+/// let decoded = decode_lenient(0x3002);
+/// assert_eq!(decoded.value, f16::from_f32(1.5));
+/// assert!(!decoded.normalized);
+/// ```
+#[cfg(feature = "half")]
+pub fn decode_lenient(input: u16) -> Lenient<f16> {
+    Lenient::new(f16::from_f64(arith::value16(input)), arith::form16(input))
+}
+
+/// Convert a 16-bit MIL-1750A word to f16 as [`decode_lenient`] does, keeping the word with the
+/// value
+#[cfg(feature = "half")]
+pub fn decode_traced(input: u16) -> Decoded<u16, f16> {
+    decode_lenient(input).with_raw(input)
+}
+
+/// Convert a 16-bit MIL-1750A word to `f32` as the hardware does, as [`decode_lenient`] does
+pub fn decode_lenient_f32(input: u16) -> Lenient<f32> {
+    Lenient::new(arith::value16(input) as f32, arith::form16(input))
+}
+
+#[cfg(all(test, feature = "half"))]
+#[allow(deprecated)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(f16::from_f32(-1.0)), 0x8000);
+        assert_eq!(encode(f16::from_f32(1.0)), 0x8000);
+        assert_eq!(encode(f16::from_f32(12.4)), 0x6344);
+        assert_eq!(encode(f16::from_f32(-12.4)), 0x9CC4);
+        assert_eq!(encode(f16::from_f32(25.63)), 0x6685);
+        assert_eq!(encode(f16::from_f32(-25.63)), 0x9985);
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(0x6344), f16::from_f32(12.40625));
+        assert_eq!(decode(0x324F), f16::from_f32(12864.0));
+    }
+    #[test]
+    fn test_decode_strict() {
+        assert_eq!(decode_strict(0x8000), Ok(f16::from_f32(-1.0)));
+        assert_eq!(decode_strict(0x4000), Ok(f16::from_f32(0.5)));
+        assert_eq!(
+            decode_strict(0x0001),
+            Err(DecodeError::NonZeroExponent {
+                word: 0x0001,
+                bits: 16
+            })
+        );
+    }
+    #[test]
+    fn test_decode_lenient() {
+        assert_eq!(decode_lenient(0xFFC0).value, f16::from_f32(-2f32.powi(-9)));
+        assert!(!decode_lenient(0xFFC0).normalized);
+        assert!(decode_lenient(0x9CC4).normalized);
+    }
+    #[test]
+    fn test_encode_with() {
+        assert_eq!(encode_with(f16::ONE, EncodeMode::TwosComplement), 0x4001);
+        assert_eq!(
+            encode_with(f16::NEG_ONE, EncodeMode::TwosComplement),
+            0x8000
+        );
+        assert_eq!(encode_with(f16::ONE, EncodeMode::Legacy), 0x8000);
+        // Rounds up to 1.0, past the legacy boundary check
+        let below_one = f16::from_f32(1.0 - 2f32.powi(-11));
+        assert_eq!(encode_with(below_one, EncodeMode::TwosComplement), 0x4001);
+        let beyond_minus_one = f16::from_f32(-1.0 - 2f32.powi(-10));
+        assert_eq!(
+            encode_with(beyond_minus_one, EncodeMode::TwosComplement),
+            0x8000
+        );
+        assert_eq!(
+            encode_with(f16::from_f32(-25.63), EncodeMode::TwosComplement),
+            0x9985
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // Every normalized word survives a round trip, negative mantissas included
+        for word in 0..=u16::MAX {
+            if !decode_lenient_f32(word).normalized {
+                continue;
+            }
+            let value = decode_f32_with(word, DecodeMode::TwosComplement);
+            assert_eq!(encode_f32_with(value, EncodeMode::TwosComplement), word);
+        }
+        assert_eq!(decode_with(0x6344, DecodeMode::Legacy), decode(0x6344));
+        assert_eq!(
+            decode_f32_with(0x403F, DecodeMode::Legacy),
+            legacy_decode(0x403F)
+        );
+        assert_eq!(decode_f32(0x403F), 0.25);
+    }
+
+    #[test]
+    fn test_f64_math() {
+        // An f32 logarithm rounds to exactly 8, so the mantissa of the original encoder overflows
+        let value = 256.0 * (1.0 + f32::EPSILON);
+        assert_eq!(legacy_encode(value), 0x8008);
+        assert_eq!(encode_f64_math(value.into()), 0x4009);
+        for value in [12.4, -12.4, 25.63, -25.63, 1.0] {
+            let half = f16::from_f32(value);
+            assert_eq!(encode_f64_math(half.into()), encode(half), "{value}");
+        }
+        for word in [0x6344, 0x324F, 0x8000, 0x403F] {
+            assert_eq!(
+                decode_f64_math(word) as f32,
+                legacy_decode(word),
+                "{word:#X}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_f32() {
+        for value in [12.4, -25.63, 1.0, -1.0, 3.0e4, -7.5e-3] {
+            let half = f16::from_f32(value);
+            assert_eq!(
+                encode_f32(half.into()),
+                encode_with(half, EncodeMode::default())
+            );
+            for mode in [EncodeMode::Legacy, EncodeMode::TwosComplement] {
+                assert_eq!(encode_f32_with(half.into(), mode), encode_with(half, mode));
+            }
+        }
+        for word in [0x6344, 0x324F, 0x9CC4, 0x8000, 0x0001, 0x3002, 0x7FDF] {
+            assert_eq!(
+                f16::from_f32(decode_f32(word)),
+                decode_with(word, DecodeMode::default())
+            );
+            assert_eq!(
+                decode_strict_f32(word).map(f16::from_f32),
+                decode_strict(word)
+            );
+            assert_eq!(
+                decode_lenient_f32(word).normalized,
+                decode_lenient(word).normalized
+            );
+        }
+        // Beyond the range of f16
+        assert_eq!(decode_strict_f32(0x7FDF), Ok(511.0 * 2f32.powi(22)));
+        assert_eq!(decode_f32(0x7FDF), 511.0 * 2f32.powi(22));
+    }
+}
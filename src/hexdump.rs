@@ -0,0 +1,267 @@
+//! # Text hexdumps
+//! Rebuild the memory behind a plain-text hexdump, as `xxd`, `hexdump -C` or a target debugger
+//! prints it, so its words can be decoded.
+//!
+//! Each line is an address, optionally prefixed with `0x` and followed by a colon, then groups
+//! of hex digits separated by single spaces, then an optional ASCII gutter. The gutter is
+//! found by the layout rather than its text, which can look like hex digits: it starts after
+//! the first run of two spaces following the address, as `xxd` prints it, or at `  |` when the
+//! line has one, as `hexdump -C` prints it with two spaces in the middle of its bytes. Every
+//! group of a line has as many digits as the first, except that the last may be shorter. A
+//! line holding only `*`, as `hexdump` prints for repeated lines, repeats the line before it up
+//! to the next address.
+//!
+//! Groups are bytes in memory order, unless the dump prints multi-byte groups as little-endian
+//! integers, as `hexdump` without `-C` does on x86 hosts; see [`Parser::group_order`].
+//! Addresses count bytes, or 16-bit words with [`Parser::word_addresses`], as 1750A debuggers
+//! print them.
+//!
+//! ```
+//! use mil1750a_converter::hexdump::Parser;
+//! use mil1750a_converter::schema::{FieldFormat, Schema, Value};
+//!
+//! let text = "\
+//! 00000100: 4000 0001 53be 7703  @...S.w.
+//! 00000108: fffe                 ..
+//! ";
+//! let dump = Parser::new().parse(text).unwrap();
+//! assert_eq!(dump.start(), 0x100);
+//! assert_eq!(dump.words(), [0x4000, 0x0001, 0x53BE, 0x7703, 0xFFFE]);
+//!
+//! let schema = Schema::new()
+//!     .field("gain", 2, FieldFormat::F32)
+//!     .field("count", 4, FieldFormat::Int16);
+//! let frame = schema.decode(&dump.words()).unwrap();
+//! assert_eq!(frame.get("gain"), Some(Value::F32(5.234)));
+//! assert_eq!(frame.get("count"), Some(Value::Int16(-2)));
+//! ```
+
+use crate::ByteOrder;
+
+/// Reasons a hexdump cannot be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum HexdumpError {
+    /// A line does not start with a hexadecimal address
+    #[error("line {line} does not start with an address")]
+    InvalidAddress {
+        /// Line of the dump, from one
+        line: usize,
+    },
+    /// A group of hex digits has an odd number of digits
+    #[error("line {line} has a group of an odd number of hex digits")]
+    OddGroup {
+        /// Line of the dump, from one
+        line: usize,
+    },
+    /// A line's address is not where the previous line ended
+    #[error("line {line} starts at {found:#X} but the dump continues at {expected:#X}")]
+    Discontiguous {
+        /// Line of the dump, from one
+        line: usize,
+        /// Address the previous line ended at
+        expected: u64,
+        /// Address of the line
+        found: u64,
+    },
+}
+
+/// A parser of text hexdumps in one dialect
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Parser {
+    group_order: ByteOrder,
+    word_addresses: bool,
+}
+
+impl Parser {
+    /// A parser of dumps with byte addresses and groups in memory order, like `xxd`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read each multi-byte group as an integer in `order`; little-endian puts the last two digits
+    /// of each group first in memory
+    pub fn group_order(mut self, order: ByteOrder) -> Self {
+        self.group_order = order;
+        self
+    }
+
+    /// Read addresses as counts of 16-bit words rather than bytes
+    pub fn word_addresses(mut self) -> Self {
+        self.word_addresses = true;
+        self
+    }
+
+    /// Rebuild the memory a dump shows
+    pub fn parse(&self, text: &str) -> Result<Dump, HexdumpError> {
+        let unit = if self.word_addresses { 2 } else { 1 };
+        let mut dump: Option<Dump> = None;
+        let mut repeat: Option<Vec<u8>> = None;
+        let mut previous = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line == "*" {
+                repeat = Some(previous.clone());
+                continue;
+            }
+
+            let (address, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let address = address.trim_end_matches(':');
+            let address = address.strip_prefix("0x").unwrap_or(address);
+            let address = u64::from_str_radix(address, 16)
+                .map_err(|_| HexdumpError::InvalidAddress { line: line_number })?
+                * unit;
+            let bytes = self.groups(rest, line_number)?;
+
+            let dump = dump.get_or_insert_with(|| Dump {
+                start: address / unit,
+                bytes: Vec::new(),
+            });
+            let end = |dump: &Dump| dump.start * unit + dump.bytes.len() as u64;
+            if let Some(repeated) = repeat.take().filter(|repeated| !repeated.is_empty()) {
+                while end(dump) < address {
+                    let missing = (address - end(dump)) as usize;
+                    dump.bytes.extend(&repeated[..missing.min(repeated.len())]);
+                }
+            }
+            if end(dump) != address {
+                return Err(HexdumpError::Discontiguous {
+                    line: line_number,
+                    expected: end(dump) / unit,
+                    found: address / unit,
+                });
+            }
+            dump.bytes.extend(&bytes);
+            previous = bytes;
+        }
+        Ok(dump.unwrap_or_default())
+    }
+
+    /// The bytes of the groups at the start of `text`, up to the gutter
+    fn groups(&self, text: &str, line: usize) -> Result<Vec<u8>, HexdumpError> {
+        let text = text.trim_start();
+        let hex = match text.find("  |") {
+            Some(gutter) => &text[..gutter],
+            None => text.split("  ").next().unwrap_or_default(),
+        };
+        let mut bytes = Vec::new();
+        let mut width = None;
+        for group in hex.split_whitespace() {
+            let is_hex = group.bytes().all(|digit| digit.is_ascii_hexdigit());
+            let width = *width.get_or_insert(group.len());
+            if !is_hex || group.len() > width {
+                break;
+            }
+            if group.len() % 2 != 0 {
+                return Err(HexdumpError::OddGroup { line });
+            }
+            let start = bytes.len();
+            for pair in group.as_bytes().chunks_exact(2) {
+                let pair = std::str::from_utf8(pair).unwrap();
+                bytes.push(u8::from_str_radix(pair, 16).unwrap());
+            }
+            if self.group_order == ByteOrder::LittleEndian {
+                bytes[start..].reverse();
+            }
+            if group.len() < width {
+                break;
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+/// The contiguous memory a hexdump shows
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dump {
+    start: u64,
+    bytes: Vec<u8>,
+}
+
+impl Dump {
+    /// Address of the first byte, in the dump's address unit
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// The bytes, in memory order
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The bytes as big-endian 16-bit words, as the 1750A stores them; a trailing odd byte is
+    /// ignored
+    pub fn words(&self) -> Vec<u16> {
+        self.bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hexdump_canonical() {
+        let text = "\
+00000000  40 00 00 01 53 be 77 03  40 00 00 01 53 be 77 03  |@...S.w.@...S.w.|
+*
+00000030  69 a3 b5 07 54 ab                                 |i...T.|
+00000036
+";
+        let dump = Parser::new().parse(text).unwrap();
+        assert_eq!(dump.bytes().len(), 0x36);
+        assert_eq!(dump.bytes()[0x20..0x24], [0x40, 0x00, 0x00, 0x01]);
+        assert_eq!(dump.words()[24..], [0x69A3, 0xB507, 0x54AB]);
+    }
+
+    #[test]
+    fn test_hex_looking_gutter() {
+        // `printf cafe | xxd`, whose gutter is itself made of hex digits
+        let text = "00000000: 6361 6665                                cafe\n";
+        let dump = Parser::new().parse(text).unwrap();
+        assert_eq!(dump.words(), [0x6361, 0x6665]);
+        let text = "00000000  63 61 66 65 63 61 66 65  63 61 66 65              |cafecafecafe|\n";
+        let dump = Parser::new().parse(text).unwrap();
+        assert_eq!(dump.bytes(), b"cafecafecafe");
+        let dump = Parser::new().parse("00000000: 6361 6665  cafe\n").unwrap();
+        assert_eq!(dump.bytes(), b"cafe");
+    }
+
+    #[test]
+    fn test_debugger_dialect() {
+        // Little-endian 16-bit groups at word addresses, as a target debugger prints them
+        let text = "0x0800: 0040 0100 cafe\n0x0803: befe\n";
+        let parser = Parser::new()
+            .group_order(ByteOrder::LittleEndian)
+            .word_addresses();
+        let dump = parser.parse(text).unwrap();
+        assert_eq!(dump.start(), 0x800);
+        assert_eq!(dump.words(), [0x4000, 0x0001, 0xFECA, 0xFEBE]);
+
+        assert_eq!(
+            parser.parse("0x0800: 0040\n0x0802: 0100\n"),
+            Err(HexdumpError::Discontiguous {
+                line: 2,
+                expected: 0x801,
+                found: 0x802
+            })
+        );
+        assert_eq!(
+            Parser::new().parse("zz: 00\n"),
+            Err(HexdumpError::InvalidAddress { line: 1 })
+        );
+        assert_eq!(
+            Parser::new().parse("0: 000\n"),
+            Err(HexdumpError::OddGroup { line: 1 })
+        );
+        assert!(Parser::new().parse("").unwrap().bytes().is_empty());
+    }
+}
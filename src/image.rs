@@ -0,0 +1,281 @@
+//! # Memory images
+//! Load Intel HEX and Motorola S-record images of 1750A target memory and read values from
+//! them by address.
+//!
+//! The 1750A addresses 16-bit words, while load images record bytes. With
+//! [`Addressing::Byte`] a record address counts bytes and word `n` is the bytes at `2n` and
+//! `2n + 1`; with [`Addressing::Word`] a record address is already a word address. Either way
+//! each word is stored high byte first, and multi-word values most significant word first
+//! unless a [`word_order`](MemoryImage::word_order) is set.
+//! Floating point values are decoded as the hardware reads them, see
+//! [`single::decode_lenient`].
+//!
+//! ```
+//! use mil1750a_converter::image::{Addressing, MemoryImage};
+//!
+//! let ihex = ":0400100040000001AB\n:00000001FF\n";
+//! let image = MemoryImage::parse(ihex, Addressing::Byte).unwrap();
+//! assert_eq!(image.read_f32_at_address(0x08), Ok(1.0));
+//! ```
+
+use crate::{extended, single, WordOrder};
+use std::collections::BTreeMap;
+
+/// How the addresses in a load image count memory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Addressing {
+    /// Addresses count bytes, two to a word
+    #[default]
+    Byte,
+    /// Addresses count 16-bit words
+    Word,
+}
+
+/// The memory contents described by a load image
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryImage {
+    /// Bytes by byte address
+    bytes: BTreeMap<u64, u8>,
+    word_order: WordOrder,
+}
+
+impl MemoryImage {
+    /// Parse an Intel HEX or Motorola S-record image, telling them apart by the first record
+    pub fn parse(text: &str, addressing: Addressing) -> Result<Self, ImageError> {
+        match text.trim_start().chars().next() {
+            Some('S' | 's') => Self::from_srec(text, addressing),
+            _ => Self::from_ihex(text, addressing),
+        }
+    }
+
+    /// Parse an Intel HEX image
+    pub fn from_ihex(text: &str, addressing: Addressing) -> Result<Self, ImageError> {
+        let mut image = Self::default();
+        let mut base = 0u64;
+        for (line, record) in records(text) {
+            let Some(hex) = record.strip_prefix(':') else {
+                return Err(ImageError::InvalidRecord { line });
+            };
+            let bytes = hex_bytes(hex, line)?;
+            if bytes.len() < 5 || bytes.len() != usize::from(bytes[0]) + 5 {
+                return Err(ImageError::InvalidRecord { line });
+            }
+            if bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)) != 0 {
+                return Err(ImageError::Checksum { line });
+            }
+
+            let address = u64::from(u16::from_be_bytes([bytes[1], bytes[2]]));
+            let data = &bytes[4..bytes.len() - 1];
+            match (bytes[3], data) {
+                (0x00, _) => image.load(base + address, data, addressing),
+                (0x01, _) => break,
+                (0x02, &[high, low]) => base = u64::from(u16::from_be_bytes([high, low])) << 4,
+                (0x04, &[high, low]) => base = u64::from(u16::from_be_bytes([high, low])) << 16,
+                (0x03 | 0x05, _) => {}
+                _ => return Err(ImageError::InvalidRecord { line }),
+            }
+        }
+        Ok(image)
+    }
+
+    /// Parse a Motorola S-record image
+    pub fn from_srec(text: &str, addressing: Addressing) -> Result<Self, ImageError> {
+        let mut image = Self::default();
+        for (line, record) in records(text) {
+            let mut chars = record.chars();
+            let (Some('S' | 's'), Some(kind)) = (chars.next(), chars.next()) else {
+                return Err(ImageError::InvalidRecord { line });
+            };
+            let bytes = hex_bytes(chars.as_str(), line)?;
+            if bytes.is_empty() || bytes.len() != usize::from(bytes[0]) + 1 {
+                return Err(ImageError::InvalidRecord { line });
+            }
+            if bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)) != 0xFF {
+                return Err(ImageError::Checksum { line });
+            }
+
+            let address_len = match kind {
+                '0' | '1' | '5' | '9' => 2,
+                '2' | '6' | '8' => 3,
+                '3' | '7' => 4,
+                _ => return Err(ImageError::InvalidRecord { line }),
+            };
+            let Some(fields) = bytes
+                .get(1..bytes.len() - 1)
+                .filter(|f| f.len() >= address_len)
+            else {
+                return Err(ImageError::InvalidRecord { line });
+            };
+            let (address, data) = fields.split_at(address_len);
+            if let '1' | '2' | '3' = kind {
+                let address = address
+                    .iter()
+                    .fold(0u64, |acc, &byte| acc << 8 | u64::from(byte));
+                image.load(address, data, addressing);
+            }
+        }
+        Ok(image)
+    }
+
+    /// Store the bytes of a data record at record address `address`
+    fn load(&mut self, address: u64, data: &[u8], addressing: Addressing) {
+        let start = match addressing {
+            Addressing::Byte => address,
+            Addressing::Word => address * 2,
+        };
+        self.bytes.extend((start..).zip(data.iter().copied()));
+    }
+
+    /// Read multi-word values with their 16-bit words in `order`
+    pub fn word_order(mut self, order: WordOrder) -> Self {
+        self.word_order = order;
+        self
+    }
+
+    /// Number of bytes the image defines
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Whether the image defines no memory at all
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// The 16-bit word at word `address`
+    pub fn word(&self, address: u32) -> Result<u16, ImageError> {
+        let byte = |offset| {
+            self.bytes
+                .get(&(u64::from(address) * 2 + offset))
+                .copied()
+                .ok_or(ImageError::Unmapped { address })
+        };
+        Ok(u16::from_be_bytes([byte(0)?, byte(1)?]))
+    }
+
+    /// The `N` words from word `address` on, joined in the image's word order
+    fn words<const N: u32>(&self, address: u32) -> Result<u64, ImageError> {
+        (0..N).try_fold(0u64, |acc, offset| {
+            let address = address
+                .checked_add(offset)
+                .ok_or(ImageError::Unmapped { address })?;
+            let word = u64::from(self.word(address)?);
+            Ok(match self.word_order {
+                WordOrder::MsWordFirst => acc << 16 | word,
+                WordOrder::LsWordFirst => acc | word << (16 * offset),
+            })
+        })
+    }
+
+    /// Read the 32-bit float at word `address`
+    pub fn read_f32_at_address(&self, address: u32) -> Result<f32, ImageError> {
+        Ok(single::decode_lenient(self.words::<2>(address)? as u32).value)
+    }
+
+    /// Read the 48-bit float at word `address`
+    pub fn read_f48_at_address(&self, address: u32) -> Result<f64, ImageError> {
+        Ok(extended::decode_lenient(self.words::<3>(address)?).value)
+    }
+}
+
+/// The non-blank lines of `text`, numbered from one
+fn records(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, record)| (index + 1, record.trim()))
+        .filter(|(_, record)| !record.is_empty())
+}
+
+/// Decode pairs of hex digits
+fn hex_bytes(hex: &str, line: usize) -> Result<Vec<u8>, ImageError> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err(ImageError::InvalidRecord { line });
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|at| {
+            u8::from_str_radix(&hex[at..at + 2], 16).map_err(|_| ImageError::InvalidRecord { line })
+        })
+        .collect()
+}
+
+/// Reasons a load image cannot be parsed or read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ImageError {
+    /// A record is malformed or of an unknown type
+    #[error("invalid record on line {line}")]
+    InvalidRecord {
+        /// Line of the record, from one
+        line: usize,
+    },
+    /// A record's checksum does not match its contents
+    #[error("checksum mismatch on line {line}")]
+    Checksum {
+        /// Line of the record, from one
+        line: usize,
+    },
+    /// A value reaches memory the image does not define
+    #[error("word {address:#06X} is not in the image")]
+    Unmapped {
+        /// Word address of the first missing word
+        address: u32,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IHEX: &str = "\
+:020000040001F9
+:0600080069A3B50754AB2B
+:00000001FF
+";
+
+    #[test]
+    fn test_ihex() {
+        let image = MemoryImage::from_ihex(IHEX, Addressing::Byte).unwrap();
+        assert_eq!(image.len(), 6);
+        assert_eq!(image.read_f48_at_address(0x8004), Ok(105.63948563742451));
+        assert_eq!(image.word(0x8006), Ok(0x54AB));
+        assert_eq!(
+            image.read_f32_at_address(0x8006),
+            Err(ImageError::Unmapped { address: 0x8007 })
+        );
+
+        let image = MemoryImage::parse(IHEX, Addressing::Word).unwrap();
+        assert_eq!(image.read_f48_at_address(0x10008), Ok(105.63948563742451));
+    }
+
+    #[test]
+    fn test_srec() {
+        let srec = "S00600004844521B\nS107000840000001AF\nS9030000FC\n";
+        let image = MemoryImage::parse(srec, Addressing::Byte).unwrap();
+        assert_eq!(image.read_f32_at_address(0x04), Ok(1.0));
+        let image = MemoryImage::from_srec(srec, Addressing::Word).unwrap();
+        assert_eq!(image.read_f32_at_address(0x08), Ok(1.0));
+        let image = image.word_order(WordOrder::LsWordFirst);
+        assert_eq!(image.read_f32_at_address(0x08), Ok(320.0 * 2f32.powi(-23)));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            MemoryImage::from_ihex(":0400100040000001AC", Addressing::Byte),
+            Err(ImageError::Checksum { line: 1 })
+        );
+        assert_eq!(
+            MemoryImage::from_ihex("\n:0400100040000001", Addressing::Byte),
+            Err(ImageError::InvalidRecord { line: 2 })
+        );
+        assert_eq!(
+            MemoryImage::from_srec("S107000840000001AE", Addressing::Byte),
+            Err(ImageError::Checksum { line: 1 })
+        );
+        assert_eq!(
+            MemoryImage::from_srec("S4030000FC", Addressing::Byte),
+            Err(ImageError::InvalidRecord { line: 1 })
+        );
+    }
+}
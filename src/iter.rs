@@ -0,0 +1,123 @@
+//! # Iterator adapters
+//! Extension methods that plug the conversions into iterator pipelines.
+
+#[cfg(feature = "half")]
+use crate::F16;
+use crate::{MilFormat, F32, F48};
+#[cfg(feature = "half")]
+use half::f16;
+use std::iter::Map;
+
+/// Conversion adapters for iterators of MIL-1750A words and of IEEE values
+///
+/// Implemented for every iterator; each method is only callable when the item type matches.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::iter::Mil1750aIterExt;
+/// let words: Vec<u32> = [1.0, 5.234].into_iter().encode_1750a_32().collect();
+/// assert_eq!(words, [0x40000001, 0x53BE7703]);
+/// let sum: f32 = words.into_iter().map_1750a_to_f32().take(1).sum();
+/// assert_eq!(sum, 1.0);
+/// ```
+pub trait Mil1750aIterExt: Iterator + Sized {
+    /// Decode 16-bit MIL-1750A words, see [`MilFormat::decode`]
+    #[cfg(feature = "half")]
+    fn map_1750a_to_f16(self) -> Map<Self, fn(u16) -> f16>
+    where
+        Self: Iterator<Item = u16>,
+    {
+        self.map(F16::decode as fn(u16) -> f16)
+    }
+
+    /// Decode 32-bit MIL-1750A words, see [`MilFormat::decode`]
+    fn map_1750a_to_f32(self) -> Map<Self, fn(u32) -> f32>
+    where
+        Self: Iterator<Item = u32>,
+    {
+        self.map(F32::decode as fn(u32) -> f32)
+    }
+
+    /// Decode 48-bit MIL-1750A words, see [`MilFormat::decode`]
+    fn map_1750a_to_f48(self) -> Map<Self, fn(u64) -> f64>
+    where
+        Self: Iterator<Item = u64>,
+    {
+        self.map(F48::decode as fn(u64) -> f64)
+    }
+
+    /// Encode values as 16-bit MIL-1750A words, see [`MilFormat::encode`]
+    #[cfg(feature = "half")]
+    fn encode_1750a_16(self) -> Map<Self, fn(f16) -> u16>
+    where
+        Self: Iterator<Item = f16>,
+    {
+        self.map(F16::encode as fn(f16) -> u16)
+    }
+
+    /// Encode values as 32-bit MIL-1750A words, see [`MilFormat::encode`]
+    fn encode_1750a_32(self) -> Map<Self, fn(f32) -> u32>
+    where
+        Self: Iterator<Item = f32>,
+    {
+        self.map(F32::encode as fn(f32) -> u32)
+    }
+
+    /// Encode values as 48-bit MIL-1750A words, see [`MilFormat::encode`]
+    fn encode_1750a_48(self) -> Map<Self, fn(f64) -> u64>
+    where
+        Self: Iterator<Item = f64>,
+    {
+        self.map(F48::encode as fn(f64) -> u64)
+    }
+}
+
+impl<I: Iterator> Mil1750aIterExt for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_16_adapters() {
+        let words: Vec<u16> = [12.4, 25.63]
+            .into_iter()
+            .map(f16::from_f32)
+            .encode_1750a_16()
+            .collect();
+        assert_eq!(words, [0x6344, 0x6685]);
+        let values: Vec<f16> = [0x6344, 0x324F, 0x8000]
+            .into_iter()
+            .map_1750a_to_f16()
+            .collect();
+        assert_eq!(
+            values,
+            [
+                f16::from_f32(12.40625),
+                f16::from_f32(12864.0),
+                f16::NEG_ONE
+            ]
+        );
+    }
+
+    #[test]
+    fn test_32_adapters() {
+        let words: Vec<u32> = [1.0, -1.0, 0.25].into_iter().encode_1750a_32().collect();
+        assert_eq!(words, [0x40000001, 0x80000000, 0x400000FF]);
+        let values: Vec<f32> = words.into_iter().map_1750a_to_f32().collect();
+        assert_eq!(values, [1.0, -1.0, 0.25]);
+    }
+
+    #[test]
+    fn test_48_adapters() {
+        let words: Vec<u64> = [105.639485637361, 0.0]
+            .into_iter()
+            .encode_1750a_48()
+            .collect();
+        assert_eq!(words, [0x69A3B50754AB, 0]);
+        let values: Vec<f64> = words.into_iter().map_1750a_to_f48().collect();
+        assert_eq!(values, [105.63948563742451, 0.0]);
+    }
+}
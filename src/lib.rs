@@ -2,248 +2,222 @@
 //! Use this tool to convert to and from `MIL-1750A`
 //!
 //! Based on [this perl library](https://metacpan.org/release/JTCLARKE/Convert-MIL1750A-0.1/source).
-
-use half::f16;
+//!
+//! Each format has its own module with an `encode` and a `decode` function: [`half`] for the
+//! 16-bit format, [`single`] for 32-bit and [`extended`] for 48-bit, while [`fixed`] handles
+//! fixed-point words. [`Mil16`], [`Mil32`] and [`Mil48`] wrap
+//! words in typed values, [`MilFormat`] abstracts over the formats, and the [`prelude`] imports
+//! the lot.
+//!
+//! ```
+//! use mil1750a_converter::{extended, single};
+//! assert_eq!(single::encode(5.234), 0x53BE7703);
+//! assert_eq!(extended::decode(0x69A3B50754AB), 105.63948563742451);
+//! ```
+//!
+//! The flat `f32_to_1750a`-style functions of earlier versions remain as deprecated aliases,
+//! and the 16-bit `encode` and `decode`, which cannot form negative mantissas, are deprecated in
+//! favour of [`half::encode_with`] and [`half::decode_with`]. The wrapper types, [`MilFormat`] and
+//! the modules built on them read and form words as the standard defines them, while the free
+//! `encode` and `decode` of [`single`] and [`extended`] keep the words and values of the
+//! original library, see [`DecodeMode`]. Those compute in `f32`; [`single::encode_f64_math`],
+//! [`half::encode_f64_math`] and their `decode_f64_math` counterparts run the same algorithms in
+//! `f64`, as the Perl library does, for comparing against its output.
+//!
+//! ## Features
+//!
+//! * `half` (default): the [`f16`](struct@::half::f16) API of the 16-bit format, including
+//!   [`F16`]. Without it the 16-bit format is converted to and from `f32`, through
+//!   [`half::encode_f32`] and [`half::decode_f32`], and the `half` crate is not pulled in.
+//! * `tokio`: [`tokio_util::codec`] codecs for streams of MIL-1750A words, see [`codec`].
+//! * `mmap`: memory-mapped conversion of whole files of words, see [`mmap`].
+//! * `bytemuck`: [`bytemuck::Pod`] for [`Mil16`], [`Mil32`] and [`Mil48`], so byte buffers can be
+//!   reinterpreted as slices of words without copying.
+//! * `python`: a `mil1750a` Python extension module with numpy batch conversions, see [`python`].
+//! * `ffi`: an `extern "C"` API for linking from C and C++, see [`ffi`].
+//! * `wasm`: [`wasm_bindgen`](mod@wasm_bindgen) exports for browser-based tools, see [`wasm`].
+//! * `defmt`: [`defmt::Format`] for the wrapper types and [`Mil1750Error`], for logging from
+//!   embedded firmware.
+//! * `num-traits`: [`num_traits`] implementations (`Zero`, `One`, `Num`, `Bounded` and casts) for
+//!   [`Mil32`] and [`Mil48`], so they work in generic numeric code. Their arithmetic is native,
+//!   see [`arith`].
+//! * `rust_decimal`: `TryFrom<`[`rust_decimal::Decimal`]`>` for [`Mil16`], [`Mil32`] and
+//!   [`Mil48`], rounding decimal values from ICDs once, exactly, instead of through an `f64`.
+//! * `ndarray`, `nalgebra`: conversion of whole [`ndarray`] arrays and [`nalgebra`] matrices,
+//!   keeping their shape, see [`matrix`].
+//! * `arrow`: conversion of whole Arrow columns of words without going through an element at a
+//!   time, for Polars and Parquet workflows, see [`arrow`].
+//! * `tracing`: [`tracing`] spans and events for the conversions of [`batch`], [`stream`] and
+//!   [`mmap`]: counts, durations and the offsets of rejected words.
+//! * `uom`: [`uom`] quantities for [`schema`] fields, declared with
+//!   [`Schema::quantity`](schema::Schema::quantity) and read with
+//!   [`Frame::quantity`](schema::Frame::quantity).
+//! * `serde`: [`serde`] `Serialize` and `Deserialize` for the
+//!   [`ConversionReport`](batch::ConversionReport)s of [`batch`], to keep them as JSON, and for
+//!   [`Schema`](schema::Schema)s and [`DecomMap`](decom::DecomMap)s, to load them from YAML or
+//!   JSON files kept next to the ICD. [`Decoded`], [`Lenient`] and
+//!   [`batch::Record`] serialize too, for JSON Lines output.
+//! * `conformance`: a table of the floating point examples of MIL-STD-1750A and other known
+//!   words, and a one-call check of the conversions against it, see [`conformance`].
+
+#[cfg(feature = "half")]
+use ::half::f16;
+
+pub mod arith;
+pub mod batch;
+pub mod ccsds;
+pub mod ch10;
+pub mod compare;
+pub mod consts;
+pub mod decom;
+pub mod detect;
+pub mod diff;
+pub mod error;
+pub mod explain;
+pub mod extended;
+pub mod fixed;
+pub mod format;
+pub mod half;
+pub mod hexdump;
+pub mod image;
+pub mod iter;
+pub mod lut;
+pub mod prelude;
+pub mod profile;
+pub mod ring;
+pub mod roundtrip;
+pub mod schema;
+pub mod single;
+pub mod soa;
+pub mod sort;
+pub mod stats;
+pub mod stream;
+pub mod table;
+pub mod testgen;
+pub mod types;
+pub mod verify;
+pub mod writer;
+
+pub use error::{BufferError, DecodeError, Mil1750Error, ParseError, TargetFormat};
+#[cfg(feature = "half")]
+pub use format::F16;
+pub use format::{ByteOrder, DecodeMode, EncodeMode, MilFormat, WordOrder, F32, F48};
+pub use types::{Decoded, Lenient, Mil16, Mil32, Mil48};
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "tokio")]
+pub mod codec;
+#[cfg(feature = "conformance")]
+pub mod conformance;
+#[cfg(feature = "rust_decimal")]
+mod decimal;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(any(feature = "ndarray", feature = "nalgebra"))]
+pub mod matrix;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+#[cfg(feature = "num-traits")]
+mod num;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "uom")]
+mod quantity;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 /// Transform 16-bit floating point number to MIL-1750A Hex
-///
-/// # Arguments
-///
-/// * `input`: number as 16-bit floating point
-///
-/// returns: u16 interpret as hex
-///
-/// # Examples
-///
-/// ```
-/// use half::f16;
-/// use MIL1750A_Converter::f16_to_1750a;
-/// assert_eq!(f16_to_1750a(f16::from_f32(25.63)), 0x6685);
-/// ```
+#[cfg(feature = "half")]
+#[deprecated(note = "use `half::encode_with`")]
 pub fn f16_to_1750a(input: f16) -> u16 {
-    let f32_input = f32::from(input);
-    let mut exponent = f32_input.abs().log2().ceil() as i32;
-    let mut mantissa = (f32_input * 2f32.powi(9 - exponent)).round() as i32;
-
-    // Boundary check
-    if mantissa == 32768 {
-        mantissa /= 2;
-        exponent += 1;
-    }
-
-    let mantissa_bits = ((mantissa as u16) & 0x3FF) << 6;
-    let exponent_bits = (exponent as u16) & 0x3F;
-
-    mantissa_bits | exponent_bits
+    half::encode_with(input, EncodeMode::Legacy)
 }
 
-/// Transform 32-bit floating point number to MIL-1750A Hex
-///
-/// # Arguments
-///
-/// * `input`: number as 32-bit floating point
+/// Transform 32-bit floating point number to 16-bit MIL-1750A Hex
 ///
-/// returns: u32 interpret as hex
+/// Unlike [`f16_to_1750a`], the value is not rounded to `f16` first, so values outside the
+/// range of `f16` but within that of the 16-bit format keep their word, and negative values get
+/// a two's-complement mantissa. See [`half::encode_f32`].
 ///
 /// # Examples
 ///
 /// ```
-/// use MIL1750A_Converter::f32_to_1750a;
-/// assert_eq!(f32_to_1750a(5.234), 0x53BE7703);
+/// use mil1750a_converter::{f32_to_1750a_16, m1750a_16_to_f32};
+/// assert_eq!(f32_to_1750a_16(12.4), 0x6344);
+/// assert_eq!(m1750a_16_to_f32(f32_to_1750a_16(-1.0)), -1.0);
+/// // Beyond the largest f16
+/// assert_eq!(m1750a_16_to_f32(f32_to_1750a_16(1e6)), 999424.0);
 /// ```
-pub fn f32_to_1750a(input: f32) -> u32 {
-    if input == 0.0 {
-        return 0;
-    }
-
-    let mut exponent = input.abs().log2().ceil() as i32;
-    let mut mantissa = (input * 2f32.powi(23 - exponent)).round() as i32;
-
-    // Boundary check
-    if mantissa == 8388608 {
-        mantissa /= 2;
-        exponent += 1;
-    }
-
-    let mut result = (mantissa as u32) << 8;
-    result |= (exponent as u32) & 0xFF;
-
-    if input.is_sign_negative() {
-        result |= 0x80000000;
-    }
-
-    result
+pub fn f32_to_1750a_16(input: f32) -> u16 {
+    half::encode_f32(input)
 }
 
+/// Transform 32-bit floating point number to MIL-1750A Hex
+#[deprecated(note = "use `single::encode`")]
+pub fn f32_to_1750a(input: f32) -> u32 {
+    single::encode(input)
+}
 
 /// Transform 48-bit floating point number to MIL-1750A Hex
-///
-/// # Arguments
-///
-/// * `input`: number as 48-bit (actually f64) floating point
-///
-/// returns: u64 interpret as hex
-///
-/// # Examples
-///
-/// ```
-/// use MIL1750A_Converter::f48_to_1750a;
-/// assert_eq!(f48_to_1750a(105.639485637361), 0x69A3B50754AB);
-/// ```
+#[deprecated(note = "use `extended::encode`")]
 pub fn f48_to_1750a(input: f64) -> u64 {
-    if input == 0.0 {
-        return 0;
-    }
-
-    let mut exponent = input.abs().log2().ceil() as i32;
-    let mut mantissa = (input * 2f64.powi(39 - exponent)).round() as i64;
-
-    // Boundary check
-    if mantissa == 549755813888 {
-        mantissa /= 2;
-        exponent += 1;
-    }
-
-    let mantissa1 = ((mantissa >> 16) & 0xFFFFFF) as u32;
-    let mantissa2 = (mantissa & 0xFFFF) as u16;
-    let exponent = exponent as u8;
-
-    let mut result = (mantissa1 as u64) << 24;
-    result |= (exponent as u64) << 16;
-    result |= mantissa2 as u64;
-
-    if input.is_sign_negative() {
-        result |= 0x800000000000;
-    }
-
-    result
+    extended::encode(input)
 }
 
-
 /// Convert MIL-1750A hex (interpreted as u16) to f16
-///
-/// # Arguments
-///
-/// * `input`:  MIL-1750A hex (interpreted as u16)
-///
-/// returns: f16 representation of the input
-///
-/// # Examples
-///
-/// ```
-/// use half::f16;
-/// use MIL1750A_Converter::m1750a_to_16flt;
-/// assert_eq!(m1750a_to_16flt(0x6344), f16::from_f32(12.40625));
-/// ```
+#[cfg(feature = "half")]
+#[deprecated(note = "use `half::decode_with`")]
 pub fn m1750a_to_16flt(input: u16) -> f16 {
-    let mantissa = ((input >> 6) & 0x3FF) as f32;
-    let exponent = (input & 0x3F) as i32;
+    half::decode_with(input, DecodeMode::Legacy)
+}
 
-    f16::from_f32(mantissa * 2f32.powi(exponent - 9))
+/// Convert 16-bit MIL-1750A hex to f32, exactly, see [`half::decode_f32`]
+pub fn m1750a_16_to_f32(input: u16) -> f32 {
+    half::decode_f32(input)
 }
 
 /// Convert MIL-1750A hex (interpreted as u32) to f32
-///
-/// # Arguments
-///
-/// * `input`:  MIL-1750A hex (interpreted as u32)
-///
-/// returns: f32 representation of the input
-///
-/// # Examples
-///
-/// ```
-/// use MIL1750A_Converter::m1750a_to_32flt;
-/// assert_eq!(m1750a_to_32flt(0x997AE105), -25.6300010681152);
-/// ```
+#[deprecated(note = "use `single::decode`")]
 pub fn m1750a_to_32flt(input: u32) -> f32 {
-    let mantissa = (input >> 8) & 0xFFFFFF;
-    let exponent = input & 0xFF;
-
-    // Convert mantissa to signed two's complement
-    let signed_mantissa = if mantissa & 0x800000 != 0 {
-        -(((!mantissa & 0xFFFFFF) + 1) as i32)
-    } else {
-        mantissa as i32
-    };
-
-    (signed_mantissa as f32) * 2f32.powi((exponent as i32) - 23)
+    single::decode(input)
 }
 
 /// Convert MIL-1750A hex (interpreted as u64) to f48 (as f64)
-///
-/// # Arguments
-///
-/// * `input`:  MIL-1750A hex (interpreted as u64)
-///
-/// returns: f48 representation of the input
-///
-/// # Examples
-///
-/// ```
-/// use MIL1750A_Converter::m1750a_to_48flt;
-/// assert_eq!(m1750a_to_48flt(0x69A3B50754AB), 105.63948563742451);
-/// ```
+#[deprecated(note = "use `extended::decode`")]
 pub fn m1750a_to_48flt(input: u64) -> f64 {
-    let mantissa1 = ((input >> 24) & 0xFFFFFF) as u32;
-    let mantissa2 = (input & 0xFFFF) as u16;
-    let exponent = ((input >> 16) & 0xFF) as i32;
-
-    let value1 = (mantissa1 as f64) * 2f64.powi(exponent - 23);
-    let value2 = (mantissa2 as f64) * 2f64.powi(exponent - 39);
-
-    value1 + value2
+    extended::decode(input)
 }
 
 #[cfg(test)]
+#[allow(deprecated)]
 mod tests {
     use super::*;
-    use half::f16;
 
     #[test]
-    fn test_f16_to_1750a() {
-        assert_eq!(f16_to_1750a(f16::from_f32(-1.0)), 0x8000);
-        assert_eq!(f16_to_1750a(f16::from_f32(1.0)), 0x8000);
+    fn test_deprecated_aliases() {
+        #[cfg(feature = "half")]
         assert_eq!(f16_to_1750a(f16::from_f32(12.4)), 0x6344);
-        assert_eq!(f16_to_1750a(f16::from_f32(-12.4)), 0x9CC4);
-        assert_eq!(f16_to_1750a(f16::from_f32(25.63)), 0x6685);
-        assert_eq!(f16_to_1750a(f16::from_f32(-25.63)), 0x9985);
-    }
-
-    #[test]
-    fn test_f32_to_1750a() {
-        assert_eq!(f32_to_1750a(1.0), 0x40000001);
-        assert_eq!(f32_to_1750a(-1.0), 0x80000000);
         assert_eq!(f32_to_1750a(5.234), 0x53BE7703);
-        assert_eq!(f32_to_1750a(-25.63f32), 0x997AE105);
-        assert_eq!(f32_to_1750a(25.63f32), 0x66851F05);
-    }
-
-    #[test]
-    fn test_f48_to_1750a() {
         assert_eq!(f48_to_1750a(105.639485637361), 0x69A3B50754AB);
-        assert_eq!(f48_to_1750a(std::f64::consts::PI), 0x6487ED025111);
-        assert_eq!(f48_to_1750a(-std::f64::consts::PI), 0x9B781202AEEF);
-        assert_eq!(f48_to_1750a(-1.0), 0x800000_00_0000);
-        assert_eq!(f48_to_1750a(0.0), 0x000000_00_0000);
-    }
-
-    #[test]
-    fn test_m1750a_to_48flt() {
-        assert_eq!(m1750a_to_48flt(0x69A3B50754AB), 105.63948563742451);
-        assert_eq!(m1750a_to_48flt(0x64A3F4275AAB), 432247429803.0);
-    }
-
-    #[test]
-    fn test_m1750a_to_32flt() {
+        #[cfg(feature = "half")]
+        assert_eq!(m1750a_to_16flt(0x6344), f16::from_f32(12.40625));
         assert_eq!(m1750a_to_32flt(0x40000001), 1.0);
-        assert_eq!(m1750a_to_32flt(0x997AE105), -25.6300010681152);
-        assert_eq!(m1750a_to_32flt(0x9F34EA0C), -3097.3857421875);
+        assert_eq!(m1750a_to_48flt(0x69A3B50754AB), 105.63948563742451);
     }
 
     #[test]
-    fn test_m1750a_to_16flt() {
-        assert_eq!(m1750a_to_16flt(0x6344), f16::from_f32(12.40625));
-        assert_eq!(m1750a_to_16flt(0x324F), f16::from_f32(12864.0));
+    fn test_16_bit_f32() {
+        assert_eq!(f32_to_1750a_16(-25.63), 0x9985);
+        assert_eq!(m1750a_16_to_f32(0x324F), 12864.0);
+        assert_eq!(m1750a_16_to_f32(0x403F), 0.25);
+        assert_eq!(f32_to_1750a_16(1.0), 0x4001);
+        // Too large for f16, which overflows to infinity
+        assert_eq!(m1750a_16_to_f32(f32_to_1750a_16(3145728.0)), 3145728.0);
+        #[cfg(feature = "half")]
+        assert_ne!(
+            f16_to_1750a(f16::from_f32(3145728.0)),
+            f32_to_1750a_16(3145728.0)
+        );
     }
 }
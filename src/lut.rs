@@ -0,0 +1,229 @@
+//! # Lookup tables
+//! Table-driven decoding for hot loops.
+//!
+//! The 16-bit format has only 65,536 words, so [`decode16_lut`] decodes from a table of every
+//! one of them, built on first use. The wider formats are too large for that, but a caller that
+//! knows its words stay within a range can build a [`LookupTable`] over just that range. Both
+//! give exactly the values of [`MilFormat::decode`].
+//!
+//! ```
+//! use mil1750a_converter::lut::LookupTable;
+//! use mil1750a_converter::{MilFormat, F32};
+//!
+//! # #[cfg(feature = "half")] {
+//! use mil1750a_converter::{lut::decode16_lut, F16};
+//! assert_eq!(decode16_lut(0x6344), F16::decode(0x6344));
+//! # }
+//!
+//! let table = LookupTable::<F32>::new(0x40000000..=0x400000FF);
+//! assert_eq!(table.get(0x40000001), Some(1.0));
+//! assert_eq!(table.get(0x50000000), None);
+//! ```
+//!
+//! Encoding goes the other way: a [`ConstantPool`] remembers the word of every value it has
+//! encoded, so a generator that encodes the same constants over and over looks each one up in
+//! a hash map after the first time, and counts how often it could.
+//!
+//! ```
+//! use mil1750a_converter::lut::ConstantPool;
+//! use mil1750a_converter::F48;
+//!
+//! let mut pool = ConstantPool::<F48>::new();
+//! pool.intern([105.639485637361]);
+//! assert_eq!(pool.encode(105.639485637361), 0x69A3B50754AB);
+//! assert_eq!(pool.encode(1.0), 0x400000010000);
+//! assert_eq!(pool.encode(1.0), 0x400000010000);
+//! assert_eq!((pool.hits(), pool.misses()), (2, 1));
+//! assert_eq!(pool.len(), 2);
+//! ```
+
+use crate::MilFormat;
+#[cfg(feature = "half")]
+use half::f16;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+#[cfg(feature = "half")]
+use std::sync::OnceLock;
+
+/// Decode a 16-bit word from a table of every word, as [`F16::decode`](MilFormat::decode) does
+///
+/// The 128 KiB table is built by the first call.
+#[cfg(feature = "half")]
+pub fn decode16_lut(word: u16) -> f16 {
+    static TABLE: OnceLock<Box<[f16]>> = OnceLock::new();
+    let table = TABLE.get_or_init(|| (0..=u16::MAX).map(crate::F16::decode).collect());
+    table[usize::from(word)]
+}
+
+/// A caller-owned table of the decoded values of a range of words
+#[derive(Debug, Clone, PartialEq)]
+pub struct LookupTable<F: MilFormat> {
+    start: u64,
+    values: Vec<F::Ieee>,
+}
+
+impl<F: MilFormat> LookupTable<F>
+where
+    F::Bits: Into<u64> + TryFrom<u64>,
+{
+    /// Decode every word of `range` into a new table
+    ///
+    /// The table holds one value per word, so its size grows with the width of the range.
+    pub fn new(range: RangeInclusive<F::Bits>) -> Self {
+        let (start, end) = ((*range.start()).into(), (*range.end()).into());
+        let values = (start..=end)
+            .map(|bits| match F::Bits::try_from(bits) {
+                Ok(bits) => F::decode(bits),
+                Err(_) => unreachable!("word between two words of the format"),
+            })
+            .collect();
+        Self { start, values }
+    }
+
+    /// The decoded value of `bits`, or `None` if it is outside the table
+    pub fn get(&self, bits: F::Bits) -> Option<F::Ieee> {
+        let index = bits.into().checked_sub(self.start)?;
+        self.values.get(usize::try_from(index).ok()?).copied()
+    }
+
+    /// The decoded value of `bits`, from the table if it is within range
+    pub fn decode(&self, bits: F::Bits) -> F::Ieee {
+        self.get(bits).unwrap_or_else(|| F::decode(bits))
+    }
+
+    /// Number of words in the table
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the table holds no words
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Words of values already encoded, keyed by the bits of the value, with counts of the
+/// encodes they served
+#[derive(Debug, Clone)]
+pub struct ConstantPool<F: MilFormat> {
+    words: HashMap<u64, F::Bits>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<F: MilFormat> Default for ConstantPool<F> {
+    fn default() -> Self {
+        Self {
+            words: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<F: MilFormat> ConstantPool<F>
+where
+    F::Ieee: Into<f64>,
+{
+    /// An empty pool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encode `values` into the pool ahead of use, without counting them as hits or misses
+    pub fn intern(&mut self, values: impl IntoIterator<Item = F::Ieee>) {
+        for value in values {
+            self.words
+                .entry(value.into().to_bits())
+                .or_insert_with(|| F::encode(value));
+        }
+    }
+
+    /// The word of `value`, as [`MilFormat::encode`] gives it, from the pool if it is there
+    pub fn encode(&mut self, value: F::Ieee) -> F::Bits {
+        let key = value.into().to_bits();
+        if let Some(&word) = self.words.get(&key) {
+            self.hits += 1;
+            return word;
+        }
+        self.misses += 1;
+        let word = F::encode(value);
+        self.words.insert(key, word);
+        word
+    }
+
+    /// Number of encodes served from the pool
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Number of encodes that had to compute their word
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// The fraction of encodes served from the pool, or `None` before the first
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+
+    /// Number of values in the pool
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether the pool holds no values
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "half")]
+    use crate::F16;
+    use crate::{F32, F48};
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_decode16_lut() {
+        for word in (0..=u16::MAX).step_by(7) {
+            assert_eq!(decode16_lut(word), F16::decode(word));
+        }
+    }
+
+    #[test]
+    fn test_lookup_table() {
+        let table = LookupTable::<F48>::new(0x69A3B50754A0..=0x69A3B50754AF);
+        assert_eq!(table.len(), 16);
+        assert_eq!(table.get(0x69A3B50754AB), Some(F48::decode(0x69A3B50754AB)));
+        assert_eq!(table.get(0x69A3B507549F), None);
+        assert_eq!(table.decode(0x400000010000), 1.0);
+        assert_eq!(table.decode(0x400000FF0000), 0.25);
+
+        #[cfg(feature = "half")]
+        assert_eq!(
+            LookupTable::<F16>::new(0xFFF0..=0xFFFF).get(0xFFFF),
+            Some(f16::from_f32(-2f32.powi(-10)))
+        );
+    }
+
+    #[test]
+    fn test_constant_pool() {
+        let mut pool = ConstantPool::<F32>::new();
+        assert!(pool.is_empty());
+        assert_eq!(pool.hit_rate(), None);
+        for _ in 0..3 {
+            for value in [5.234, -25.63, 0.0, -0.0] {
+                assert_eq!(pool.encode(value), F32::encode(value));
+            }
+        }
+        assert_eq!(pool.len(), 4);
+        assert_eq!((pool.hits(), pool.misses()), (8, 4));
+        assert_eq!(pool.hit_rate(), Some(8.0 / 12.0));
+        pool.intern([5.234, 1.0]);
+        assert_eq!((pool.len(), pool.hits()), (5, 8));
+    }
+}
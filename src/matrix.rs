@@ -0,0 +1,197 @@
+//! # Matrix conversion
+//! Convert whole [`ndarray`] arrays and [`nalgebra`] matrices of IEEE values to and from the
+//! words of a format, keeping their shape.
+//!
+//! Each function maps every element through the format's conversion, the `ndarray` ones with the
+//! `ndarray` feature and the `nalgebra` ones with the `nalgebra` feature. Decoding accepts
+//! unnormalized words and reads them as the hardware does, like [`MilFormat::decode`]; the
+//! `try_` functions reject them with the index of the first one instead, and otherwise give the
+//! same values, from [`MilFormat::decode_strict`].
+//!
+//! ```
+//! # #[cfg(feature = "ndarray")] {
+//! use mil1750a_converter::matrix::{decode_array, encode_array};
+//! use mil1750a_converter::F32;
+//! use ndarray::array;
+//!
+//! let gains = array![[1.0f32, 0.5], [-2.0, 5.234]];
+//! let words = encode_array::<F32, _, _>(&gains);
+//! assert_eq!(words[[0, 0]], 0x40000001);
+//! assert_eq!(decode_array::<F32, _, _>(&words), gains);
+//! # }
+//! ```
+
+use crate::{BufferError, MilFormat};
+#[cfg(feature = "nalgebra")]
+use nalgebra::{allocator::Allocator, DefaultAllocator, Dim, Matrix, OMatrix, RawStorage, Scalar};
+#[cfg(feature = "ndarray")]
+use ndarray::{Array, ArrayBase, Data, Dimension};
+
+/// Encode every element of an array, keeping its shape
+#[cfg(feature = "ndarray")]
+pub fn encode_array<F, S, D>(values: &ArrayBase<S, D>) -> Array<F::Bits, D>
+where
+    F: MilFormat,
+    S: Data<Elem = F::Ieee>,
+    D: Dimension,
+{
+    values.mapv(F::encode)
+}
+
+/// Decode every word of an array, keeping its shape
+#[cfg(feature = "ndarray")]
+pub fn decode_array<F, S, D>(words: &ArrayBase<S, D>) -> Array<F::Ieee, D>
+where
+    F: MilFormat,
+    S: Data<Elem = F::Bits>,
+    D: Dimension,
+{
+    words.mapv(F::decode)
+}
+
+/// Decode every word of an array, failing at the first unnormalized word in logical order
+#[cfg(feature = "ndarray")]
+pub fn try_decode_array<F, S, D>(words: &ArrayBase<S, D>) -> Result<Array<F::Ieee, D>, BufferError>
+where
+    F: MilFormat,
+    S: Data<Elem = F::Bits>,
+    D: Dimension,
+{
+    let values = words
+        .iter()
+        .enumerate()
+        .map(|(index, &bits)| F::decode_strict(bits).map_err(|error| (index, error)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|(index, error)| BufferError::Decode {
+            index,
+            byte_offset: index * F::BYTES,
+            error,
+        })?;
+    Ok(Array::from_shape_vec(words.raw_dim(), values).expect("one value per word"))
+}
+
+/// Encode every element of a matrix, keeping its shape
+#[cfg(feature = "nalgebra")]
+pub fn encode_matrix<F, R, C, S>(values: &Matrix<F::Ieee, R, C, S>) -> OMatrix<F::Bits, R, C>
+where
+    F: MilFormat,
+    F::Ieee: Scalar,
+    F::Bits: Scalar,
+    R: Dim,
+    C: Dim,
+    S: RawStorage<F::Ieee, R, C>,
+    DefaultAllocator: Allocator<R, C>,
+{
+    values.map(F::encode)
+}
+
+/// Decode every word of a matrix, keeping its shape
+#[cfg(feature = "nalgebra")]
+pub fn decode_matrix<F, R, C, S>(words: &Matrix<F::Bits, R, C, S>) -> OMatrix<F::Ieee, R, C>
+where
+    F: MilFormat,
+    F::Ieee: Scalar,
+    F::Bits: Scalar,
+    R: Dim,
+    C: Dim,
+    S: RawStorage<F::Bits, R, C>,
+    DefaultAllocator: Allocator<R, C>,
+{
+    words.map(F::decode)
+}
+
+/// Decode every word of a matrix, failing at the first unnormalized word in column-major
+/// order
+#[cfg(feature = "nalgebra")]
+pub fn try_decode_matrix<F, R, C, S>(
+    words: &Matrix<F::Bits, R, C, S>,
+) -> Result<OMatrix<F::Ieee, R, C>, BufferError>
+where
+    F: MilFormat,
+    F::Ieee: Scalar,
+    F::Bits: Scalar,
+    R: Dim,
+    C: Dim,
+    S: RawStorage<F::Bits, R, C>,
+    DefaultAllocator: Allocator<R, C>,
+{
+    let values = words
+        .iter()
+        .enumerate()
+        .map(|(index, &bits)| F::decode_strict(bits).map_err(|error| (index, error)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|(index, error)| BufferError::Decode {
+            index,
+            byte_offset: index * F::BYTES,
+            error,
+        })?;
+    let (rows, columns) = words.shape_generic();
+    Ok(OMatrix::from_iterator_generic(rows, columns, values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DecodeError, F32};
+
+    #[test]
+    #[cfg(feature = "ndarray")]
+    fn test_array() {
+        let values = ndarray::Array::from_shape_fn((2, 3, 2), |(i, j, k)| {
+            i as f64 * 100.0 + j as f64 * 0.25 + k as f64 + 1.0
+        });
+        let words = encode_array::<crate::F48, _, _>(&values);
+        assert_eq!(words.shape(), [2, 3, 2]);
+        assert_eq!(words[[0, 0, 0]], 0x400000010000);
+        assert_eq!(decode_array::<crate::F48, _, _>(&words), values);
+        assert_eq!(
+            try_decode_array::<crate::F48, _, _>(&words.t()),
+            Ok(values.t().to_owned())
+        );
+
+        let mut words = ndarray::Array2::from_elem((2, 2), 0x40000001u32);
+        words[[1, 0]] = 0x20000001;
+        assert_eq!(
+            try_decode_array::<F32, _, _>(&words),
+            Err(BufferError::Decode {
+                index: 2,
+                byte_offset: 8,
+                error: DecodeError::Unnormalized {
+                    word: 0x20000001,
+                    bits: 32
+                }
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "nalgebra")]
+    fn test_matrix() {
+        let values = nalgebra::Matrix2x3::new(1.0f32, 0.5, -2.0, 5.234, 12.5, -100.0);
+        let words = encode_matrix::<F32, _, _, _>(&values);
+        assert_eq!(words[(0, 1)], 0x40000000);
+        assert_eq!(decode_matrix::<F32, _, _, _>(&words), values);
+        assert_eq!(try_decode_matrix::<F32, _, _, _>(&words), Ok(values));
+        let quarter = nalgebra::Matrix1::new(0x400000FFu32);
+        assert_eq!(
+            try_decode_matrix::<F32, _, _, _>(&quarter),
+            Ok(nalgebra::Matrix1::new(0.25))
+        );
+        assert_eq!(decode_matrix::<F32, _, _, _>(&quarter)[0], 0.25);
+
+        let dynamic = nalgebra::DMatrix::from_row_slice(2, 2, &[0x40000001u32, 0, 0x20000001, 0]);
+        let decoded = decode_matrix::<F32, _, _, _>(&dynamic);
+        assert_eq!(decoded.shape(), (2, 2));
+        assert_eq!(
+            try_decode_matrix::<F32, _, _, _>(&dynamic).map(|_| ()),
+            Err(BufferError::Decode {
+                index: 1,
+                byte_offset: 4,
+                error: DecodeError::Unnormalized {
+                    word: 0x20000001,
+                    bits: 32
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,384 @@
+//! # Memory-mapped file conversion
+//! Convert whole files of packed MIL-1750A words without reading them into memory.
+//!
+//! MIL-1750A words are stored big-endian, IEEE values in native byte order so the converted
+//! file can itself be mapped as `&[f32]`/`&[f64]`. Words are read byte-wise, so the mappings
+//! carry no alignment requirements. A trailing partial word is never converted: in-place
+//! conversions leave it untouched and file-to-file conversions drop it, and both report its
+//! size in [`Converted::tail`].
+//!
+//! The files must not be modified by other processes while they are mapped.
+//!
+//! With the `tracing` feature each conversion runs in a `debug` span with the path of its
+//! input and ends with a `debug` event of the words converted and the time taken, or a `warn`
+//! event if a partial word was left over.
+
+#[cfg(feature = "half")]
+use crate::F16;
+use crate::{MilFormat, F32, F48};
+#[cfg(feature = "half")]
+use half::f16;
+use memmap2::{Mmap, MmapMut};
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+/// Summary of a memory-mapped conversion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Converted {
+    /// Number of words converted
+    pub words: usize,
+    /// Number of trailing bytes that did not form a whole word
+    pub tail: usize,
+}
+
+/// Convert a file of 16-bit MIL-1750A words into IEEE half precision, in place
+///
+/// # Arguments
+///
+/// * `path`: file of big-endian 16-bit MIL-1750A words
+///
+/// returns: number of words converted and size of the unconverted tail
+///
+/// # Examples
+///
+/// ```no_run
+/// use mil1750a_converter::mmap::decode_16_in_place;
+/// let converted = decode_16_in_place("channel.bin").unwrap();
+/// println!("{} words converted", converted.words);
+/// ```
+#[cfg(feature = "half")]
+pub fn decode_16_in_place(path: impl AsRef<Path>) -> io::Result<Converted> {
+    convert_in_place::<2>(path.as_ref(), |word| {
+        F16::decode(u16::from_be_bytes(word)).to_ne_bytes()
+    })
+}
+
+/// Convert a file of IEEE half precision values into 16-bit MIL-1750A words, in place
+///
+/// # Arguments
+///
+/// * `path`: file of native-endian `f16` values
+///
+/// returns: number of values converted and size of the unconverted tail
+///
+/// # Examples
+///
+/// ```no_run
+/// use mil1750a_converter::mmap::encode_16_in_place;
+/// encode_16_in_place("channel.bin").unwrap();
+/// ```
+#[cfg(feature = "half")]
+pub fn encode_16_in_place(path: impl AsRef<Path>) -> io::Result<Converted> {
+    convert_in_place::<2>(path.as_ref(), |value| {
+        F16::encode(f16::from_ne_bytes(value)).to_be_bytes()
+    })
+}
+
+/// Convert a file of 32-bit MIL-1750A words into IEEE single precision, in place
+///
+/// # Arguments
+///
+/// * `path`: file of big-endian 32-bit MIL-1750A words
+///
+/// returns: number of words converted and size of the unconverted tail
+///
+/// # Examples
+///
+/// ```no_run
+/// use mil1750a_converter::mmap::decode_32_in_place;
+/// let converted = decode_32_in_place("archive.bin").unwrap();
+/// assert_eq!(converted.tail, 0, "archive is not made of whole words");
+/// ```
+pub fn decode_32_in_place(path: impl AsRef<Path>) -> io::Result<Converted> {
+    convert_in_place::<4>(path.as_ref(), |word| {
+        F32::decode(u32::from_be_bytes(word)).to_ne_bytes()
+    })
+}
+
+/// Convert a file of IEEE single precision values into 32-bit MIL-1750A words, in place
+///
+/// # Arguments
+///
+/// * `path`: file of native-endian `f32` values
+///
+/// returns: number of values converted and size of the unconverted tail
+///
+/// # Examples
+///
+/// ```no_run
+/// use mil1750a_converter::mmap::encode_32_in_place;
+/// encode_32_in_place("archive.bin").unwrap();
+/// ```
+pub fn encode_32_in_place(path: impl AsRef<Path>) -> io::Result<Converted> {
+    convert_in_place::<4>(path.as_ref(), |value| {
+        F32::encode(f32::from_ne_bytes(value)).to_be_bytes()
+    })
+}
+
+/// Convert a file of 48-bit MIL-1750A words into a file of IEEE double precision values
+///
+/// The output is created (or truncated) and sized to hold one `f64` per input word. It must
+/// not be the input: that is an [`io::ErrorKind::InvalidInput`] error, and the input is left as
+/// it was.
+///
+/// # Arguments
+///
+/// * `input`: file of big-endian 48-bit MIL-1750A words
+/// * `output`: destination for native-endian `f64` values
+///
+/// returns: number of words converted and size of the unconverted input tail
+///
+/// # Examples
+///
+/// ```no_run
+/// use mil1750a_converter::mmap::decode_48_to_file;
+/// decode_48_to_file("archive.bin", "archive.f64").unwrap();
+/// ```
+pub fn decode_48_to_file(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+) -> io::Result<Converted> {
+    convert_to_file::<6, 8>(input.as_ref(), output.as_ref(), |word| {
+        let mut bits = [0u8; 8];
+        bits[2..].copy_from_slice(&word);
+        F48::decode(u64::from_be_bytes(bits)).to_ne_bytes()
+    })
+}
+
+/// Convert a file of IEEE double precision values into a file of 48-bit MIL-1750A words
+///
+/// The output is created (or truncated) and sized to hold one word per input value. It must not
+/// be the input, as for [`decode_48_to_file`].
+///
+/// # Arguments
+///
+/// * `input`: file of native-endian `f64` values
+/// * `output`: destination for big-endian 48-bit MIL-1750A words
+///
+/// returns: number of values converted and size of the unconverted input tail
+///
+/// # Examples
+///
+/// ```no_run
+/// use mil1750a_converter::mmap::encode_48_to_file;
+/// encode_48_to_file("table.f64", "table.bin").unwrap();
+/// ```
+pub fn encode_48_to_file(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+) -> io::Result<Converted> {
+    convert_to_file::<8, 6>(input.as_ref(), output.as_ref(), |value| {
+        let bits = F48::encode(f64::from_ne_bytes(value)).to_be_bytes();
+        let mut word = [0u8; 6];
+        word.copy_from_slice(&bits[2..]);
+        word
+    })
+}
+
+#[cfg(feature = "tracing")]
+impl Converted {
+    /// Trace the outcome of a conversion that started at `start`
+    fn trace(&self, start: std::time::Instant) {
+        if self.tail > 0 {
+            tracing::warn!(
+                words = self.words,
+                tail = self.tail,
+                "partial word left over"
+            );
+        } else {
+            tracing::debug!(words = self.words, elapsed = ?start.elapsed(), "file converted");
+        }
+    }
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(path = %path.display()))
+)]
+fn convert_in_place<const N: usize>(
+    path: &Path,
+    convert: impl Fn([u8; N]) -> [u8; N],
+) -> io::Result<Converted> {
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+    let file = OpenOptions::new().read(true).write(true).open(path)?;
+    let len = file.metadata()?.len() as usize;
+    if len < N {
+        return Ok(Converted {
+            words: 0,
+            tail: len,
+        });
+    }
+
+    // SAFETY: the module documents that mapped files must not be modified concurrently.
+    let mut map = unsafe { MmapMut::map_mut(&file)? };
+    let mut chunks = map.chunks_exact_mut(N);
+    let mut words = 0;
+    for chunk in &mut chunks {
+        let word: [u8; N] = (&*chunk).try_into().unwrap();
+        chunk.copy_from_slice(&convert(word));
+        words += 1;
+    }
+    let tail = chunks.into_remainder().len();
+    map.flush()?;
+
+    let converted = Converted { words, tail };
+    #[cfg(feature = "tracing")]
+    converted.trace(start);
+    Ok(converted)
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(path = %input.display()))
+)]
+fn convert_to_file<const I: usize, const O: usize>(
+    input: &Path,
+    output: &Path,
+    convert: impl Fn([u8; I]) -> [u8; O],
+) -> io::Result<Converted> {
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+    let source = File::open(input)?;
+    let len = source.metadata()?.len() as usize;
+    let words = len / I;
+    let tail = len % I;
+    // Truncating the output would wipe the input before it is mapped
+    if let Ok(output) = fs::canonicalize(output) {
+        if output == fs::canonicalize(input)? {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is both the input and the output", output.display()),
+            ));
+        }
+    }
+
+    let destination = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(output)?;
+    destination.set_len((words * O) as u64)?;
+    if words == 0 {
+        return Ok(Converted { words, tail });
+    }
+
+    // SAFETY: the module documents that mapped files must not be modified concurrently.
+    let source = unsafe { Mmap::map(&source)? };
+    let mut destination = unsafe { MmapMut::map_mut(&destination)? };
+    for (word, value) in source.chunks_exact(I).zip(destination.chunks_exact_mut(O)) {
+        value.copy_from_slice(&convert(word.try_into().unwrap()));
+    }
+    destination.flush()?;
+
+    let converted = Converted { words, tail };
+    #[cfg(feature = "tracing")]
+    converted.trace(start);
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("mil1750a-{}-{name}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_decode_32_in_place_keeps_tail() {
+        let path = temp_file(
+            "decode32",
+            &[0x40, 0x00, 0x00, 0x01, 0x53, 0xBE, 0x77, 0x03, 0xAA],
+        );
+        let converted = decode_32_in_place(&path).unwrap();
+        assert_eq!(converted, Converted { words: 2, tail: 1 });
+
+        let bytes = fs::read(&path).unwrap();
+        assert_eq!(f32::from_ne_bytes(bytes[0..4].try_into().unwrap()), 1.0);
+        assert_eq!(
+            f32::from_ne_bytes(bytes[4..8].try_into().unwrap()),
+            F32::decode(0x53BE7703)
+        );
+        assert_eq!(bytes[8], 0xAA);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_encode_32_in_place() {
+        let path = temp_file("encode32", &5.234f32.to_ne_bytes());
+        assert_eq!(
+            encode_32_in_place(&path).unwrap(),
+            Converted { words: 1, tail: 0 }
+        );
+        assert_eq!(fs::read(&path).unwrap(), [0x53, 0xBE, 0x77, 0x03]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_16_in_place_round_trip() {
+        let path = temp_file("round16", &[0x63, 0x44, 0x80, 0x00]);
+        decode_16_in_place(&path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        assert_eq!(f16::from_ne_bytes([bytes[2], bytes[3]]), f16::NEG_ONE);
+        encode_16_in_place(&path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), [0x63, 0x44, 0x80, 0x00]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_48_to_file_and_back() {
+        let input = temp_file("in48", &[0x69, 0xA3, 0xB5, 0x07, 0x54, 0xAB, 0x01, 0x02]);
+        let output = temp_file("out48", &[]);
+        let back = temp_file("back48", &[]);
+
+        let converted = decode_48_to_file(&input, &output).unwrap();
+        assert_eq!(converted, Converted { words: 1, tail: 2 });
+        let bytes = fs::read(&output).unwrap();
+        assert_eq!(
+            f64::from_ne_bytes(bytes[..].try_into().unwrap()),
+            105.63948563742451
+        );
+
+        assert_eq!(
+            encode_48_to_file(&output, &back).unwrap(),
+            Converted { words: 1, tail: 0 }
+        );
+        assert_eq!(
+            fs::read(&back).unwrap(),
+            [0x69, 0xA3, 0xB5, 0x07, 0x54, 0xAB]
+        );
+
+        for path in [input, output, back] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_to_file_rejects_input() {
+        let words = [0x69, 0xA3, 0xB5, 0x07, 0x54, 0xAB];
+        let path = temp_file("same48", &words);
+        let error = decode_48_to_file(&path, &path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        // The same file by another name
+        let name = path.file_name().unwrap();
+        let alias = path.parent().unwrap().join(".").join(name);
+        let error = encode_48_to_file(&path, alias).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(fs::read(&path).unwrap(), words);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_empty_file() {
+        let path = temp_file("empty", &[]);
+        assert_eq!(decode_32_in_place(&path).unwrap(), Converted::default());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+//! [`num_traits`] implementations for [`Mil32`] and [`Mil48`].
+//!
+//! Arithmetic goes through the [`arith`](crate::arith) engine. `Float` is deliberately not
+//! implemented: MIL-1750A has no NaN, infinities or signed zero, so large parts of that trait
+//! cannot be honoured.
+
+use crate::{arith, Mil32, Mil48};
+use num_traits::{Bounded, FromPrimitive, Num, NumCast, One, ToPrimitive, Zero};
+
+impl Zero for Mil32 {
+    fn zero() -> Self {
+        Mil32::from_bits(0)
+    }
+
+    /// Any word with a zero mantissa is zero, whatever its exponent
+    fn is_zero(&self) -> bool {
+        self.to_bits() >> 8 == 0
+    }
+}
+
+impl One for Mil32 {
+    fn one() -> Self {
+        Mil32::from_bits(0x40000001)
+    }
+}
+
+impl Num for Mil32 {
+    type FromStrRadixErr = <f64 as Num>::FromStrRadixErr;
+
+    /// Parse as an `f64` and round once to the format, saturating
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        f64::from_str_radix(str, radix).map(|value| Mil32::from_bits(arith::round32(value)))
+    }
+}
+
+impl Bounded for Mil32 {
+    fn min_value() -> Self {
+        Mil32::from_bits(0x8000007F)
+    }
+
+    fn max_value() -> Self {
+        Mil32::from_bits(0x7FFFFF7F)
+    }
+}
+
+impl ToPrimitive for Mil32 {
+    fn to_i64(&self) -> Option<i64> {
+        ToPrimitive::to_f64(self).and_then(|value| value.to_i64())
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        ToPrimitive::to_f64(self).and_then(|value| value.to_u64())
+    }
+
+    /// The exact value of the word
+    fn to_f64(&self) -> Option<f64> {
+        Some(arith::value32(self.to_bits()))
+    }
+}
+
+impl FromPrimitive for Mil32 {
+    fn from_i64(n: i64) -> Option<Self> {
+        <Self as FromPrimitive>::from_f64(n as f64)
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        <Self as FromPrimitive>::from_f64(n as f64)
+    }
+
+    /// Round to the format, or `None` for NaN, infinities and out-of-range magnitudes
+    fn from_f64(n: f64) -> Option<Self> {
+        arith::try_round32(n).map(Mil32::from_bits)
+    }
+}
+
+impl NumCast for Mil32 {
+    fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+        n.to_f64().and_then(<Self as FromPrimitive>::from_f64)
+    }
+}
+
+impl Zero for Mil48 {
+    fn zero() -> Self {
+        Mil48::from_bits(0)
+    }
+
+    /// Any word with a zero mantissa is zero, whatever its exponent
+    fn is_zero(&self) -> bool {
+        self.to_bits() & 0xFFFFFF00FFFF == 0
+    }
+}
+
+impl One for Mil48 {
+    fn one() -> Self {
+        Mil48::from_bits(0x400000010000)
+    }
+}
+
+impl Num for Mil48 {
+    type FromStrRadixErr = <f64 as Num>::FromStrRadixErr;
+
+    /// Parse as an `f64` and round once to the format, saturating
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        f64::from_str_radix(str, radix).map(|value| Mil48::from_bits(arith::round48(value)))
+    }
+}
+
+impl Bounded for Mil48 {
+    fn min_value() -> Self {
+        Mil48::from_bits(0x8000007F0000)
+    }
+
+    fn max_value() -> Self {
+        Mil48::from_bits(0x7FFFFF7FFFFF)
+    }
+}
+
+impl ToPrimitive for Mil48 {
+    fn to_i64(&self) -> Option<i64> {
+        ToPrimitive::to_f64(self).and_then(|value| value.to_i64())
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        ToPrimitive::to_f64(self).and_then(|value| value.to_u64())
+    }
+
+    /// The exact value of the word
+    fn to_f64(&self) -> Option<f64> {
+        Some(arith::value48(self.to_bits()))
+    }
+}
+
+impl FromPrimitive for Mil48 {
+    fn from_i64(n: i64) -> Option<Self> {
+        <Self as FromPrimitive>::from_f64(n as f64)
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        <Self as FromPrimitive>::from_f64(n as f64)
+    }
+
+    /// Round to the format, or `None` for NaN, infinities and out-of-range magnitudes
+    fn from_f64(n: f64) -> Option<Self> {
+        arith::try_round48(n).map(Mil48::from_bits)
+    }
+}
+
+impl NumCast for Mil48 {
+    fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+        n.to_f64().and_then(<Self as FromPrimitive>::from_f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum<T: Num + Copy>(values: &[T]) -> T {
+        values.iter().fold(T::zero(), |acc, &value| acc + value)
+    }
+
+    #[test]
+    fn test_generic_code() {
+        let values = [1.0, 2.5, -0.25].map(|value| <Mil32 as NumCast>::from(value).unwrap());
+        assert_eq!(ToPrimitive::to_f64(&sum(&values)), Some(3.25));
+        let values = [1.0, 2.5, -0.25].map(|value| <Mil48 as NumCast>::from(value).unwrap());
+        assert_eq!(ToPrimitive::to_f64(&sum(&values)), Some(3.25));
+    }
+
+    #[test]
+    fn test_zero_one() {
+        assert!(Mil32::from_bits(0x0000007F).is_zero());
+        assert!(!Mil32::one().is_zero());
+        assert!(Mil48::from_bits(0x000000FF0000).is_zero());
+        assert_eq!(ToPrimitive::to_f64(&Mil48::one()), Some(1.0));
+    }
+
+    #[test]
+    fn test_from_str_radix() {
+        assert_eq!(
+            Mil32::from_str_radix("5.234", 10).unwrap().to_bits(),
+            0x53BE7703
+        );
+        assert_eq!(
+            Mil48::from_str_radix("-1", 10).unwrap().to_bits(),
+            0x800000000000
+        );
+        assert!(Mil32::from_str_radix("five", 10).is_err());
+    }
+
+    #[test]
+    fn test_bounds_and_casts() {
+        assert_eq!(
+            Mil32::max_value().to_f64(),
+            Some((1.0 - 2f64.powi(-23)) * 2f64.powi(127))
+        );
+        assert_eq!(
+            ToPrimitive::to_f64(&Mil48::min_value()),
+            Some(-(2f64.powi(127)))
+        );
+        assert_eq!(<Mil32 as NumCast>::from(3u8).unwrap().to_i64(), Some(3));
+        assert!(<Mil32 as FromPrimitive>::from_f64(1e300).is_none());
+        assert!(<Mil32 as FromPrimitive>::from_f64(1e-300).is_none());
+        assert!(<Mil48 as FromPrimitive>::from_f64(f64::NAN).is_none());
+        assert_eq!(<Mil48 as FromPrimitive>::from_f64(0.0), Some(Mil48::zero()));
+    }
+}
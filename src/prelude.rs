@@ -0,0 +1,17 @@
+//! # Prelude
+//! The types and traits most code needs, for a single glob import.
+//!
+//! ```
+//! use mil1750a_converter::prelude::*;
+//! let word: Mil32 = "25.63".parse().unwrap();
+//! assert_eq!(word.to_bits(), 0x66851F05);
+//! assert_eq!(F32::decode(0x40000001), 1.0);
+//! ```
+
+pub use crate::iter::Mil1750aIterExt;
+#[cfg(feature = "half")]
+pub use crate::F16;
+pub use crate::{
+    BufferError, ByteOrder, DecodeError, DecodeMode, Decoded, EncodeMode, Mil16, Mil1750Error,
+    Mil32, Mil48, MilFormat, ParseError, WordOrder, F32, F48,
+};
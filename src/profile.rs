@@ -0,0 +1,567 @@
+//! # Target profiles
+//! The choices that differ between 1750A implementations, made once for each target.
+//!
+//! Processors and cores built to MIL-STD-1750A share its formats but not always its least
+//! significant bit: some round to nearest and others truncate, some fault on overflow rather
+//! than saturate, pre-standard boxes keep their mantissas in sign-magnitude rather than two's
+//! complement, and the recorders and buses around them store words in different orders. A
+//! [`TargetProfile`] bundles those choices, so code that converts or computes for a target
+//! takes one value rather than a mode at every call. [`TargetProfile::STANDARD`] is the
+//! behaviour of the rest of the crate: [`MilFormat::encode`] and the [`arith`]
+//! operations, on big-endian words most significant word first.
+//!
+//! ```
+//! use mil1750a_converter::profile::{Dialect, Overflow, Rounding, TargetProfile};
+//! use mil1750a_converter::{ByteOrder, Mil1750Error, WordOrder, F32};
+//!
+//! // A core that truncates its results, faults on overflow, and stores words swapped
+//! let core = TargetProfile {
+//!     rounding: Rounding::TowardZero,
+//!     arithmetic: Rounding::TowardNegative,
+//!     overflow: Overflow::Error,
+//!     dialect: Dialect::TwosComplement,
+//!     word_order: WordOrder::LsWordFirst,
+//!     byte_order: ByteOrder::BigEndian,
+//! };
+//! assert_eq!(TargetProfile::STANDARD.encode32(0.7), Ok(0x5999_9A00));
+//! assert_eq!(core.encode32(0.7), Ok(0x5999_9900));
+//! assert!(matches!(core.encode32(f32::MAX), Err(Mil1750Error::OutOfRange { .. })));
+//!
+//! let mut bytes = [0; 4];
+//! core.write::<F32>(0x4000_0001, &mut bytes);
+//! assert_eq!(bytes, [0x00, 0x01, 0x40, 0x00]);
+//! assert_eq!(core.decode::<F32>(core.read::<F32>(&bytes)), 1.0);
+//! ```
+//!
+//! For a dump from an undocumented target, [`decode_each`] reads the same bytes under several
+//! profiles side by side and flags the words whose values the profiles disagree on.
+//!
+//! ```
+//! use mil1750a_converter::profile::{decode_each, TargetProfile};
+//! use mil1750a_converter::{WordOrder, F32};
+//!
+//! let swapped = TargetProfile {
+//!     word_order: WordOrder::LsWordFirst,
+//!     ..TargetProfile::STANDARD
+//! };
+//! let bytes = [0x40, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00];
+//! let words = decode_each::<F32>(&bytes, &[TargetProfile::STANDARD, swapped]);
+//! assert_eq!(words[0].values, [1.0, 5.0 * 2f32.powi(-17)]);
+//! assert!(words[0].differs());
+//! assert!(!words[1].differs());
+//! ```
+
+use crate::arith::{self, Op};
+use crate::{ByteOrder, Mil1750Error, MilFormat, TargetFormat, WordOrder};
+
+/// How a result between two words is rounded to one of them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// To the nearer word, ties away from zero, as the encoders and [`arith`] round
+    #[default]
+    NearestTiesAway,
+    /// To the nearer word, ties to the one with an even mantissa
+    NearestTiesEven,
+    /// To the word nearer zero, truncating the magnitude
+    TowardZero,
+    /// To the word below, as hardware that drops the low bits of a two's-complement mantissa
+    /// does
+    TowardNegative,
+}
+
+/// What happens to a result too large or too small in magnitude for its format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// Saturate to the most positive or most negative word, and flush to zero below the
+    /// smallest magnitude
+    #[default]
+    Saturate,
+    /// Fail with [`Mil1750Error::OutOfRange`], or [`Mil1750Error::NotFinite`] for infinities
+    /// and division by zero
+    Error,
+}
+
+/// How the mantissa of a word carries its sign
+///
+/// The exponent is two's complement in both dialects, and zero is all-zero bits. A word-swapped
+/// legacy layout is a [`WordOrder`] and [`ByteOrder`] of its own, and combines with either.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::profile::{Dialect, TargetProfile};
+///
+/// let legacy = TargetProfile {
+///     dialect: Dialect::SignMagnitude,
+///     ..TargetProfile::STANDARD
+/// };
+/// // -1.0 is a magnitude of 0.5 at exponent 1, with the sign bit set
+/// assert_eq!(legacy.encode32(-1.0), Ok(0xC000_0001));
+/// assert_eq!(TargetProfile::STANDARD.encode32(-1.0), Ok(0x8000_0000));
+/// assert_eq!(legacy.decode32(0xC000_0001), -1.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// A two's-complement mantissa, as the standard defines it
+    #[default]
+    TwosComplement,
+    /// A sign bit at the top of the mantissa field and the magnitude below it, normalized so
+    /// its top bit is set, as some pre-standard implementations store it
+    SignMagnitude,
+}
+
+/// The rounding, overflow and storage of one 1750A implementation, see the
+/// [module documentation](self)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TargetProfile {
+    /// How values are rounded to words when encoding
+    pub rounding: Rounding,
+    /// How the results of arithmetic are rounded
+    pub arithmetic: Rounding,
+    /// What happens to values and results beyond the format
+    pub overflow: Overflow,
+    /// How the mantissa carries its sign
+    pub dialect: Dialect,
+    /// Order of the 16-bit words of 32- and 48-bit words
+    pub word_order: WordOrder,
+    /// Order of the two bytes of each 16-bit word
+    pub byte_order: ByteOrder,
+}
+
+impl TargetProfile {
+    /// The behaviour of the standard, and of the rest of the crate
+    pub const STANDARD: Self = TargetProfile {
+        rounding: Rounding::NearestTiesAway,
+        arithmetic: Rounding::NearestTiesAway,
+        overflow: Overflow::Saturate,
+        dialect: Dialect::TwosComplement,
+        word_order: WordOrder::MsWordFirst,
+        byte_order: ByteOrder::BigEndian,
+    };
+
+    /// Encode a value of any format, see [`MilFormat::encode_for`]
+    pub fn encode<F: MilFormat>(&self, value: F::Ieee) -> Result<F::Bits, Mil1750Error> {
+        F::encode_for(value, self)
+    }
+
+    /// Decode a word of any format, see [`MilFormat::decode_for`]; in the standard dialect
+    /// that is [`MilFormat::decode`], since implementations agree on the value of every word
+    pub fn decode<F: MilFormat>(&self, bits: F::Bits) -> F::Ieee {
+        F::decode_for(bits, self)
+    }
+
+    /// Encode an `f32` as a 16-bit word; NaN is always an error
+    pub fn encode16(&self, value: f32) -> Result<u16, Mil1750Error> {
+        let value = f64::from(value);
+        self.encoded(
+            value,
+            TargetFormat::Float16,
+            arith::try_round16_with(value, self.rounding, self.dialect),
+        )
+    }
+
+    /// Encode an `f32` as a 32-bit word; NaN is always an error
+    pub fn encode32(&self, value: f32) -> Result<u32, Mil1750Error> {
+        let value = f64::from(value);
+        self.encoded(
+            value,
+            TargetFormat::Float32,
+            arith::try_round32_with(value, self.rounding, self.dialect),
+        )
+    }
+
+    /// Encode an `f64` as a 48-bit word; NaN is always an error
+    pub fn encode48(&self, value: f64) -> Result<u64, Mil1750Error> {
+        self.encoded(
+            value,
+            TargetFormat::Float48,
+            arith::try_round48_with(value, self.rounding, self.dialect),
+        )
+    }
+
+    /// Decode a 16-bit word to `f32`, which holds its value exactly
+    pub fn decode16(&self, bits: u16) -> f32 {
+        arith::value16_in(bits, self.dialect) as f32
+    }
+
+    /// Decode a 32-bit word to `f32`
+    pub fn decode32(&self, bits: u32) -> f32 {
+        arith::value32_in(bits, self.dialect) as f32
+    }
+
+    /// Decode a 48-bit word to `f64`, which holds its value exactly
+    pub fn decode48(&self, bits: u64) -> f64 {
+        arith::value48_in(bits, self.dialect)
+    }
+
+    /// Read a word from the first [`BYTES`](MilFormat::BYTES) bytes of `bytes`, in the
+    /// profile's word and byte order
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than a word.
+    pub fn read<F: MilFormat>(&self, bytes: &[u8]) -> F::Bits {
+        let mut word = [0; 6];
+        word[..F::BYTES].copy_from_slice(&bytes[..F::BYTES]);
+        self.arrange_bytes(&mut word[..F::BYTES]);
+        F::read_ordered(&word, self.word_order)
+    }
+
+    /// Write a word to the first [`BYTES`](MilFormat::BYTES) bytes of `out`, in the profile's
+    /// word and byte order
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is shorter than a word.
+    pub fn write<F: MilFormat>(&self, bits: F::Bits, out: &mut [u8]) {
+        F::write_ordered(bits, self.word_order, out);
+        self.arrange_bytes(&mut out[..F::BYTES]);
+    }
+
+    /// Add two 32-bit words
+    pub fn add32(&self, a: u32, b: u32) -> Result<u32, Mil1750Error> {
+        self.apply32(Op::Add, a, b)
+    }
+
+    /// Subtract the 32-bit word `b` from `a`
+    pub fn sub32(&self, a: u32, b: u32) -> Result<u32, Mil1750Error> {
+        self.apply32(Op::Sub, a, b)
+    }
+
+    /// Multiply two 32-bit words
+    pub fn mul32(&self, a: u32, b: u32) -> Result<u32, Mil1750Error> {
+        self.apply32(Op::Mul, a, b)
+    }
+
+    /// Divide the 32-bit word `a` by `b`
+    pub fn div32(&self, a: u32, b: u32) -> Result<u32, Mil1750Error> {
+        self.apply32(Op::Div, a, b)
+    }
+
+    /// Add two 48-bit words
+    pub fn add48(&self, a: u64, b: u64) -> Result<u64, Mil1750Error> {
+        self.apply48(Op::Add, a, b)
+    }
+
+    /// Subtract the 48-bit word `b` from `a`
+    pub fn sub48(&self, a: u64, b: u64) -> Result<u64, Mil1750Error> {
+        self.apply48(Op::Sub, a, b)
+    }
+
+    /// Multiply two 48-bit words
+    pub fn mul48(&self, a: u64, b: u64) -> Result<u64, Mil1750Error> {
+        self.apply48(Op::Mul, a, b)
+    }
+
+    /// Divide the 48-bit word `a` by `b`
+    pub fn div48(&self, a: u64, b: u64) -> Result<u64, Mil1750Error> {
+        self.apply48(Op::Div, a, b)
+    }
+
+    fn apply32(&self, op: Op, a: u32, b: u32) -> Result<u32, Mil1750Error> {
+        let value = |word| arith::value32_in(word, self.dialect);
+        let exact = || exact(op, value(a), value(b));
+        let result = arith::apply32(op, a, b, self.arithmetic, self.dialect);
+        self.resolved(result, exact, TargetFormat::Float32)
+    }
+
+    fn apply48(&self, op: Op, a: u64, b: u64) -> Result<u64, Mil1750Error> {
+        let value = |word| arith::value48_in(word, self.dialect);
+        let exact = || exact(op, value(a), value(b));
+        let result = arith::apply48(op, a, b, self.arithmetic, self.dialect);
+        self.resolved(result, exact, TargetFormat::Float48)
+    }
+
+    /// The word of an encoded `value`, rejecting NaN
+    fn encoded<T>(
+        &self,
+        value: f64,
+        target: TargetFormat,
+        result: Result<T, T>,
+    ) -> Result<T, Mil1750Error> {
+        if value.is_nan() {
+            return Err(Mil1750Error::NotFinite { value, target });
+        }
+        self.resolved(result, || value, target)
+    }
+
+    /// The word of a rounded result, or, if it is beyond the format, its clamped word or an
+    /// error by the overflow policy
+    fn resolved<T>(
+        &self,
+        result: Result<T, T>,
+        value: impl FnOnce() -> f64,
+        target: TargetFormat,
+    ) -> Result<T, Mil1750Error> {
+        match (result, self.overflow) {
+            (Ok(word), _) | (Err(word), Overflow::Saturate) => Ok(word),
+            (Err(_), Overflow::Error) => {
+                let value = value();
+                Err(if value.is_finite() {
+                    Mil1750Error::OutOfRange { value, target }
+                } else {
+                    Mil1750Error::NotFinite { value, target }
+                })
+            }
+        }
+    }
+
+    /// Convert the bytes of a word between big-endian 16-bit words and the profile's byte
+    /// order, which is the same in both directions
+    fn arrange_bytes(&self, word: &mut [u8]) {
+        if self.byte_order == ByteOrder::LittleEndian {
+            word.chunks_exact_mut(2).for_each(|pair| pair.swap(0, 1));
+        }
+    }
+}
+
+/// One word of a dump as each of several profiles reads it, see [`decode_each`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interpretations<T> {
+    /// Byte offset of the word in the dump
+    pub offset: usize,
+    /// The value of the word under each profile, in the order the profiles were given
+    pub values: Vec<T>,
+}
+
+impl<T: PartialEq> Interpretations<T> {
+    /// Whether any two profiles read the word as different values
+    pub fn differs(&self) -> bool {
+        self.values.windows(2).any(|pair| pair[0] != pair[1])
+    }
+}
+
+/// Decode every whole word of `bytes` under each of `profiles`; a partial word at the end is
+/// ignored
+pub fn decode_each<F: MilFormat>(
+    bytes: &[u8],
+    profiles: &[TargetProfile],
+) -> Vec<Interpretations<F::Ieee>> {
+    bytes
+        .chunks_exact(F::BYTES)
+        .enumerate()
+        .map(|(index, word)| Interpretations {
+            offset: index * F::BYTES,
+            values: profiles
+                .iter()
+                .map(|profile| profile.decode::<F>(profile.read::<F>(word)))
+                .collect(),
+        })
+        .collect()
+}
+
+/// The result of `op`, as near as an `f64` holds it, for reporting
+fn exact(op: Op, a: f64, b: f64) -> f64 {
+    match op {
+        Op::Add => a + b,
+        Op::Sub => a - b,
+        Op::Mul => a * b,
+        Op::Div => a / b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{extended, single, EncodeMode, F48};
+
+    const TRUNCATING: TargetProfile = TargetProfile {
+        rounding: Rounding::TowardNegative,
+        arithmetic: Rounding::TowardNegative,
+        overflow: Overflow::Error,
+        dialect: Dialect::TwosComplement,
+        word_order: WordOrder::LsWordFirst,
+        byte_order: ByteOrder::LittleEndian,
+    };
+
+    #[test]
+    fn test_standard() {
+        let standard = TargetProfile::default();
+        assert_eq!(standard, TargetProfile::STANDARD);
+        for value in [0.7f32, -0.7, 5.234, -25.63, 1e30, -3e-30, f32::MAX, 0.0] {
+            assert_eq!(
+                standard.encode32(value),
+                Ok(single::encode_with(value, EncodeMode::TwosComplement))
+            );
+        }
+        assert_eq!(
+            standard.encode48(-0.1),
+            Ok(extended::encode_with(-0.1, EncodeMode::TwosComplement))
+        );
+        assert_eq!(standard.encode16(1.0), Ok(0x4001));
+        let (a, b) = (0x53BE7703, 0x997AE105);
+        assert_eq!(standard.add32(a, b), Ok(arith::add32(a, b)));
+        assert_eq!(standard.div32(a, b), Ok(arith::div32(a, b)));
+        assert_eq!(standard.div32(a, 0), Ok(0x7FFFFF7F));
+    }
+
+    #[test]
+    fn test_rounding() {
+        let profile = |rounding| TargetProfile {
+            rounding,
+            ..TargetProfile::STANDARD
+        };
+        // 0.7 lies between two words, nearer the upper
+        let (below, above) = (0x5999_9900, 0x5999_9A00);
+        let words = [
+            Rounding::NearestTiesAway,
+            Rounding::NearestTiesEven,
+            Rounding::TowardZero,
+            Rounding::TowardNegative,
+        ]
+        .map(|rounding| profile(rounding).encode32(0.7).unwrap());
+        assert_eq!(words, [above, above, below, below]);
+        let negative = [Rounding::TowardZero, Rounding::TowardNegative]
+            .map(|rounding| profile(rounding).encode32(-0.7).unwrap());
+        assert_eq!(
+            negative.map(arith::value32),
+            [-arith::value32(below), -arith::value32(above)]
+        );
+
+        // Halfway between 0.5 and the word above it
+        let tie = 0.5 + 2f32.powi(-24);
+        assert_eq!(
+            profile(Rounding::NearestTiesAway).encode32(tie),
+            Ok(0x4000_0100)
+        );
+        assert_eq!(
+            profile(Rounding::NearestTiesEven).encode32(tie),
+            Ok(0x4000_0000)
+        );
+        assert_eq!(profile(Rounding::TowardZero).encode16(0.99), Ok(0x7E80));
+        assert_eq!(TargetProfile::STANDARD.encode16(0.99), Ok(0x7EC0));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let (one, three) = (0x4000_0001, 0x6000_0002);
+        let third = TargetProfile::STANDARD.div32(one, three).unwrap();
+        assert_eq!(TRUNCATING.div32(one, three), Ok(third));
+        let minus_one = single::encode(-1.0);
+        assert_eq!(
+            arith::value32(TRUNCATING.div32(minus_one, three).unwrap()),
+            -arith::value32(third) - arith::ulp32(third)
+        );
+        assert!(matches!(
+            TRUNCATING.mul32(0x7FFF_FF7F, 0x4000_0002),
+            Err(Mil1750Error::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            TRUNCATING.div48(0x4000_0001_0000, 0),
+            Err(Mil1750Error::NotFinite { .. })
+        ));
+        // A far smaller addend still moves a truncated sum down
+        let tiny = extended::encode_with(-1e-30, EncodeMode::TwosComplement);
+        let one48 = 0x4000_0001_0000;
+        assert_eq!(TargetProfile::STANDARD.add48(one48, tiny), Ok(one48));
+        assert!(arith::value48(TRUNCATING.add48(one48, tiny).unwrap()) < 1.0);
+        assert_eq!(TRUNCATING.sub48(one48, one48), Ok(0));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(
+            TargetProfile::STANDARD.encode32(f32::INFINITY),
+            Ok(0x7FFF_FF7F)
+        );
+        assert!(matches!(
+            TRUNCATING.encode32(f32::INFINITY),
+            Err(Mil1750Error::NotFinite { .. })
+        ));
+        assert!(matches!(
+            TargetProfile::STANDARD.encode16(f32::NAN),
+            Err(Mil1750Error::NotFinite { .. })
+        ));
+        assert_eq!(
+            TRUNCATING.encode16(1e10),
+            Err(Mil1750Error::OutOfRange {
+                value: 1e10,
+                target: TargetFormat::Float16
+            })
+        );
+        assert!(TRUNCATING.encode48(1e-60).is_err());
+        assert_eq!(TargetProfile::STANDARD.encode48(1e-60), Ok(0));
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut bytes = [0xFF; 7];
+        TRUNCATING.write::<F48>(0x69A3_B507_54AB, &mut bytes);
+        assert_eq!(bytes, [0xAB, 0x54, 0x07, 0xB5, 0xA3, 0x69, 0xFF]);
+        assert_eq!(TRUNCATING.read::<F48>(&bytes), 0x69A3_B507_54AB);
+        assert_eq!(TRUNCATING.encode::<F48>(1.0), Ok(0x4000_0001_0000));
+        assert_eq!(TRUNCATING.decode::<F48>(0x4000_0001_0000), 1.0);
+    }
+
+    #[test]
+    fn test_sign_magnitude() {
+        let legacy = TargetProfile {
+            dialect: Dialect::SignMagnitude,
+            ..TargetProfile::STANDARD
+        };
+        for (value, word) in [
+            (1.0, 0x4000_0001),
+            (-1.0, 0xC000_0001),
+            (-0.75, 0xE000_0000),
+            (-(2f64.powi(-129) as f32), 0xC000_0080),
+            (0.0, 0),
+        ] {
+            assert_eq!(legacy.encode32(value), Ok(word), "{value}");
+            assert_eq!(legacy.decode32(word), value);
+        }
+        assert_eq!(legacy.decode32(0x8000_0000), 0.0);
+        assert_eq!(
+            TargetProfile::STANDARD.encode32(-(2f64.powi(-129) as f32)),
+            Ok(0)
+        );
+        assert_eq!(legacy.encode32(-f32::MAX), Ok(0xFFFF_FF7F));
+        assert_eq!(legacy.encode16(-1.0), Ok(0xC001));
+        assert_eq!(legacy.encode16(-1e30), Ok(0xFFDF));
+        assert_eq!(legacy.decode16(0xC001), -1.0);
+        assert_eq!(legacy.encode48(-1.0), Ok(0xC000_0001_0000));
+        assert_eq!(legacy.decode::<F48>(0xC000_0001_0000), -1.0);
+
+        // The magnitude of a negative value rounds up toward negative infinity
+        let truncating = TargetProfile {
+            rounding: Rounding::TowardNegative,
+            ..legacy
+        };
+        assert_eq!(truncating.encode32(-0.7), Ok(0xD999_9A00));
+        assert_eq!(truncating.encode32(0.7), Ok(0x5999_9900));
+
+        assert_eq!(legacy.add32(0xC000_0001, 0x4000_00FF), Ok(0xE000_0000));
+        assert_eq!(
+            legacy.mul48(0xC000_0001_0000, 0xC000_0001_0000),
+            Ok(0x4000_0001_0000)
+        );
+        assert_eq!(legacy.sub32(0xFFFF_FF7F, 0x7FFF_FF7F), Ok(0xFFFF_FF7F));
+        assert!(matches!(
+            TargetProfile {
+                overflow: Overflow::Error,
+                ..legacy
+            }
+            .sub32(0xFFFF_FF7F, 0x7FFF_FF7F),
+            Err(Mil1750Error::OutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_each() {
+        let profiles = [TargetProfile::STANDARD, TRUNCATING];
+        let bytes = [
+            0x69, 0xA3, 0xB5, 0x07, 0x54, 0xAB, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+        ];
+        let words = decode_each::<F48>(&bytes, &profiles);
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].values[0], 105.63948563742451);
+        assert_eq!(
+            words[0].values[1],
+            extended::decode_with(0xAB54_07B5_A369, crate::DecodeMode::TwosComplement)
+        );
+        assert!(words[0].differs());
+        assert_eq!((words[1].offset, words[1].differs()), (6, false));
+        assert!(decode_each::<F48>(&bytes, &[])
+            .iter()
+            .all(|word| !word.differs()));
+    }
+}
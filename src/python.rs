@@ -0,0 +1,115 @@
+//! # Python bindings
+//! A `mil1750a` Python extension module exposing the conversions, built with
+//! [maturin](https://www.maturin.rs) (`maturin develop --features python`).
+//!
+//! Every scalar function has an `_array` counterpart taking and returning numpy arrays of any
+//! shape. Arrays must already have the matching dtype (`float16`, `float32`, `float64`,
+//! `uint16`, `uint32` or `uint64`).
+//!
+//! ```python
+//! import numpy as np
+//! import mil1750a
+//!
+//! assert mil1750a.f32_to_1750a(5.234) == 0x53BE7703
+//! words = mil1750a.f32_to_1750a_array(np.array([1.0, -1.0], dtype=np.float32))
+//! ```
+
+use pyo3::pymodule;
+
+#[pymodule]
+mod mil1750a {
+    use crate::{MilFormat, F16, F32, F48};
+    use half::f16;
+    use numpy::{IntoPyArray, PyArrayDyn, PyReadonlyArrayDyn};
+    use pyo3::{pyfunction, Bound, Python};
+
+    /// Transform a number to 16-bit MIL-1750A, rounding it to half precision first
+    #[pyfunction]
+    fn f16_to_1750a(value: f32) -> u16 {
+        F16::encode(f16::from_f32(value))
+    }
+
+    /// Transform a number to 32-bit MIL-1750A
+    #[pyfunction]
+    fn f32_to_1750a(value: f32) -> u32 {
+        F32::encode(value)
+    }
+
+    /// Transform a number to 48-bit MIL-1750A
+    #[pyfunction]
+    fn f48_to_1750a(value: f64) -> u64 {
+        F48::encode(value)
+    }
+
+    /// Convert a 16-bit MIL-1750A word to a number
+    #[pyfunction]
+    fn m1750a_to_16flt(word: u16) -> f32 {
+        F16::decode(word).to_f32()
+    }
+
+    /// Convert a 32-bit MIL-1750A word to a number
+    #[pyfunction]
+    fn m1750a_to_32flt(word: u32) -> f32 {
+        F32::decode(word)
+    }
+
+    /// Convert a 48-bit MIL-1750A word to a number
+    #[pyfunction]
+    fn m1750a_to_48flt(word: u64) -> f64 {
+        F48::decode(word)
+    }
+
+    /// Transform a `float16` array to 16-bit MIL-1750A words
+    #[pyfunction]
+    fn f16_to_1750a_array<'py>(
+        py: Python<'py>,
+        values: PyReadonlyArrayDyn<'py, f16>,
+    ) -> Bound<'py, PyArrayDyn<u16>> {
+        values.as_array().mapv(F16::encode).into_pyarray(py)
+    }
+
+    /// Transform a `float32` array to 32-bit MIL-1750A words
+    #[pyfunction]
+    fn f32_to_1750a_array<'py>(
+        py: Python<'py>,
+        values: PyReadonlyArrayDyn<'py, f32>,
+    ) -> Bound<'py, PyArrayDyn<u32>> {
+        values.as_array().mapv(F32::encode).into_pyarray(py)
+    }
+
+    /// Transform a `float64` array to 48-bit MIL-1750A words
+    #[pyfunction]
+    fn f48_to_1750a_array<'py>(
+        py: Python<'py>,
+        values: PyReadonlyArrayDyn<'py, f64>,
+    ) -> Bound<'py, PyArrayDyn<u64>> {
+        values.as_array().mapv(F48::encode).into_pyarray(py)
+    }
+
+    /// Convert a `uint16` array of 16-bit MIL-1750A words to `float16`
+    #[pyfunction]
+    fn m1750a_to_16flt_array<'py>(
+        py: Python<'py>,
+        words: PyReadonlyArrayDyn<'py, u16>,
+    ) -> Bound<'py, PyArrayDyn<f16>> {
+        words.as_array().mapv(F16::decode).into_pyarray(py)
+    }
+
+    /// Convert a `uint32` array of 32-bit MIL-1750A words to `float32`
+    #[pyfunction]
+    fn m1750a_to_32flt_array<'py>(
+        py: Python<'py>,
+        words: PyReadonlyArrayDyn<'py, u32>,
+    ) -> Bound<'py, PyArrayDyn<f32>> {
+        words.as_array().mapv(F32::decode).into_pyarray(py)
+    }
+
+    /// Convert a `uint64` array of 48-bit MIL-1750A words to `float64`
+    #[pyfunction]
+    fn m1750a_to_48flt_array<'py>(
+        py: Python<'py>,
+        words: PyReadonlyArrayDyn<'py, u64>,
+    ) -> Bound<'py, PyArrayDyn<f64>> {
+        words.as_array().mapv(F48::decode).into_pyarray(py)
+    }
+}
@@ -0,0 +1,160 @@
+//! [`uom`] quantities for [`Schema`] fields.
+//!
+//! [`Schema::quantity`] declares the unit a field is stored in, by its `uom` constructor such as
+//! `Length::new::<foot>`, and [`Frame::quantity`] reads the decoded field back as that quantity,
+//! so it can only be used as the dimension it was declared with. The unit is kept as the affine
+//! map from the stored value to the base unit of the dimension, the way `uom` stores quantities,
+//! so units with an offset such as degrees Celsius convert correctly.
+
+use crate::schema::{Frame, Schema, SchemaError};
+use std::any::TypeId;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use uom::si::{Dimension, Quantity, Units};
+
+/// The quantity type of a field and the map from its decoded value to base units
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Unit {
+    quantity: TypeId,
+    coefficient: f64,
+    constant: f64,
+}
+
+impl PartialEq for Unit {
+    fn eq(&self, other: &Self) -> bool {
+        self.quantity == other.quantity
+            && self.coefficient.to_bits() == other.coefficient.to_bits()
+            && self.constant.to_bits() == other.constant.to_bits()
+    }
+}
+
+impl Eq for Unit {}
+
+impl Hash for Unit {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.quantity.hash(state);
+        self.coefficient.to_bits().hash(state);
+        self.constant.to_bits().hash(state);
+    }
+}
+
+impl Schema {
+    /// Decode the field added last as a quantity in the unit `new` builds, such as
+    /// `Length::new::<foot>`, read with [`Frame::quantity`]
+    ///
+    /// The value converted is the decoded one, after any [`calibrated`](Schema::calibrated)
+    /// calibration.
+    ///
+    /// ```
+    /// use mil1750a_converter::schema::{FieldFormat, Schema};
+    /// use uom::si::f64::{Length, Velocity};
+    /// use uom::si::length::{foot, meter};
+    ///
+    /// let schema = Schema::new()
+    ///     .field("altitude", 0, FieldFormat::Int16)
+    ///     .quantity(Length::new::<foot>);
+    /// let frame = schema.decode(&[1000]).unwrap();
+    /// let altitude: Length = frame.quantity("altitude").unwrap();
+    /// assert!((altitude.get::<meter>() - 304.8).abs() < 1e-9);
+    /// let speed: Result<Velocity, _> = frame.quantity("altitude");
+    /// assert!(speed.is_err());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schema has no fields.
+    pub fn quantity<D, U>(mut self, new: fn(f64) -> Quantity<D, U, f64>) -> Self
+    where
+        D: Dimension + ?Sized + 'static,
+        U: Units<f64> + ?Sized + 'static,
+    {
+        let name = self
+            .fields()
+            .last()
+            .expect("no field to give a quantity")
+            .name
+            .clone();
+        let constant = new(0.0).value;
+        let unit = Unit {
+            quantity: TypeId::of::<Quantity<D, U, f64>>(),
+            coefficient: new(1.0).value - constant,
+            constant,
+        };
+        self.quantities.retain(|(field, _)| *field != name);
+        self.quantities.push((name, unit));
+        self
+    }
+}
+
+impl Frame {
+    /// The field called `name` as a quantity, if its schema declared it with
+    /// [`Schema::quantity`] as one of the same dimension
+    ///
+    /// Fails with [`SchemaError::MissingField`] if the frame has no such field, and with
+    /// [`SchemaError::Quantity`] if the field was declared as another quantity or none.
+    pub fn quantity<D, U>(&self, name: &str) -> Result<Quantity<D, U, f64>, SchemaError>
+    where
+        D: Dimension + ?Sized + 'static,
+        U: Units<f64> + ?Sized + 'static,
+    {
+        let value = self.require(name)?.to_f64();
+        let unit = self
+            .quantities
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, unit)| unit)
+            .filter(|unit| unit.quantity == TypeId::of::<Quantity<D, U, f64>>())
+            .ok_or_else(|| SchemaError::Quantity(name.to_owned()))?;
+        Ok(Quantity {
+            dimension: PhantomData,
+            units: PhantomData,
+            value: value * unit.coefficient + unit.constant,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::{Calibration, FieldFormat, Schema, SchemaError};
+    use uom::si::f64::{Length, ThermodynamicTemperature, Time};
+    use uom::si::length::{foot, meter};
+    use uom::si::thermodynamic_temperature::{degree_celsius, kelvin};
+    use uom::si::time::second;
+
+    #[test]
+    fn test_quantity() {
+        let schema = Schema::new()
+            .field("altitude", 0, FieldFormat::F32)
+            .quantity(Length::new::<foot>)
+            .field("temperature", 2, FieldFormat::Int16)
+            .calibrated(Calibration::new(0.5, 0.0, "degC"))
+            .quantity(ThermodynamicTemperature::new::<degree_celsius>)
+            .field("count", 3, FieldFormat::Int16);
+        let frame = schema.decode(&[0x4000, 0x0001, 50, 7]).unwrap();
+
+        let altitude: Length = frame.quantity("altitude").unwrap();
+        assert!((altitude.get::<meter>() - 0.3048).abs() < 1e-12);
+        let temperature: ThermodynamicTemperature = frame.quantity("temperature").unwrap();
+        assert!((temperature.get::<kelvin>() - 298.15).abs() < 1e-9);
+
+        let time: Result<Time, _> = frame.quantity("altitude");
+        assert_eq!(time, Err(SchemaError::Quantity("altitude".into())));
+        let count: Result<Length, _> = frame.quantity("count");
+        assert_eq!(count, Err(SchemaError::Quantity("count".into())));
+        let missing: Result<Length, _> = frame.quantity("missing");
+        assert_eq!(missing, Err(SchemaError::MissingField("missing".into())));
+    }
+
+    #[test]
+    fn test_redeclared() {
+        let schema = Schema::new()
+            .field("range", 0, FieldFormat::Int16)
+            .quantity(Time::new::<second>)
+            .quantity(Length::new::<meter>);
+        let frame = schema.decode(&[3]).unwrap();
+        let range: Length = frame.quantity("range").unwrap();
+        assert_eq!(range.get::<meter>(), 3.0);
+        let time: Result<Time, _> = frame.quantity("range");
+        assert!(time.is_err());
+    }
+}
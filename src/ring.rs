@@ -0,0 +1,200 @@
+//! # Ring buffer
+//! Hand decoded samples from an acquisition thread to a display thread.
+//!
+//! A [`RingDecoder`] holds a fixed number of timestamped samples behind a mutex. The
+//! acquisition thread pushes raw words, which are decoded with [`MilFormat::decode`] and stamped
+//! as they arrive; the consumer takes them with [`drain`](RingDecoder::drain), or looks at the
+//! newest with [`latest`](RingDecoder::latest) without taking them. When the consumer falls
+//! behind, the oldest samples are overwritten, since a display wants the newest, and
+//! [`overwritten`](RingDecoder::overwritten) counts how many were lost.
+//!
+//! ```
+//! use mil1750a_converter::ring::RingDecoder;
+//! use mil1750a_converter::F32;
+//!
+//! let ring = RingDecoder::<F32>::new(2);
+//! std::thread::scope(|scope| {
+//!     scope.spawn(|| ring.push_words(&[0x40000001, 0x40000002, 0x40000003]));
+//! });
+//! let samples = ring.drain();
+//! let values: Vec<_> = samples.iter().map(|sample| sample.value).collect();
+//! assert_eq!(values, [2.0, 4.0]);
+//! assert_eq!(ring.overwritten(), 1);
+//! assert!(ring.is_empty());
+//! ```
+
+use crate::MilFormat;
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+/// A decoded value and when its word was pushed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample<T> {
+    /// The decoded value
+    pub value: T,
+    /// When the word arrived
+    pub timestamp: Instant,
+}
+
+/// A fixed-capacity buffer of decoded samples shared between threads, see the
+/// [module documentation](self)
+#[derive(Debug)]
+pub struct RingDecoder<F: MilFormat> {
+    capacity: usize,
+    ring: Mutex<Ring<F::Ieee>>,
+}
+
+#[derive(Debug)]
+struct Ring<T> {
+    samples: VecDeque<Sample<T>>,
+    overwritten: u64,
+}
+
+impl<F: MilFormat> RingDecoder<F> {
+    /// A ring holding up to `capacity` samples
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "ring capacity must not be zero");
+        Self {
+            capacity,
+            ring: Mutex::new(Ring {
+                samples: VecDeque::with_capacity(capacity),
+                overwritten: 0,
+            }),
+        }
+    }
+
+    /// Decode a word and add it, stamped now, overwriting the oldest sample if the ring is full
+    pub fn push(&self, word: F::Bits) {
+        self.push_at(word, Instant::now());
+    }
+
+    /// Decode a word and add it with its own timestamp, for words stamped by the hardware
+    pub fn push_at(&self, word: F::Bits, timestamp: Instant) {
+        let sample = Sample {
+            value: F::decode(word),
+            timestamp,
+        };
+        self.lock().insert(sample, self.capacity);
+    }
+
+    /// Decode a block of words and add them under one lock, all stamped now
+    pub fn push_words(&self, words: &[F::Bits]) {
+        let timestamp = Instant::now();
+        let mut ring = self.lock();
+        for &word in words {
+            let sample = Sample {
+                value: F::decode(word),
+                timestamp,
+            };
+            ring.insert(sample, self.capacity);
+        }
+    }
+
+    /// Take every sample, oldest first
+    pub fn drain(&self) -> Vec<Sample<F::Ieee>> {
+        self.lock().samples.drain(..).collect()
+    }
+
+    /// Copies of the newest `count` samples, or all of them if there are fewer, oldest first,
+    /// leaving them in the ring
+    pub fn latest(&self, count: usize) -> Vec<Sample<F::Ieee>> {
+        let ring = self.lock();
+        let skip = ring.samples.len().saturating_sub(count);
+        ring.samples.iter().skip(skip).copied().collect()
+    }
+
+    /// Number of samples waiting
+    pub fn len(&self) -> usize {
+        self.lock().samples.len()
+    }
+
+    /// Whether no samples are waiting
+    pub fn is_empty(&self) -> bool {
+        self.lock().samples.is_empty()
+    }
+
+    /// Most samples the ring holds
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of samples overwritten before they were taken, since the ring was made
+    pub fn overwritten(&self) -> u64 {
+        self.lock().overwritten
+    }
+
+    /// The ring, even if a thread panicked while holding it, since every update leaves it
+    /// consistent
+    fn lock(&self) -> MutexGuard<'_, Ring<F::Ieee>> {
+        self.ring.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Ring<T> {
+    fn insert(&mut self, sample: Sample<T>, capacity: usize) {
+        if self.samples.len() == capacity {
+            self.samples.pop_front();
+            self.overwritten += 1;
+        }
+        self.samples.push_back(sample);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::F48;
+    use std::time::Duration;
+
+    #[test]
+    fn test_ring() {
+        let ring = RingDecoder::<F48>::new(3);
+        let start = Instant::now();
+        ring.push_at(0x400000010000, start);
+        ring.push(0x69A3B50754AB);
+        assert_eq!(ring.len(), 2);
+        let latest = ring.latest(1);
+        assert_eq!(latest[0].value, F48::decode(0x69A3B50754AB));
+        assert!(latest[0].timestamp >= start);
+        assert_eq!(ring.latest(5).len(), 2);
+
+        ring.push_words(&[0x400000020000, 0x400000030000]);
+        assert_eq!((ring.len(), ring.capacity(), ring.overwritten()), (3, 3, 1));
+        let values: Vec<_> = ring.drain().iter().map(|sample| sample.value).collect();
+        assert_eq!(values, [F48::decode(0x69A3B50754AB), 2.0, 4.0]);
+        assert!(ring.is_empty());
+        assert!(ring.latest(2).is_empty());
+    }
+
+    #[test]
+    fn test_threads() {
+        let ring = RingDecoder::<F48>::new(64);
+        let mut taken = Vec::new();
+        std::thread::scope(|scope| {
+            let producer = scope.spawn(|| {
+                for word in 0..1000u64 {
+                    ring.push(0x4000_0001_0000 | word);
+                }
+            });
+            while !producer.is_finished() || !ring.is_empty() {
+                taken.extend(ring.drain());
+                std::thread::sleep(Duration::from_micros(50));
+            }
+        });
+        assert_eq!(taken.len() as u64 + ring.overwritten(), 1000);
+        assert!(taken
+            .windows(2)
+            .all(|pair| pair[0].timestamp <= pair[1].timestamp));
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity must not be zero")]
+    fn test_zero_capacity() {
+        let _ = RingDecoder::<F48>::new(0);
+    }
+}
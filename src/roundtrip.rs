@@ -0,0 +1,120 @@
+//! # Bit-exact round trips
+//! Decode a word and encode its value again without losing a bit.
+//!
+//! A 32-bit word with an exponent below -126 holds a value that is subnormal as an `f32`, with
+//! fewer than 24 significant bits, so decoding it to `f32` and encoding that can change its
+//! mantissa. [`reencode_32`] and its 16- and 48-bit counterparts carry the value through an
+//! `f64`, which holds every MIL-1750A value exactly, and encode it as the standard does. Every
+//! normalized word, and zero as all-zero bits, comes back with exactly its original bits; another
+//! word comes back as the normalized word nearest its value, or zero if its value is nearer zero
+//! than any normalized word. [`try_reencode_32`] and the others reject those words instead, so a
+//! comparison by bits can rely on the result.
+//!
+//! ```
+//! use mil1750a_converter::roundtrip::{reencode_32, try_reencode_32};
+//! use mil1750a_converter::{DecodeError, MilFormat, F32};
+//!
+//! // Just under 2^-128, with more significant bits than an f32 keeps there
+//! let word = 0x7FFF_FF80;
+//! assert_ne!(F32::encode(F32::decode(word)), word);
+//! assert_eq!(reencode_32(word), word);
+//!
+//! // 0.25 * 2^1, not normalized
+//! assert_eq!(reencode_32(0x2000_0001), 0x4000_0000);
+//! assert_eq!(
+//!     try_reencode_32(0x2000_0001),
+//!     Err(DecodeError::Unnormalized { word: 0x2000_0001, bits: 32 })
+//! );
+//! ```
+
+use crate::{arith, DecodeError};
+
+/// Decode a 16-bit word and encode its value again
+///
+/// Normalized words and zero come back unchanged, see the [module documentation](self).
+pub fn reencode_16(word: u16) -> u16 {
+    arith::round16(arith::value16(word))
+}
+
+/// Decode a 32-bit word and encode its value again
+///
+/// Normalized words and zero come back unchanged, see the [module documentation](self).
+pub fn reencode_32(word: u32) -> u32 {
+    arith::round32(arith::value32(word))
+}
+
+/// Decode the low 48 bits of a word and encode its value again
+///
+/// Normalized words and zero come back unchanged, see the [module documentation](self).
+pub fn reencode_48(word: u64) -> u64 {
+    arith::round48(arith::value48(word))
+}
+
+/// Decode a 16-bit word and encode its value again, rejecting words that would not come back
+/// unchanged
+pub fn try_reencode_16(word: u16) -> Result<u16, DecodeError> {
+    DecodeError::check(arith::form16(word), word.into(), 16)?;
+    Ok(reencode_16(word))
+}
+
+/// Decode a 32-bit word and encode its value again, rejecting words that would not come back
+/// unchanged
+pub fn try_reencode_32(word: u32) -> Result<u32, DecodeError> {
+    DecodeError::check(arith::form32(word), word.into(), 32)?;
+    Ok(reencode_32(word))
+}
+
+/// Decode a 48-bit word and encode its value again, rejecting words that would not come back
+/// unchanged
+pub fn try_reencode_48(word: u64) -> Result<u64, DecodeError> {
+    DecodeError::check(arith::form48(word), word, 48)?;
+    Ok(reencode_48(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reencode_16() {
+        for word in 0..=u16::MAX {
+            match try_reencode_16(word) {
+                Ok(reencoded) => assert_eq!(reencoded, word, "{word:#06X}"),
+                Err(_) => {
+                    let reencoded = reencode_16(word);
+                    assert_eq!(try_reencode_16(reencoded), Ok(reencoded), "{word:#06X}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reencode_32() {
+        for word in (0..=u32::MAX)
+            .step_by(65521)
+            .chain([0x4000_0080, 0xBFFF_FF80])
+        {
+            if let Ok(reencoded) = try_reencode_32(word) {
+                assert_eq!(reencoded, word, "{word:#010X}");
+            }
+        }
+        assert_eq!(
+            try_reencode_32(0x0000_0001),
+            Err(DecodeError::NonZeroExponent { word: 1, bits: 32 })
+        );
+        assert_eq!(reencode_32(0x0000_0001), 0);
+    }
+
+    #[test]
+    fn test_reencode_48() {
+        for word in (0..1 << 48)
+            .step_by(0x1_0000_0FFF)
+            .chain([0x4000_0080_0000])
+        {
+            if let Ok(reencoded) = try_reencode_48(word) {
+                assert_eq!(reencoded, word, "{word:#014X}");
+            }
+        }
+        assert_eq!(reencode_48(0x2000_0001_0000), 0x4000_0000_0000);
+    }
+}
@@ -0,0 +1,1491 @@
+//! # Frame schemas
+//! Describe the layout of a telemetry frame once and decode whole frames of 16-bit words in one
+//! call.
+//!
+//! A [`Schema`] lists named fields by word offset and [`FieldFormat`]. Multi-word fields are
+//! stored most significant word first, as the 1750A stores them in memory, unless the schema's
+//! [`word_order`](Schema::word_order) says otherwise. Floating point
+//! fields are decoded as the hardware reads them, with two's-complement mantissas and exponents
+//! (see [`single::decode_lenient`]); a [`strict`](Schema::strict) schema rejects unnormalized
+//! words instead. [`Schema::encode`] packs named values back into a frame. A field with a
+//! [`Calibration`] is decoded to, and encoded from, engineering units.
+//!
+//! Floats can be mixed with the integers, flags, bitfields and enumerations of real ICD
+//! messages. Those narrower than a word name the bit they start at, counting from the most
+//! significant bit of the word as bit 0, as MIL-STD-1750A numbers bits, and fields may share a
+//! word as long as they do not share bits.
+//!
+//! ```
+//! use mil1750a_converter::schema::{FieldFormat, Schema, Value};
+//!
+//! let schema = Schema::new()
+//!     .field("armed", 0, FieldFormat::Bool { bit: 0 })
+//!     .field("mode", 0, FieldFormat::Enum { bit: 1, width: 3 })
+//!     .enumerated([(0, "SAFE"), (1, "IDLE"), (2, "ACTIVE")])
+//!     .field("trim", 0, FieldFormat::SignedBits { bit: 8, width: 8 })
+//!     .field("gain", 1, FieldFormat::F32);
+//! let frame = schema.decode(&[0xA0FD, 0x4000, 0x0001]).unwrap();
+//! assert_eq!(frame.get("armed"), Some(Value::Bool(true)));
+//! assert_eq!(frame.get("mode"), Some(Value::Enum(2)));
+//! assert_eq!(frame.label("mode"), Some("ACTIVE"));
+//! assert_eq!(frame.get("trim"), Some(Value::Int16(-3)));
+//! assert_eq!(schema.encode(&frame).unwrap(), [0xA0FD, 0x4000, 0x0001]);
+//! ```
+//!
+//! ```
+//! use mil1750a_converter::schema::{FieldFormat, Schema, Value};
+//!
+//! let schema = Schema::new()
+//!     .field("sync", 0, FieldFormat::Spare { words: 1 })
+//!     .field("altitude", 1, FieldFormat::F32)
+//!     .field("count", 3, FieldFormat::Int16);
+//! let frame = schema.decode(&[0xEB90, 0x53BE, 0x7703, 0xFFFE]).unwrap();
+//! assert_eq!(frame.get("altitude"), Some(Value::F32(5.234)));
+//! assert_eq!(frame.get("count"), Some(Value::Int16(-2)));
+//! ```
+//!
+//! A [`FieldFormat::Checksum`] word holds a word sum or CRC-16 of other words of the frame,
+//! with the [`Checksum`] algorithm it names. Decoding fails with [`SchemaError::Checksum`] if
+//! it does not match them, and encoding computes it.
+//!
+//! ```
+//! use mil1750a_converter::schema::{Checksum, FieldFormat, Schema, SchemaError};
+//!
+//! let schema = Schema::new().field("gain", 0, FieldFormat::F32).field(
+//!     "crc",
+//!     2,
+//!     FieldFormat::Checksum {
+//!         algorithm: Checksum::CRC16_CCITT_FALSE,
+//!         start: 0,
+//!         words: 2,
+//!     },
+//! );
+//! let frame = schema.decode(&[0x4000, 0x0001, 0xFA7D]).unwrap();
+//! assert_eq!(schema.encode(&frame).unwrap(), [0x4000, 0x0001, 0xFA7D]);
+//! assert!(matches!(
+//!     schema.decode(&[0x4000, 0x0002, 0xFA7D]),
+//!     Err(SchemaError::Checksum { .. })
+//! ));
+//! ```
+//!
+//! With the `serde` feature a schema can be loaded from, and saved to, a YAML or JSON file kept
+//! next to the ICD, with any serde format crate. Formats and word orders are named in
+//! `snake_case`, formats with parameters as a map of one entry, and a field's `calibration` and
+//! `labels` may be left out. Loading fails with a [`SchemaError`] where [`Schema::field`] and
+//! the other builders would panic, and on overlapping fields. Quantities declared with
+//! `Schema::quantity` are not kept in the file.
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use mil1750a_converter::schema::{Schema, Value};
+//!
+//! let schema: Schema = serde_json::from_str(
+//!     r#"{
+//!         "strict": true,
+//!         "fields": [
+//!             {"name": "mode", "offset": 0, "format": {"enum": {"bit": 0, "width": 2}},
+//!              "labels": [[0, "SAFE"], [1, "ACTIVE"]]},
+//!             {"name": "temperature", "offset": 1, "format": "int16",
+//!              "calibration": {"gain": 0.1, "offset": -40.0, "units": "degC"}}
+//!         ]
+//!     }"#,
+//! )
+//! .unwrap();
+//! let frame = schema.decode(&[0x4000, 650]).unwrap();
+//! assert_eq!(frame.label("mode"), Some("ACTIVE"));
+//! assert_eq!(frame.get("temperature"), Some(Value::Calibrated(25.0)));
+//! # }
+//! ```
+
+use crate::{arith, extended, fixed, single, DecodeError, Decoded, Mil1750Error};
+use crate::{TargetFormat, WordOrder};
+#[cfg(feature = "half")]
+use half::f16;
+use std::hash::{Hash, Hasher};
+
+/// How a field is stored in the frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum FieldFormat {
+    /// A 16-bit MIL-1750A float, one word
+    #[cfg(feature = "half")]
+    F16,
+    /// A 32-bit MIL-1750A float, two words
+    F32,
+    /// A 48-bit MIL-1750A float, three words
+    F48,
+    /// A two's-complement integer, one word
+    Int16,
+    /// An unsigned integer, one word
+    #[cfg_attr(feature = "serde", serde(rename = "uint16"))]
+    UInt16,
+    /// An unsigned integer of `width` bits of one word, starting at bit `bit` from the most
+    /// significant
+    Bits {
+        /// First bit of the field, 0 for the most significant bit of the word
+        bit: u32,
+        /// Number of bits
+        width: u32,
+    },
+    /// A two's-complement integer of `width` bits of one word, starting at bit `bit` from the
+    /// most significant
+    SignedBits {
+        /// First bit of the field, 0 for the most significant bit of the word
+        bit: u32,
+        /// Number of bits
+        width: u32,
+    },
+    /// A flag in bit `bit` of a discrete word, from the most significant
+    Bool {
+        /// Bit of the flag, 0 for the most significant bit of the word
+        bit: u32,
+    },
+    /// An enumeration of `width` bits of one word, starting at bit `bit` from the most
+    /// significant; see [`Schema::enumerated`] for the names of its values
+    Enum {
+        /// First bit of the field, 0 for the most significant bit of the word
+        bit: u32,
+        /// Number of bits
+        width: u32,
+    },
+    /// A 16-bit fixed-point value with binary scale `bscale`, see [`fixed`]
+    Fixed {
+        /// Binary scale of the field
+        bscale: i32,
+    },
+    /// A 32-bit fixed-point value with binary scale `bscale`, two words
+    Fixed32 {
+        /// Binary scale of the field
+        bscale: i32,
+    },
+    /// Words that carry no value
+    Spare {
+        /// Number of words
+        words: usize,
+    },
+    /// A one-word checksum of the `words` words starting at word `start`, decoded as a
+    /// [`Value::UInt16`]
+    Checksum {
+        /// How the checksum is computed
+        algorithm: Checksum,
+        /// Offset of the first word covered
+        start: usize,
+        /// Number of words covered
+        words: usize,
+    },
+}
+
+/// How a [`FieldFormat::Checksum`] is computed from the words it covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum Checksum {
+    /// The sum of the words, modulo 2^16
+    WordSum,
+    /// The two's complement of the sum of the words, so words and checksum sum to zero
+    NegatedSum,
+    /// The exclusive or of the words
+    Xor,
+    /// A CRC-16 of the bytes of the words, high byte first, shifting out the most significant
+    /// bit first, with no final exclusive or
+    Crc16 {
+        /// The generator polynomial, without its x^16 term
+        polynomial: u16,
+        /// The initial register
+        init: u16,
+    },
+}
+
+impl Checksum {
+    /// CRC-16/CCITT-FALSE, polynomial 0x1021 from 0xFFFF
+    pub const CRC16_CCITT_FALSE: Checksum = Checksum::Crc16 {
+        polynomial: 0x1021,
+        init: 0xFFFF,
+    };
+
+    /// CRC-16/XMODEM, polynomial 0x1021 from zero
+    pub const CRC16_XMODEM: Checksum = Checksum::Crc16 {
+        polynomial: 0x1021,
+        init: 0,
+    };
+
+    /// The checksum of `words`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mil1750a_converter::schema::Checksum;
+    /// assert_eq!(Checksum::WordSum.compute(&[0xFFFF, 0x0002]), 0x0001);
+    /// assert_eq!(Checksum::CRC16_CCITT_FALSE.compute(&[0x3132, 0x3334]), 0x5349);
+    /// ```
+    pub fn compute(self, words: &[u16]) -> u16 {
+        let sum = || words.iter().fold(0u16, |sum, &word| sum.wrapping_add(word));
+        match self {
+            Checksum::WordSum => sum(),
+            Checksum::NegatedSum => sum().wrapping_neg(),
+            Checksum::Xor => words.iter().fold(0, |xor, &word| xor ^ word),
+            Checksum::Crc16 { polynomial, init } => {
+                let bytes = words.iter().flat_map(|word| word.to_be_bytes());
+                bytes.fold(init, |mut crc, byte| {
+                    crc ^= u16::from(byte) << 8;
+                    for _ in 0..8 {
+                        crc = if crc & 0x8000 != 0 {
+                            crc << 1 ^ polynomial
+                        } else {
+                            crc << 1
+                        };
+                    }
+                    crc
+                })
+            }
+        }
+    }
+}
+
+impl FieldFormat {
+    /// Number of 16-bit words the field occupies
+    pub const fn words(self) -> usize {
+        match self {
+            #[cfg(feature = "half")]
+            FieldFormat::F16 => 1,
+            FieldFormat::Int16 | FieldFormat::UInt16 | FieldFormat::Fixed { .. } => 1,
+            FieldFormat::Checksum { .. } => 1,
+            FieldFormat::Bits { .. }
+            | FieldFormat::SignedBits { .. }
+            | FieldFormat::Bool { .. }
+            | FieldFormat::Enum { .. } => 1,
+            FieldFormat::F32 | FieldFormat::Fixed32 { .. } => 2,
+            FieldFormat::F48 => 3,
+            FieldFormat::Spare { words } => words,
+        }
+    }
+
+    /// The bits of its first word the field starts at, from the most significant, and its
+    /// width in bits
+    const fn bit_range(self) -> (u32, u32) {
+        match self {
+            FieldFormat::Bits { bit, width }
+            | FieldFormat::SignedBits { bit, width }
+            | FieldFormat::Enum { bit, width } => (bit, width),
+            FieldFormat::Bool { bit } => (bit, 1),
+            _ => (0, 16 * self.words() as u32),
+        }
+    }
+}
+
+/// A named field of a [`Schema`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Field {
+    /// Name the value is reported under
+    pub name: String,
+    /// Offset of the first word of the field in the frame
+    pub offset: usize,
+    /// How the field is stored
+    pub format: FieldFormat,
+    /// Conversion of the decoded value to engineering units, if any
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub calibration: Option<Calibration>,
+    /// Names of the values of an enumeration, see [`Schema::enumerated`]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub labels: Vec<(u16, String)>,
+}
+
+/// A linear conversion from a field's decoded value to engineering units,
+/// `value * gain + offset`
+///
+/// Calibrations compare and hash by the bits of their coefficients.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::schema::Calibration;
+/// let celsius = Calibration::new(0.1, -40.0, "degC");
+/// assert_eq!(celsius.apply(650.0), 25.0);
+/// assert_eq!(celsius.invert(25.0), 650.0);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Calibration {
+    /// Engineering units per unit of decoded value
+    pub gain: f64,
+    /// Engineering value of a decoded zero
+    pub offset: f64,
+    /// Name of the engineering units
+    pub units: String,
+}
+
+impl Calibration {
+    /// A calibration of `value * gain + offset`, in `units`
+    pub fn new(gain: f64, offset: f64, units: impl Into<String>) -> Self {
+        Calibration {
+            gain,
+            offset,
+            units: units.into(),
+        }
+    }
+
+    /// Convert a decoded value to engineering units
+    pub fn apply(&self, value: f64) -> f64 {
+        value * self.gain + self.offset
+    }
+
+    /// Convert a value in engineering units back to the value to encode
+    pub fn invert(&self, value: f64) -> f64 {
+        (value - self.offset) / self.gain
+    }
+}
+
+impl PartialEq for Calibration {
+    fn eq(&self, other: &Calibration) -> bool {
+        self.gain.to_bits() == other.gain.to_bits()
+            && self.offset.to_bits() == other.offset.to_bits()
+            && self.units == other.units
+    }
+}
+
+impl Eq for Calibration {}
+
+impl Hash for Calibration {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.gain.to_bits().hash(state);
+        self.offset.to_bits().hash(state);
+        self.units.hash(state);
+    }
+}
+
+/// A decoded field value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    /// From a [`FieldFormat::F16`] field
+    #[cfg(feature = "half")]
+    F16(f16),
+    /// From a [`FieldFormat::F32`] field
+    F32(f32),
+    /// From a [`FieldFormat::F48`] field
+    F48(f64),
+    /// From a [`FieldFormat::Int16`] or [`FieldFormat::SignedBits`] field
+    Int16(i16),
+    /// From a [`FieldFormat::UInt16`], [`FieldFormat::Bits`] or [`FieldFormat::Checksum`] field
+    UInt16(u16),
+    /// From a [`FieldFormat::Bool`] field
+    Bool(bool),
+    /// From a [`FieldFormat::Enum`] field, the raw value
+    Enum(u16),
+    /// From a [`FieldFormat::Fixed`] or [`FieldFormat::Fixed32`] field
+    Fixed(f64),
+    /// From a field with a [`Calibration`], in its units
+    Calibrated(f64),
+}
+
+impl Value {
+    /// The value as an `f64`, exactly; `true` is one
+    pub fn to_f64(self) -> f64 {
+        match self {
+            #[cfg(feature = "half")]
+            Value::F16(value) => value.into(),
+            Value::F32(value) => value.into(),
+            Value::F48(value) | Value::Fixed(value) | Value::Calibrated(value) => value,
+            Value::Int16(value) => value.into(),
+            Value::UInt16(value) | Value::Enum(value) => value.into(),
+            Value::Bool(value) => u8::from(value).into(),
+        }
+    }
+}
+
+/// The values of a decoded frame, in schema order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frame {
+    values: Vec<(String, Value)>,
+    units: Vec<(String, String)>,
+    labels: Vec<(String, Vec<(u16, String)>)>,
+    raw: Vec<(String, u64, bool)>,
+    #[cfg(feature = "uom")]
+    pub(crate) quantities: Vec<(String, crate::quantity::Unit)>,
+}
+
+impl Frame {
+    /// The value of the field called `name`
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.values
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|&(_, value)| value)
+    }
+
+    /// The engineering units of the field called `name`, if the schema that decoded the frame
+    /// gave it a [`Calibration`]
+    pub fn units(&self, name: &str) -> Option<&str> {
+        self.units
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, units)| units.as_str())
+    }
+
+    /// The name of the value of the enumeration called `name`, if the schema that decoded the
+    /// frame gave that value one with [`Schema::enumerated`]
+    pub fn label(&self, name: &str) -> Option<&str> {
+        let Value::Enum(value) = self.get(name)? else {
+            return None;
+        };
+        let (_, labels) = self.labels.iter().find(|(field, _)| field == name)?;
+        labels
+            .iter()
+            .find(|&&(raw, _)| raw == value)
+            .map(|(_, label)| label.as_str())
+    }
+
+    /// The words of the field called `name`, most significant first, if the schema that decoded
+    /// the frame was [`traced`](Schema::traced)
+    ///
+    /// A field narrower than a word gives the whole word it is in.
+    pub fn raw(&self, name: &str) -> Option<u64> {
+        self.raw
+            .iter()
+            .find(|(field, ..)| field == name)
+            .map(|&(_, raw, _)| raw)
+    }
+
+    /// The value of the field called `name` with its words, if the schema that decoded the frame
+    /// was [`traced`](Schema::traced)
+    ///
+    /// [`Decoded::valid`] is `false` for floating point words that are not normalized.
+    pub fn decoded(&self, name: &str) -> Option<Decoded<u64, Value>> {
+        let &(_, raw, valid) = self.raw.iter().find(|(field, ..)| field == name)?;
+        Some(Decoded {
+            raw,
+            value: self.get(name)?,
+            valid,
+        })
+    }
+
+    /// The value of the field called `name`, or [`SchemaError::MissingField`]
+    pub fn require(&self, name: &str) -> Result<Value, SchemaError> {
+        self.get(name)
+            .ok_or_else(|| SchemaError::MissingField(name.to_owned()))
+    }
+
+    /// The values by field name, in schema order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Value)> {
+        self.values
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// Number of values in the frame
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the frame has no values
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Set the value of the field called `name`, replacing any previous value and forgetting
+    /// the words it was decoded from
+    pub fn insert(&mut self, name: impl Into<String>, value: Value) {
+        let name = name.into();
+        self.raw.retain(|(field, ..)| *field != name);
+        match self.values.iter_mut().find(|(field, _)| *field == name) {
+            Some((_, old)) => *old = value,
+            None => self.values.push((name, value)),
+        }
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, Value)> for Frame {
+    fn from_iter<I: IntoIterator<Item = (S, Value)>>(iter: I) -> Self {
+        let mut frame = Frame::default();
+        for (name, value) in iter {
+            frame.insert(name, value);
+        }
+        frame
+    }
+}
+
+impl IntoIterator for Frame {
+    type Item = (String, Value);
+    type IntoIter = std::vec::IntoIter<(String, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+/// Types that can be built from a decoded [`Frame`], see [`Schema::decode_as`]
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::schema::{FieldFormat, Frame, FromFrame, Schema, SchemaError};
+///
+/// struct Attitude {
+///     roll: f64,
+///     pitch: f64,
+/// }
+///
+/// impl FromFrame for Attitude {
+///     fn from_frame(frame: &Frame) -> Result<Self, SchemaError> {
+///         Ok(Attitude {
+///             roll: frame.require("roll")?.to_f64(),
+///             pitch: frame.require("pitch")?.to_f64(),
+///         })
+///     }
+/// }
+///
+/// let schema = Schema::new()
+///     .field("roll", 0, FieldFormat::Fixed { bscale: 0 })
+///     .field("pitch", 1, FieldFormat::Fixed { bscale: 0 });
+/// let attitude: Attitude = schema.decode_as(&[0x4000, 0xC000]).unwrap();
+/// assert_eq!((attitude.roll, attitude.pitch), (0.5, -0.5));
+/// ```
+pub trait FromFrame: Sized {
+    /// Build the value from the fields of `frame`
+    fn from_frame(frame: &Frame) -> Result<Self, SchemaError>;
+}
+
+impl FromFrame for Frame {
+    fn from_frame(frame: &Frame) -> Result<Self, SchemaError> {
+        Ok(frame.clone())
+    }
+}
+
+/// Reasons a frame cannot be decoded or encoded with a schema
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum SchemaError {
+    /// The frame has fewer words than the schema covers
+    #[error("frame has {len} words but the schema needs {needed}")]
+    FrameTooShort {
+        /// Words the schema needs
+        needed: usize,
+        /// Words in the frame
+        len: usize,
+    },
+    /// A strict schema found a word that is not normalized
+    #[error("field {name:?}: {error}")]
+    Field {
+        /// Name of the offending field
+        name: String,
+        /// Why the field was rejected
+        #[source]
+        error: DecodeError,
+    },
+    /// A field a [`FromFrame`] implementation or the encoder needs is not in the frame
+    #[error("frame has no field {0:?}")]
+    MissingField(String),
+    /// A value to encode has no field in the schema
+    #[error("schema has no field {0:?}")]
+    UnknownField(String),
+    /// A value cannot be encoded in the format of its field
+    #[error("field {name:?}: {error}")]
+    Value {
+        /// Name of the offending field
+        name: String,
+        /// Why the value was rejected
+        #[source]
+        error: Mil1750Error,
+    },
+    /// A checksum field does not match the words it covers
+    #[error("field {name:?} is {found:#06X} but the words it covers give {expected:#06X}")]
+    Checksum {
+        /// Name of the checksum field
+        name: String,
+        /// The checksum of the words
+        expected: u16,
+        /// The word of the field
+        found: u16,
+    },
+    /// A field loaded from a file is not one the builders would accept
+    #[error("field {name:?}: {reason}")]
+    Invalid {
+        /// Name of the offending field
+        name: String,
+        /// What is wrong with it
+        reason: String,
+    },
+    /// Two fields of the schema share words, so a frame cannot hold both
+    #[error("fields {first:?} and {second:?} overlap")]
+    Overlap {
+        /// The field at the lower offset
+        first: String,
+        /// The field that starts inside it
+        second: String,
+    },
+    /// A field read as a `uom` quantity was not declared as one of that dimension and unit
+    /// system
+    #[cfg(feature = "uom")]
+    #[error("field {0:?} is not that quantity")]
+    Quantity(String),
+}
+
+/// The layout of a frame of 16-bit words
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "SchemaFile", try_from = "SchemaFile")
+)]
+pub struct Schema {
+    fields: Vec<Field>,
+    strict: bool,
+    traced: bool,
+    word_order: WordOrder,
+    #[cfg(feature = "uom")]
+    pub(crate) quantities: Vec<(String, crate::quantity::Unit)>,
+}
+
+impl Schema {
+    /// A schema with no fields
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a field starting at word `offset`
+    ///
+    /// # Panics
+    ///
+    /// Panics if a field narrower than a word has no bits or does not fit in its word, or a
+    /// checksum covers itself.
+    pub fn field(mut self, name: impl Into<String>, offset: usize, format: FieldFormat) -> Self {
+        if let Some(reason) = invalid(offset, format) {
+            panic!("{reason}");
+        }
+        self.fields.push(Field {
+            name: name.into(),
+            offset,
+            format,
+            calibration: None,
+            labels: Vec::new(),
+        });
+        self
+    }
+
+    /// Give the field added last a calibration, so it decodes to [`Value::Calibrated`] in
+    /// engineering units and encodes from them
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mil1750a_converter::schema::{Calibration, FieldFormat, Schema, Value};
+    ///
+    /// let schema = Schema::new()
+    ///     .field("temperature", 0, FieldFormat::Int16)
+    ///     .calibrated(Calibration::new(0.1, -40.0, "degC"));
+    /// let frame = schema.decode(&[650]).unwrap();
+    /// assert_eq!(frame.get("temperature"), Some(Value::Calibrated(25.0)));
+    /// assert_eq!(frame.units("temperature"), Some("degC"));
+    /// assert_eq!(schema.encode(&frame).unwrap(), [650]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schema has no fields.
+    pub fn calibrated(mut self, calibration: Calibration) -> Self {
+        let field = self.fields.last_mut().expect("no field to calibrate");
+        field.calibration = Some(calibration);
+        self
+    }
+
+    /// Name the values of the [`FieldFormat::Enum`] field added last, for [`Frame::label`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schema has no fields or the last is not an enumeration.
+    pub fn enumerated<S: Into<String>>(
+        mut self,
+        labels: impl IntoIterator<Item = (u16, S)>,
+    ) -> Self {
+        let field = self.fields.last_mut().expect("no field to enumerate");
+        assert!(
+            matches!(field.format, FieldFormat::Enum { .. }),
+            "field {:?} is not an enumeration",
+            field.name
+        );
+        field.labels = labels
+            .into_iter()
+            .map(|(value, label)| (value, label.into()))
+            .collect();
+        self
+    }
+
+    /// Reject unnormalized floating point fields, and zeros with a non-zero exponent, with
+    /// [`SchemaError::Field`] instead of decoding them
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Keep the words of every decoded field in the [`Frame`], for [`Frame::raw`] and
+    /// [`Frame::decoded`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mil1750a_converter::schema::{FieldFormat, Schema, Value};
+    /// use mil1750a_converter::Decoded;
+    ///
+    /// let schema = Schema::new().field("gain", 0, FieldFormat::F32).traced();
+    /// let frame = schema.decode(&[0x2000, 0x0002]).unwrap();
+    /// assert_eq!(
+    ///     frame.decoded("gain"),
+    ///     Some(Decoded { raw: 0x2000_0002, value: Value::F32(1.0), valid: false })
+    /// );
+    /// ```
+    pub fn traced(mut self) -> Self {
+        self.traced = true;
+        self
+    }
+
+    /// Store multi-word fields in `order` rather than most significant word first
+    pub fn word_order(mut self, order: WordOrder) -> Self {
+        self.word_order = order;
+        self
+    }
+
+    /// The fields, in the order they were added
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Whether the schema is [`strict`](Self::strict)
+    #[cfg(feature = "serde")]
+    pub(crate) fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// The order of the words of multi-word fields
+    #[cfg(feature = "serde")]
+    pub(crate) fn order(&self) -> WordOrder {
+        self.word_order
+    }
+
+    /// Number of words a frame needs to hold every field, and every word a checksum covers
+    pub fn len_words(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| match field.format {
+                FieldFormat::Checksum { start, words, .. } => (start + words).max(field.offset + 1),
+                format => field.offset + format.words(),
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Decode every field of `frame`; words beyond [`len_words`](Self::len_words) are ignored
+    pub fn decode(&self, frame: &[u16]) -> Result<Frame, SchemaError> {
+        let needed = self.len_words();
+        if frame.len() < needed {
+            return Err(SchemaError::FrameTooShort {
+                needed,
+                len: frame.len(),
+            });
+        }
+
+        let mut values = Vec::with_capacity(self.fields.len());
+        let mut raw = Vec::new();
+        for field in &self.fields {
+            if let FieldFormat::Checksum {
+                algorithm,
+                start,
+                words,
+            } = field.format
+            {
+                let expected = algorithm.compute(&frame[start..start + words]);
+                if frame[field.offset] != expected {
+                    return Err(SchemaError::Checksum {
+                        name: field.name.clone(),
+                        expected,
+                        found: frame[field.offset],
+                    });
+                }
+            }
+            let words = &frame[field.offset..field.offset + field.format.words()];
+            if let Some(decoded) = self.decode_traced_field(field, words)? {
+                values.push((field.name.clone(), decoded.value));
+                if self.traced {
+                    raw.push((field.name.clone(), decoded.raw, decoded.valid));
+                }
+            }
+        }
+        let units = self
+            .fields
+            .iter()
+            .filter_map(|field| Some((field.name.clone(), field.calibration.clone()?.units)))
+            .collect();
+        let labels = self
+            .fields
+            .iter()
+            .filter(|field| !field.labels.is_empty())
+            .map(|field| (field.name.clone(), field.labels.clone()))
+            .collect();
+        Ok(Frame {
+            values,
+            units,
+            labels,
+            raw,
+            #[cfg(feature = "uom")]
+            quantities: self.quantities.clone(),
+        })
+    }
+
+    /// Decode `frame` into a user type
+    pub fn decode_as<T: FromFrame>(&self, frame: &[u16]) -> Result<T, SchemaError> {
+        T::from_frame(&self.decode(frame)?)
+    }
+
+    /// Pack the values of `frame` into a frame of [`len_words`](Self::len_words) words
+    ///
+    /// Every field except the spares and checksums needs a value, of any [`Value`] variant, and
+    /// spare words are zero. Checksums are computed last, in schema order, and any values given for
+    /// them are ignored. Floating point fields are rounded once to the nearest normalized word, as
+    /// [`EncodeMode::TwosComplement`](crate::EncodeMode::TwosComplement) does, and values out of
+    /// range of their field are errors rather than saturating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mil1750a_converter::schema::{FieldFormat, Frame, Schema, Value};
+    ///
+    /// let schema = Schema::new()
+    ///     .field("altitude", 0, FieldFormat::F32)
+    ///     .field("count", 2, FieldFormat::Int16);
+    /// let frame: Frame = [("altitude", Value::F32(1.0)), ("count", Value::Int16(-2))]
+    ///     .into_iter()
+    ///     .collect();
+    /// assert_eq!(schema.encode(&frame).unwrap(), [0x4000, 0x0001, 0xFFFE]);
+    /// ```
+    pub fn encode(&self, frame: &Frame) -> Result<Vec<u16>, SchemaError> {
+        self.check_overlaps()?;
+        if let Some((name, _)) = frame
+            .iter()
+            .find(|(name, _)| !self.fields.iter().any(|field| field.name == *name))
+        {
+            return Err(SchemaError::UnknownField(name.to_owned()));
+        }
+
+        let mut words = vec![0; self.len_words()];
+        for field in &self.fields {
+            if let FieldFormat::Spare { .. } | FieldFormat::Checksum { .. } = field.format {
+                continue;
+            }
+            let value = frame.require(&field.name)?.to_f64();
+            let value = match &field.calibration {
+                Some(calibration) => calibration.invert(value),
+                None => value,
+            };
+            let bits = encode_field(field, value)?;
+            let len = field.format.words();
+            for (index, word) in words[field.offset..field.offset + len]
+                .iter_mut()
+                .enumerate()
+            {
+                let significance = match self.word_order {
+                    WordOrder::MsWordFirst => len - 1 - index,
+                    WordOrder::LsWordFirst => index,
+                };
+                *word |= (bits >> (16 * significance)) as u16;
+            }
+        }
+        for field in &self.fields {
+            if let FieldFormat::Checksum {
+                algorithm,
+                start,
+                words: covered,
+            } = field.format
+            {
+                words[field.offset] = algorithm.compute(&words[start..start + covered]);
+            }
+        }
+        Ok(words)
+    }
+
+    /// Fail with [`SchemaError::Overlap`] if two fields share a bit
+    fn check_overlaps(&self) -> Result<(), SchemaError> {
+        // Each field as the range of bits of the frame it covers
+        let bits = |field: &Field| {
+            let (bit, width) = field.format.bit_range();
+            let start = field.offset * 16 + bit as usize;
+            start..start + width as usize
+        };
+        let mut fields: Vec<_> = self
+            .fields
+            .iter()
+            .filter(|field| field.format.words() > 0)
+            .collect();
+        fields.sort_by_key(|field| bits(field).start);
+        for pair in fields.windows(2) {
+            if bits(pair[0]).end > bits(pair[1]).start {
+                return Err(SchemaError::Overlap {
+                    first: pair[0].name.clone(),
+                    second: pair[1].name.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn decode_field(
+        &self,
+        field: &Field,
+        words: &[u16],
+    ) -> Result<Option<Value>, SchemaError> {
+        Ok(self
+            .decode_traced_field(field, words)?
+            .map(|decoded| decoded.value))
+    }
+
+    /// Decode one field from its words, keeping them joined most significant first
+    fn decode_traced_field(
+        &self,
+        field: &Field,
+        words: &[u16],
+    ) -> Result<Option<Decoded<u64, Value>>, SchemaError> {
+        let join = |words: &[u16]| match self.word_order {
+            WordOrder::MsWordFirst => words
+                .iter()
+                .fold(0u64, |acc, &word| acc << 16 | u64::from(word)),
+            WordOrder::LsWordFirst => words
+                .iter()
+                .rev()
+                .fold(0u64, |acc, &word| acc << 16 | u64::from(word)),
+        };
+        let check = |result: Result<(), DecodeError>| {
+            result.map_err(|error| SchemaError::Field {
+                name: field.name.clone(),
+                error,
+            })
+        };
+
+        let raw = join(words);
+        let mut valid = true;
+        let value = match field.format {
+            #[cfg(feature = "half")]
+            FieldFormat::F16 => {
+                if self.strict {
+                    check(crate::half::decode_strict(words[0]).map(drop))?;
+                }
+                let decoded = crate::half::decode_lenient(words[0]);
+                valid = decoded.normalized;
+                Value::F16(decoded.value)
+            }
+            FieldFormat::F32 => {
+                let word = raw as u32;
+                if self.strict {
+                    check(single::decode_strict(word).map(drop))?;
+                }
+                let decoded = single::decode_lenient(word);
+                valid = decoded.normalized;
+                Value::F32(decoded.value)
+            }
+            FieldFormat::F48 => {
+                if self.strict {
+                    check(extended::decode_strict(raw).map(drop))?;
+                }
+                let decoded = extended::decode_lenient(raw);
+                valid = decoded.normalized;
+                Value::F48(decoded.value)
+            }
+            FieldFormat::Int16 => Value::Int16(words[0] as i16),
+            FieldFormat::UInt16 | FieldFormat::Checksum { .. } => Value::UInt16(words[0]),
+            FieldFormat::Bits { bit, width } => Value::UInt16(extract(words[0], bit, width)),
+            FieldFormat::SignedBits { bit, width } => {
+                let unused = 16 - width;
+                Value::Int16(((extract(words[0], bit, width) << unused) as i16) >> unused)
+            }
+            FieldFormat::Bool { bit } => Value::Bool(extract(words[0], bit, 1) == 1),
+            FieldFormat::Enum { bit, width } => Value::Enum(extract(words[0], bit, width)),
+            FieldFormat::Fixed { bscale } => Value::Fixed(fixed::decode16(words[0], bscale)),
+            FieldFormat::Fixed32 { bscale } => Value::Fixed(fixed::decode32(raw as u32, bscale)),
+            FieldFormat::Spare { .. } => return Ok(None),
+        };
+        let value = match &field.calibration {
+            Some(calibration) => Value::Calibrated(calibration.apply(value.to_f64())),
+            None => value,
+        };
+        Ok(Some(Decoded { raw, value, valid }))
+    }
+}
+
+/// The layout of a [`Schema`] as it is kept in a file
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaFile {
+    #[serde(default)]
+    strict: bool,
+    #[serde(default)]
+    traced: bool,
+    #[serde(default)]
+    word_order: WordOrder,
+    fields: Vec<Field>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Schema> for SchemaFile {
+    fn from(schema: Schema) -> Self {
+        SchemaFile {
+            strict: schema.strict,
+            traced: schema.traced,
+            word_order: schema.word_order,
+            fields: schema.fields,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SchemaFile> for Schema {
+    type Error = SchemaError;
+
+    fn try_from(file: SchemaFile) -> Result<Self, SchemaError> {
+        for field in &file.fields {
+            let reason = match invalid(field.offset, field.format) {
+                Some(reason) => reason,
+                None if !field.labels.is_empty()
+                    && !matches!(field.format, FieldFormat::Enum { .. }) =>
+                {
+                    "labels for a field that is not an enumeration".to_owned()
+                }
+                None => continue,
+            };
+            return Err(SchemaError::Invalid {
+                name: field.name.clone(),
+                reason,
+            });
+        }
+        let schema = Schema {
+            fields: file.fields,
+            strict: file.strict,
+            traced: file.traced,
+            word_order: file.word_order,
+            #[cfg(feature = "uom")]
+            quantities: Vec::new(),
+        };
+        schema.check_overlaps()?;
+        Ok(schema)
+    }
+}
+
+/// Why a field cannot have `format` at word `offset`, if it cannot
+pub(crate) fn invalid(offset: usize, format: FieldFormat) -> Option<String> {
+    let (bit, width) = format.bit_range();
+    if format.words() == 1 && (width == 0 || bit + width > 16) {
+        return Some(format!(
+            "bitfield of {width} bits at bit {bit} does not fit in a word"
+        ));
+    }
+    match format {
+        FieldFormat::Checksum { start, words, .. } if (start..start + words).contains(&offset) => {
+            Some(format!("checksum at word {offset} covers itself"))
+        }
+        _ => None,
+    }
+}
+
+/// The `width` bits of `word` starting at bit `bit` from the most significant
+fn extract(word: u16, bit: u32, width: u32) -> u16 {
+    ((u32::from(word) >> (16 - bit - width)) & ((1 << width) - 1)) as u16
+}
+
+/// Encode one value as the words of `field`, most significant first, in the low bits
+fn encode_field(field: &Field, value: f64) -> Result<u64, SchemaError> {
+    let rounded = |bits: Option<u64>, target| {
+        bits.ok_or(if value.is_finite() {
+            Mil1750Error::OutOfRange { value, target }
+        } else {
+            Mil1750Error::NotFinite { value, target }
+        })
+    };
+    let bits = match field.format {
+        #[cfg(feature = "half")]
+        FieldFormat::F16 => rounded(
+            arith::try_round16(value).map(u64::from),
+            TargetFormat::Float16,
+        ),
+        FieldFormat::F32 => rounded(
+            arith::try_round32(value).map(u64::from),
+            TargetFormat::Float32,
+        ),
+        FieldFormat::F48 => rounded(arith::try_round48(value), TargetFormat::Float48),
+        FieldFormat::Int16 => fixed::encode16(value, 15).map(u64::from),
+        FieldFormat::UInt16 => integer(value, 0, 16, false),
+        FieldFormat::Bits { bit, width } | FieldFormat::Enum { bit, width } => {
+            integer(value, bit, width, false)
+        }
+        FieldFormat::SignedBits { bit, width } => integer(value, bit, width, true),
+        FieldFormat::Bool { bit } => integer(value, bit, 1, false),
+        FieldFormat::Fixed { bscale } => fixed::encode16(value, bscale).map(u64::from),
+        FieldFormat::Fixed32 { bscale } => fixed::encode32(value, bscale).map(u64::from),
+        FieldFormat::Spare { .. } | FieldFormat::Checksum { .. } => Ok(0),
+    };
+    bits.map_err(|error| SchemaError::Value {
+        name: field.name.clone(),
+        error,
+    })
+}
+
+/// Round `value` to an integer of `width` bits and place it at bit `bit` of a word, from the
+/// most significant
+fn integer(value: f64, bit: u32, width: u32, signed: bool) -> Result<u64, Mil1750Error> {
+    let target = TargetFormat::Integer {
+        bits: width,
+        signed,
+    };
+    let (min, max) = if signed {
+        (-(1i64 << (width - 1)), (1i64 << (width - 1)) - 1)
+    } else {
+        (0, (1i64 << width) - 1)
+    };
+    let rounded = value.round();
+    if !value.is_finite() {
+        return Err(Mil1750Error::NotFinite { value, target });
+    }
+    if rounded < min as f64 || rounded > max as f64 {
+        return Err(Mil1750Error::OutOfRange { value, target });
+    }
+    let mask = (1u64 << width) - 1;
+    Ok((rounded as i64 as u64 & mask) << (16 - bit - width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The format of the `half` field, an integer where the `half` feature is off
+    #[cfg(feature = "half")]
+    const HALF: FieldFormat = FieldFormat::F16;
+    #[cfg(not(feature = "half"))]
+    const HALF: FieldFormat = FieldFormat::Int16;
+
+    fn schema() -> Schema {
+        Schema::new()
+            .field("half", 0, HALF)
+            .field("single", 1, FieldFormat::F32)
+            .field("extended", 3, FieldFormat::F48)
+            .field("spare", 6, FieldFormat::Spare { words: 2 })
+            .field("fixed", 8, FieldFormat::Fixed32 { bscale: 15 })
+    }
+
+    const FRAME: [u16; 10] = [
+        0x6344, 0x4000, 0x0001, 0x69A3, 0xB507, 0x54AB, 0xDEAD, 0xBEEF, 0xFFFF, 0x8000,
+    ];
+
+    #[test]
+    fn test_decode() {
+        let frame = schema().decode(&FRAME).unwrap();
+        assert_eq!(schema().len_words(), 10);
+        assert_eq!(frame.len(), 4);
+        #[cfg(feature = "half")]
+        assert_eq!(frame.get("half"), Some(Value::F16(f16::from_f32(12.40625))));
+        assert_eq!(frame.get("single"), Some(Value::F32(1.0)));
+        assert_eq!(frame.get("extended"), Some(Value::F48(105.63948563742451)));
+        assert_eq!(frame.get("fixed"), Some(Value::Fixed(-0.5)));
+        assert_eq!(frame.get("spare"), None);
+        let names: Vec<_> = frame.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["half", "single", "extended", "fixed"]);
+    }
+
+    #[test]
+    fn test_traced() {
+        assert_eq!(schema().decode(&FRAME).unwrap().raw("single"), None);
+        let mut frame = FRAME;
+        frame[1] = 0x2000;
+        let mut decoded = schema().traced().decode(&frame).unwrap();
+        assert_eq!(decoded.raw("extended"), Some(0x69A3B50754AB));
+        assert_eq!(decoded.raw("spare"), None);
+        assert_eq!(
+            decoded.decoded("single"),
+            Some(Decoded {
+                raw: 0x20000001,
+                value: Value::F32(0.5),
+                valid: false
+            })
+        );
+        assert_eq!(
+            decoded.decoded("fixed").map(|field| field.valid),
+            Some(true)
+        );
+        decoded.insert("single", Value::F32(2.0));
+        assert_eq!(decoded.decoded("single"), None);
+
+        let swapped = Schema::new()
+            .field("gain", 0, FieldFormat::F32)
+            .word_order(WordOrder::LsWordFirst)
+            .traced();
+        let frame = swapped.decode(&[0x0001, 0x4000]).unwrap();
+        assert_eq!(frame.raw("gain"), Some(0x40000001));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            schema().decode(&FRAME[..9]),
+            Err(SchemaError::FrameTooShort { needed: 10, len: 9 })
+        );
+
+        let mut frame = FRAME;
+        frame[1] = 0x2000;
+        assert!(schema().decode(&frame).is_ok());
+        assert_eq!(
+            schema().strict().decode(&frame),
+            Err(SchemaError::Field {
+                name: "single".into(),
+                error: DecodeError::Unnormalized {
+                    word: 0x20000001,
+                    bits: 32
+                }
+            })
+        );
+        assert_eq!(
+            schema().decode_as::<Frame>(&FRAME).unwrap().require("nope"),
+            Err(SchemaError::MissingField("nope".into()))
+        );
+    }
+    #[test]
+    fn test_encode_round_trip() {
+        let frame = schema().decode(&FRAME).unwrap();
+        let mut expected = FRAME;
+        expected[6..8].copy_from_slice(&[0, 0]);
+        assert_eq!(schema().encode(&frame).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_calibrated() {
+        let schema = Schema::new()
+            .field("altitude", 0, FieldFormat::F32)
+            .calibrated(Calibration::new(0.3048, 0.0, "m"))
+            .field("count", 2, FieldFormat::Int16);
+        let frame = schema.decode(&[0x5000, 0x0004, 3]).unwrap();
+        assert_eq!(
+            frame.get("altitude"),
+            Some(Value::Calibrated(10.0 * 0.3048))
+        );
+        assert_eq!(frame.get("count"), Some(Value::Int16(3)));
+        assert_eq!(
+            (frame.units("altitude"), frame.units("count")),
+            (Some("m"), None)
+        );
+        assert_eq!(schema.encode(&frame).unwrap(), [0x5000, 0x0004, 3]);
+        assert_eq!(
+            schema.fields()[0].calibration,
+            Some(Calibration::new(0.3048, 0.0, "m"))
+        );
+    }
+
+    #[test]
+    fn test_bitfields() {
+        let schema = Schema::new()
+            .field("status", 0, FieldFormat::Bits { bit: 0, width: 4 })
+            .field("valid", 0, FieldFormat::Bool { bit: 15 })
+            .field("count", 1, FieldFormat::UInt16)
+            .field("mode", 2, FieldFormat::Enum { bit: 12, width: 4 })
+            .enumerated([(1, "ON")]);
+        let frame = schema.decode(&[0x9001, 0xFFFE, 0x0007]).unwrap();
+        assert_eq!(frame.get("status"), Some(Value::UInt16(9)));
+        assert_eq!(frame.get("valid"), Some(Value::Bool(true)));
+        assert_eq!(frame.get("count"), Some(Value::UInt16(0xFFFE)));
+        assert_eq!(frame.get("mode"), Some(Value::Enum(7)));
+        assert_eq!((frame.label("mode"), frame.label("count")), (None, None));
+        assert_eq!(schema.encode(&frame).unwrap(), [0x9001, 0xFFFE, 0x0007]);
+
+        let mut frame = frame;
+        frame.insert("status", Value::UInt16(16));
+        assert_eq!(
+            schema.encode(&frame),
+            Err(SchemaError::Value {
+                name: "status".into(),
+                error: Mil1750Error::OutOfRange {
+                    value: 16.0,
+                    target: TargetFormat::Integer {
+                        bits: 4,
+                        signed: false
+                    }
+                }
+            })
+        );
+
+        let overlapping = schema.field("late", 0, FieldFormat::SignedBits { bit: 3, width: 2 });
+        assert_eq!(
+            overlapping.encode(&Frame::default()),
+            Err(SchemaError::Overlap {
+                first: "status".into(),
+                second: "late".into()
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in a word")]
+    fn test_bitfield_too_wide() {
+        let _ = Schema::new().field("wide", 0, FieldFormat::Bits { bit: 8, width: 9 });
+    }
+
+    #[test]
+    fn test_checksums() {
+        let words = [0x4000, 0x0001, 0x53BE, 0x7703];
+        let checksums = [
+            Checksum::WordSum,
+            Checksum::NegatedSum,
+            Checksum::Xor,
+            Checksum::CRC16_CCITT_FALSE,
+            Checksum::CRC16_XMODEM,
+        ];
+        assert_eq!(
+            checksums.map(|checksum| checksum.compute(&words)),
+            [0x0AC2, 0xF53E, 0x64BC, 0xC70B, 0xF635]
+        );
+
+        // A word sum ahead of the words it covers
+        let schema = Schema::new()
+            .field(
+                "sum",
+                0,
+                FieldFormat::Checksum {
+                    algorithm: Checksum::NegatedSum,
+                    start: 1,
+                    words: 4,
+                },
+            )
+            .field("gain", 1, FieldFormat::F32)
+            .field("altitude", 3, FieldFormat::F32);
+        let mut frame = [0xF53E, 0x4000, 0x0001, 0x53BE, 0x7703];
+        let decoded = schema.decode(&frame).unwrap();
+        assert_eq!(decoded.get("sum"), Some(Value::UInt16(0xF53E)));
+        let mut values = decoded.clone();
+        values.insert("sum", Value::UInt16(0));
+        assert_eq!(schema.encode(&values).unwrap(), frame);
+
+        frame[4] = 0x7704;
+        assert_eq!(
+            schema.decode(&frame),
+            Err(SchemaError::Checksum {
+                name: "sum".into(),
+                expected: 0xF53D,
+                found: 0xF53E
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "covers itself")]
+    fn test_checksum_covers_itself() {
+        let algorithm = Checksum::Xor;
+        let format = FieldFormat::Checksum {
+            algorithm,
+            start: 0,
+            words: 2,
+        };
+        let _ = Schema::new().field("xor", 1, format);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let schema = schema()
+            .field("mode", 10, FieldFormat::Enum { bit: 0, width: 2 })
+            .enumerated([(0, "SAFE"), (3, "ARMED")])
+            .field("temperature", 11, FieldFormat::Int16)
+            .calibrated(Calibration::new(0.1, -40.0, "degC"))
+            .field(
+                "crc",
+                12,
+                FieldFormat::Checksum {
+                    algorithm: Checksum::CRC16_XMODEM,
+                    start: 0,
+                    words: 12,
+                },
+            )
+            .word_order(WordOrder::LsWordFirst)
+            .strict();
+        let json = serde_json::to_value(&schema).unwrap();
+        assert_eq!(json["word_order"], "ls_word_first");
+        assert_eq!(json["fields"][1]["format"], "f32");
+        assert_eq!(json["fields"][4]["format"]["fixed32"]["bscale"], 15);
+        assert_eq!(serde_json::from_value::<Schema>(json).unwrap(), schema);
+
+        let load = |json: &str| {
+            serde_json::from_str::<Schema>(json)
+                .unwrap_err()
+                .to_string()
+        };
+        let field = |name: &str, offset: usize, format: &str| {
+            format!(r#"{{"name": "{name}", "offset": {offset}, "format": {format}}}"#)
+        };
+        let wide = field("wide", 0, r#"{"bits": {"bit": 8, "width": 9}}"#);
+        assert!(load(&format!(r#"{{"fields": [{wide}]}}"#)).contains("does not fit in a word"));
+        let (a, b) = (field("a", 0, r#""f32""#), field("b", 1, r#""uint16""#));
+        assert!(load(&format!(r#"{{"fields": [{a}, {b}]}}"#)).contains("overlap"));
+        let labelled = r#"{"name": "c", "offset": 0, "format": "int16", "labels": [[0, "X"]]}"#;
+        assert!(load(&format!(r#"{{"fields": [{labelled}]}}"#)).contains("not an enumeration"));
+        assert!(load(r#"{"fields": [], "gain": 2}"#).contains("unknown field"));
+    }
+
+    #[test]
+    fn test_word_order() {
+        let swapped_schema = schema().word_order(WordOrder::LsWordFirst);
+        let swapped = [
+            0x6344, 0x0001, 0x4000, 0x54AB, 0xB507, 0x69A3, 0, 0, 0x8000, 0xFFFF,
+        ];
+        let frame = swapped_schema.decode(&swapped).unwrap();
+        assert_eq!(frame, schema().decode(&FRAME).unwrap());
+        assert_eq!(swapped_schema.encode(&frame).unwrap(), swapped);
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        let mut frame = schema().decode(&FRAME).unwrap();
+        frame.insert("fixed", Value::Fixed(1e6));
+        assert_eq!(
+            schema().encode(&frame),
+            Err(SchemaError::Value {
+                name: "fixed".into(),
+                error: Mil1750Error::OutOfRange {
+                    value: 1e6,
+                    target: TargetFormat::Fixed32
+                }
+            })
+        );
+        frame.insert("typo", Value::Int16(0));
+        assert_eq!(
+            schema().encode(&frame),
+            Err(SchemaError::UnknownField("typo".into()))
+        );
+        assert_eq!(
+            schema().encode(&Frame::default()),
+            Err(SchemaError::MissingField("half".into()))
+        );
+
+        let overlapping = schema().field("late", 4, FieldFormat::Int16);
+        assert_eq!(
+            overlapping.encode(&Frame::default()),
+            Err(SchemaError::Overlap {
+                first: "extended".into(),
+                second: "late".into()
+            })
+        );
+    }
+}
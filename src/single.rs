@@ -0,0 +1,323 @@
+//! # Single precision
+//! The 32-bit MIL-1750A floating point format: a 24-bit two's-complement mantissa followed by an
+//! 8-bit two's-complement exponent.
+
+use crate::{arith, DecodeError, DecodeMode, Decoded, EncodeMode, Lenient};
+
+/// Transform 32-bit floating point number to MIL-1750A Hex
+///
+/// # Arguments
+///
+/// * `input`: number as 32-bit floating point
+///
+/// returns: u32 interpret as hex
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::single;
+/// assert_eq!(single::encode(5.234), 0x53BE7703);
+/// ```
+pub fn encode(input: f32) -> u32 {
+    if input == 0.0 {
+        return 0;
+    }
+
+    let mut exponent = input.abs().log2().ceil() as i32;
+    let mut mantissa = (input * 2f32.powi(23 - exponent)).round() as i32;
+
+    // Boundary check
+    if mantissa == 8388608 {
+        mantissa /= 2;
+        exponent += 1;
+    }
+
+    let mut result = (mantissa as u32) << 8;
+    result |= (exponent as u32) & 0xFF;
+
+    if input.is_sign_negative() {
+        result |= 0x80000000;
+    }
+
+    result
+}
+
+/// Transform a number to 32-bit MIL-1750A as [`encode`] does, but computing in `f64`
+///
+/// This is the arithmetic of the Perl library. An `f32` logarithm can put a value just above a
+/// power of two at the exponent below, which overflows the mantissa of [`encode`] into the sign.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::single;
+/// let value = 256.0 * (1.0 + f32::EPSILON);
+/// assert_eq!(single::encode(value), 0x80000108);
+/// assert_eq!(single::encode_f64_math(value.into()), 0x40000109);
+/// ```
+pub fn encode_f64_math(input: f64) -> u32 {
+    if input == 0.0 {
+        return 0;
+    }
+
+    let mut exponent = input.abs().log2().ceil() as i32;
+    let mut mantissa = (input * 2f64.powi(23 - exponent)).round() as i32;
+
+    // Boundary check
+    if mantissa == 8388608 {
+        mantissa /= 2;
+        exponent += 1;
+    }
+
+    let mut result = (mantissa as u32) << 8;
+    result |= (exponent as u32) & 0xFF;
+
+    if input.is_sign_negative() {
+        result |= 0x80000000;
+    }
+
+    result
+}
+
+/// Transform a f32 to 32-bit MIL-1750A in the given [`EncodeMode`]
+///
+/// [`EncodeMode::Legacy`] gives the same word as [`encode`]. [`EncodeMode::TwosComplement`]
+/// rounds to the nearest normalized word and saturates values too large for the format.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{single, EncodeMode};
+/// assert_eq!(single::encode_with(-1.0, EncodeMode::TwosComplement), 0x80000000);
+/// assert_eq!(single::encode_with(2.0, EncodeMode::TwosComplement), 0x40000002);
+/// ```
+pub fn encode_with(input: f32, mode: EncodeMode) -> u32 {
+    match mode {
+        EncodeMode::TwosComplement => arith::round32(input.into()),
+        EncodeMode::Legacy => encode(input),
+    }
+}
+
+/// Transform a f32 to 32-bit MIL-1750A, rounding up or down at random, as
+/// [`extended::encode_stochastic`](crate::extended::encode_stochastic) does
+pub fn encode_stochastic(input: f32, random: &mut impl FnMut() -> u64) -> u32 {
+    arith::round32_stochastic(input.into(), random())
+}
+
+/// Convert MIL-1750A hex (interpreted as u32) to f32
+///
+/// # Arguments
+///
+/// * `input`:  MIL-1750A hex (interpreted as u32)
+///
+/// returns: f32 representation of the input
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::single;
+/// assert_eq!(single::decode(0x997AE105), -25.6300010681152);
+/// ```
+pub fn decode(input: u32) -> f32 {
+    // Sign-extend the mantissa with an arithmetic shift
+    let mantissa = (input as i32) >> 8;
+    let exponent = input & 0xFF;
+
+    // 2^(exponent - 23) built directly from its bits; exponents past the f32 range give the
+    // all-ones biased exponent, infinity
+    let scale = f32::from_bits((exponent + 127 - 23).min(0xFF) << 23);
+    mantissa as f32 * scale
+}
+
+/// Convert MIL-1750A hex (interpreted as u32) to f64 as [`decode`] does, but computing in
+/// `f64`
+///
+/// Every scale fits an `f64`, so a zero mantissa gives zero where [`decode`] multiplies it by
+/// infinity.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::single;
+/// assert!(single::decode(0x000000FF).is_nan());
+/// assert_eq!(single::decode_f64_math(0x000000FF), 0.0);
+/// assert_eq!(single::decode_f64_math(0x997AE105), -25.630001068115234);
+/// ```
+pub fn decode_f64_math(input: u32) -> f64 {
+    // Sign-extend the mantissa with an arithmetic shift
+    let mantissa = (input as i32) >> 8;
+    let exponent = (input & 0xFF) as i32;
+
+    f64::from(mantissa) * 2f64.powi(exponent - 23)
+}
+
+/// Convert a 32-bit MIL-1750A word to f32 in the given [`DecodeMode`]
+///
+/// [`DecodeMode::Legacy`] gives the same value as [`decode`]. [`DecodeMode::TwosComplement`]
+/// reads the exponent as two's complement, like [`decode_lenient`].
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{single, DecodeMode};
+/// assert_eq!(single::decode_with(0x400000FF, DecodeMode::TwosComplement), 0.25);
+/// assert!(single::decode_with(0x400000FF, DecodeMode::Legacy) > 1e38);
+/// ```
+pub fn decode_with(input: u32, mode: DecodeMode) -> f32 {
+    match mode {
+        DecodeMode::TwosComplement => arith::value32(input) as f32,
+        DecodeMode::Legacy => decode(input),
+    }
+}
+
+/// Convert a 32-bit MIL-1750A word to f32, rejecting words that are not normalized
+///
+/// Unlike [`decode`], the exponent is read as two's complement, as the standard defines it, and
+/// zero must be all-zero bits.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{single, DecodeError};
+/// assert_eq!(single::decode_strict(0x40000001), Ok(1.0));
+/// assert_eq!(single::decode_strict(0x400000FF), Ok(0.25));
+/// assert_eq!(
+///     single::decode_strict(0x20000002),
+///     Err(DecodeError::Unnormalized { word: 0x20000002, bits: 32 })
+/// );
+/// ```
+pub fn decode_strict(input: u32) -> Result<f32, DecodeError> {
+    DecodeError::check(arith::form32(input), input.into(), 32)?;
+    Ok(arith::value32(input) as f32)
+}
+
+/// Convert a 32-bit MIL-1750A word to f32 as the hardware does, accepting unnormalized words
+///
+/// The value is computed exactly like [`decode_strict`], and [`Lenient::normalized`] records
+/// whether the word would have passed it.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::single;
+/// let decoded = single::decode_lenient(0x20000002);
+/// assert_eq!(decoded.value, 1.0);
+/// assert!(!decoded.normalized);
+/// ```
+pub fn decode_lenient(input: u32) -> Lenient<f32> {
+    Lenient::new(arith::value32(input) as f32, arith::form32(input))
+}
+
+/// Convert a 32-bit MIL-1750A word to f32 as [`decode_lenient`] does, keeping the word with the
+/// value
+pub fn decode_traced(input: u32) -> Decoded<u32, f32> {
+    decode_lenient(input).with_raw(input)
+}
+
+#[cfg(test)]
+#[allow(clippy::excessive_precision, clippy::unusual_byte_groupings)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(1.0), 0x40000001);
+        assert_eq!(encode(-1.0), 0x80000000);
+        assert_eq!(encode(5.234), 0x53BE7703);
+        assert_eq!(encode(-25.63f32), 0x997AE105);
+        assert_eq!(encode(25.63f32), 0x66851F05);
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(0x40000001), 1.0);
+        assert_eq!(decode(0x997AE105), -25.6300010681152);
+        assert_eq!(decode(0x9F34EA0C), -3097.3857421875);
+        assert_eq!(decode(0x400000FF), f32::INFINITY);
+        assert!(decode(0x000000FF).is_nan());
+    }
+    #[test]
+    fn test_f64_math() {
+        // An f32 logarithm rounds to exactly 8, so the mantissa of `encode` overflows
+        let value = 256.0 * (1.0 + f32::EPSILON);
+        assert_eq!(encode(value), 0x80000108);
+        assert_eq!(encode_f64_math(value.into()), 0x40000109);
+        for value in [1.0, -1.0, 5.234, -25.63, 25.63] {
+            assert_eq!(encode_f64_math(f64::from(value)), encode(value), "{value}");
+        }
+        for word in [0x40000001, 0x997AE105, 0x9F34EA0C, 0x400000FF] {
+            assert_eq!(decode_f64_math(word) as f32, decode(word), "{word:#X}");
+        }
+        assert_eq!(decode_f64_math(0x000000FF), 0.0);
+        assert_eq!(decode_f64_math(0x0000017F), 2f64.powi(104));
+    }
+    #[test]
+    fn test_decode_matches_reference() {
+        let reference = |input: u32| {
+            let mantissa = (input >> 8) & 0xFFFFFF;
+            let exponent = input & 0xFF;
+            let signed_mantissa = if mantissa & 0x800000 != 0 {
+                -(((!mantissa & 0xFFFFFF) + 1) as i32)
+            } else {
+                mantissa as i32
+            };
+            (signed_mantissa as f32) * 2f32.powi((exponent as i32) - 23)
+        };
+        for word in (0..=u32::MAX).step_by(65521) {
+            let (decoded, expected) = (decode(word), reference(word));
+            assert!(
+                decoded.to_bits() == expected.to_bits() || decoded.is_nan() && expected.is_nan(),
+                "{word:#X}"
+            );
+        }
+    }
+    #[test]
+    fn test_decode_strict() {
+        assert_eq!(decode_strict(0x997AE105), Ok(-25.6300010681152));
+        assert_eq!(decode_strict(0x40000080), Ok(2f64.powi(-129) as f32));
+        assert_eq!(decode_strict(0), Ok(0.0));
+        assert_eq!(
+            decode_strict(0xC0000000),
+            Err(DecodeError::Unnormalized {
+                word: 0xC0000000,
+                bits: 32
+            })
+        );
+        assert_eq!(
+            decode_strict(0x0000007F),
+            Err(DecodeError::NonZeroExponent {
+                word: 0x0000007F,
+                bits: 32
+            })
+        );
+    }
+    #[test]
+    fn test_decode_lenient() {
+        let decoded = decode_lenient(0x40000001);
+        assert_eq!(
+            decoded,
+            Lenient {
+                value: 1.0,
+                normalized: true
+            }
+        );
+        assert_eq!(decode_lenient(0xC0000001).value, -1.0);
+        assert_eq!(decode_lenient(0x000001FF).value, 2f64.powi(-24) as f32);
+        assert!(!decode_lenient(0x0000007F).normalized);
+        assert!(decode_lenient(0).normalized);
+    }
+    #[test]
+    fn test_encode_with() {
+        for value in [1.0, -1.0, -0.5, -0.75, 5.234, -25.63, -3097.3857] {
+            assert_eq!(
+                encode_with(value, EncodeMode::TwosComplement),
+                encode_with(value, EncodeMode::Legacy),
+                "{value}"
+            );
+        }
+        assert_eq!(
+            encode_with(f32::MAX, EncodeMode::TwosComplement),
+            0x7FFFFF7F
+        );
+    }
+}
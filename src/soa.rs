@@ -0,0 +1,146 @@
+//! # Structure-of-arrays conversions
+//! Decode words into separate arrays, or from separate arrays of their halves, for pipelines
+//! that keep each quantity in its own buffer.
+//!
+//! The `decode_columns` functions split each word into caller-provided mantissa, exponent and
+//! value arrays in one pass, and the `decode_halves` functions read words whose 16-bit halves
+//! arrive in separate arrays, most significant first, as a DMA engine that de-interleaves a
+//! 1553 stream leaves them. Nothing is allocated and no interleaved copy of the words is made.
+//!
+//! Words are read as the hardware reads them, with two's-complement mantissas and exponents
+//! (see [`single::decode_lenient`](crate::single::decode_lenient)), so unnormalized words keep
+//! their value. Mantissas are the signed integer the word holds, worth
+//! `mantissa * 2^(exponent - 23)` for 32-bit words and `mantissa * 2^(exponent - 39)` for
+//! 48-bit ones.
+//!
+//! ```
+//! use mil1750a_converter::soa;
+//!
+//! let words = [0x40000001, 0x80000000, 0x53BE7703];
+//! let (mut mantissas, mut exponents, mut values) = ([0; 3], [0; 3], [0.0; 3]);
+//! soa::decode_columns32(&words, &mut mantissas, &mut exponents, &mut values);
+//! assert_eq!(mantissas, [0x400000, -0x800000, 0x53BE77]);
+//! assert_eq!(exponents, [1, 0, 3]);
+//! assert_eq!(values, [1.0, -1.0, 5.234]);
+//!
+//! let (high, low) = ([0x4000, 0x8000, 0x53BE], [0x0001, 0x0000, 0x7703]);
+//! let mut joined = [0.0; 3];
+//! soa::decode_halves32([&high, &low], &mut joined);
+//! assert_eq!(joined, values);
+//! ```
+
+use crate::arith;
+
+/// Split 32-bit words into their mantissas, exponents and values
+///
+/// # Panics
+///
+/// Panics if the slices are not all the same length.
+pub fn decode_columns32(
+    words: &[u32],
+    mantissas: &mut [i32],
+    exponents: &mut [i8],
+    values: &mut [f32],
+) {
+    check_lengths(
+        words.len(),
+        [mantissas.len(), exponents.len(), values.len()],
+    );
+    for (((&word, mantissa), exponent), value) in
+        words.iter().zip(mantissas).zip(exponents).zip(values)
+    {
+        let unpacked = arith::unpack32(word);
+        *mantissa = unpacked.mantissa as i32;
+        *exponent = unpacked.exponent as i8;
+        *value = arith::value32(word) as f32;
+    }
+}
+
+/// Split 48-bit words into their 40-bit mantissas, exponents and values
+///
+/// # Panics
+///
+/// Panics if the slices are not all the same length.
+pub fn decode_columns48(
+    words: &[u64],
+    mantissas: &mut [i64],
+    exponents: &mut [i8],
+    values: &mut [f64],
+) {
+    check_lengths(
+        words.len(),
+        [mantissas.len(), exponents.len(), values.len()],
+    );
+    for (((&word, mantissa), exponent), value) in
+        words.iter().zip(mantissas).zip(exponents).zip(values)
+    {
+        let unpacked = arith::unpack48(word);
+        *mantissa = unpacked.mantissa;
+        *exponent = unpacked.exponent as i8;
+        *value = arith::value48(word);
+    }
+}
+
+/// Decode 32-bit words whose two 16-bit halves are in separate arrays, most significant
+/// first
+///
+/// # Panics
+///
+/// Panics if the slices are not all the same length.
+pub fn decode_halves32(halves: [&[u16]; 2], values: &mut [f32]) {
+    let [high, low] = halves;
+    check_lengths(values.len(), [high.len(), low.len()]);
+    for ((&high, &low), value) in high.iter().zip(low).zip(values) {
+        *value = arith::value32((high as u32) << 16 | low as u32) as f32;
+    }
+}
+
+/// Decode 48-bit words whose three 16-bit thirds are in separate arrays, most significant
+/// first
+///
+/// # Panics
+///
+/// Panics if the slices are not all the same length.
+pub fn decode_halves48(thirds: [&[u16]; 3], values: &mut [f64]) {
+    let [high, middle, low] = thirds;
+    check_lengths(values.len(), [high.len(), middle.len(), low.len()]);
+    for (((&high, &middle), &low), value) in high.iter().zip(middle).zip(low).zip(values) {
+        let word = (high as u64) << 32 | (middle as u64) << 16 | low as u64;
+        *value = arith::value48(word);
+    }
+}
+
+/// Panic unless every length in `others` is `len`
+fn check_lengths<const N: usize>(len: usize, others: [usize; N]) {
+    assert!(
+        others.iter().all(|&other| other == len),
+        "structure-of-arrays slices differ in length: {len} and {others:?}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns48() {
+        // A negative word whose low mantissa bits are set, and an unnormalized one
+        let words = [0xFFFFFF00FFFF, 0x200000010000, 0];
+        let (mut mantissas, mut exponents, mut values) = ([0; 3], [0; 3], [0.0; 3]);
+        decode_columns48(&words, &mut mantissas, &mut exponents, &mut values);
+        assert_eq!(mantissas, [-1, 0x20_0000_0000, 0]);
+        assert_eq!(exponents, [0, 1, 0]);
+        assert_eq!(values, [-2f64.powi(-39), 0.5, 0.0]);
+
+        let split = |shift: u32| words.map(|word| (word >> shift) as u16);
+        let mut joined = [1.0; 3];
+        decode_halves48([&split(32), &split(16), &split(0)], &mut joined);
+        assert_eq!(joined, values);
+    }
+
+    #[test]
+    #[should_panic(expected = "differ in length")]
+    fn test_length_mismatch() {
+        decode_halves32([&[0x4000], &[]], &mut [0.0]);
+    }
+}
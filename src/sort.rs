@@ -0,0 +1,202 @@
+//! # Sort keys
+//! Order raw words by value without decoding them.
+//!
+//! Each `sort_key` function maps a word to an unsigned integer of the same width whose integer
+//! order is the numeric order of the word's value, so arrays of words can be radix sorted, and
+//! searched for a range of values, by key. Every word has a key, unnormalized ones included:
+//! words with the same value, such as the different encodings of zero, share a key, and words
+//! with different values never do.
+//!
+//! ```
+//! use mil1750a_converter::single;
+//! use mil1750a_converter::sort::sort_key_32;
+//!
+//! let mut words = [0x53BE7703, 0x80000000, 0x00000000, 0x40000001, 0xC0000000];
+//! words.sort_by_key(|&word| sort_key_32(word));
+//! assert_eq!(words, [0x80000000, 0xC0000000, 0x00000000, 0x40000001, 0x53BE7703]);
+//!
+//! // The words in [0.5, 2.0]
+//! let (low, high) = (sort_key_32(single::encode(0.5)), sort_key_32(single::encode(2.0)));
+//! let start = words.partition_point(|&word| sort_key_32(word) < low);
+//! let end = words.partition_point(|&word| sort_key_32(word) <= high);
+//! assert_eq!(words[start..end], [0x40000001]);
+//! ```
+//!
+//! ## Monotonicity
+//!
+//! Limit checks that compare encoded words by key are only sound if encoding keeps the order
+//! of its inputs: `a <= b` must give `key(encode(a)) <= key(encode(b))`. The encoders of
+//! [`EncodeMode::TwosComplement`] guarantee it, rounding to nearest and saturating, and
+//! [`assert_monotone`] checks that they do. The legacy encoders do not: the 16-bit one encodes
+//! `1.0` as `-1.0`. The `is_monotone_region` functions check one mode over a range of inputs
+//! at both ends of the range and at every input of the [`testgen`](crate::testgen)
+//! `adversarial` generators inside it, which crowd every rounding tie and power of two, where
+//! the word changes.
+//!
+//! ```
+//! use mil1750a_converter::sort::{assert_monotone, is_monotone_region16};
+//! use mil1750a_converter::EncodeMode;
+//!
+//! assert_monotone();
+//! assert!(is_monotone_region16(0.5..=0.75, EncodeMode::Legacy));
+//! assert!(!is_monotone_region16(0.75..=1.0, EncodeMode::Legacy));
+//! ```
+
+use crate::testgen::{adversarial16, adversarial32, adversarial48, Vector};
+use crate::{arith, extended, half, single, EncodeMode};
+use std::ops::RangeInclusive;
+
+/// A key for a 16-bit word whose order is the order of the word's value
+pub fn sort_key_16(word: u16) -> u16 {
+    key(arith::rank16(word), 1 << 15) as u16
+}
+
+/// A key for a 32-bit word whose order is the order of the word's value
+pub fn sort_key_32(word: u32) -> u32 {
+    key(arith::rank32(word), 1 << 31) as u32
+}
+
+/// A key for a 48-bit word whose order is the order of the word's value
+///
+/// Like the narrower keys, it is centred on the top bit of its type, `1 << 63`, so it is not
+/// confined to the low 48 bits.
+pub fn sort_key_48(word: u64) -> u64 {
+    key(arith::rank48(word), 1 << 63)
+}
+
+/// Whether encoding 16-bit words in `mode` keeps the order of the inputs in `range`, see
+/// [Monotonicity](self#monotonicity)
+pub fn is_monotone_region16(range: RangeInclusive<f32>, mode: EncodeMode) -> bool {
+    let range = f64::from(*range.start())..=f64::from(*range.end());
+    let encode = |value: f64| sort_key_16(half::encode_f32_with(value as f32, mode));
+    monotone(range, adversarial16(), encode)
+}
+
+/// Whether encoding 32-bit words in `mode` keeps the order of the inputs in `range`, see
+/// [Monotonicity](self#monotonicity)
+pub fn is_monotone_region32(range: RangeInclusive<f32>, mode: EncodeMode) -> bool {
+    let range = f64::from(*range.start())..=f64::from(*range.end());
+    let encode = |value: f64| sort_key_32(single::encode_with(value as f32, mode));
+    monotone(range, adversarial32(), encode)
+}
+
+/// Whether encoding 48-bit words in `mode` keeps the order of the inputs in `range`, see
+/// [Monotonicity](self#monotonicity)
+pub fn is_monotone_region48(range: RangeInclusive<f64>, mode: EncodeMode) -> bool {
+    let encode = |value: f64| sort_key_48(extended::encode_with(value, mode));
+    monotone(range, adversarial48(), encode)
+}
+
+/// Check that the [`EncodeMode::TwosComplement`] encoders of every format keep the order of
+/// all their finite inputs, see [Monotonicity](self#monotonicity)
+///
+/// # Panics
+///
+/// Panics, naming the format, if one does not.
+pub fn assert_monotone() {
+    let mode = EncodeMode::TwosComplement;
+    assert!(
+        is_monotone_region16(f32::MIN..=f32::MAX, mode),
+        "the 16-bit encoder is not monotone"
+    );
+    assert!(
+        is_monotone_region32(f32::MIN..=f32::MAX, mode),
+        "the 32-bit encoder is not monotone"
+    );
+    assert!(
+        is_monotone_region48(f64::MIN..=f64::MAX, mode),
+        "the 48-bit encoder is not monotone"
+    );
+}
+
+/// Whether the keys `encode` gives grow with the inputs of `vectors` in `range`, and its ends
+fn monotone<W, K: Ord>(
+    range: RangeInclusive<f64>,
+    vectors: Vec<Vector<W>>,
+    encode: impl Fn(f64) -> K,
+) -> bool {
+    let mut values: Vec<f64> = vectors
+        .into_iter()
+        .map(|vector| vector.value)
+        .filter(|value| range.contains(value))
+        .chain([*range.start(), *range.end()])
+        .collect();
+    values.sort_by(f64::total_cmp);
+    values
+        .windows(2)
+        .all(|pair| encode(pair[0]) <= encode(pair[1]))
+}
+
+/// The key of a signed rank, with zero at `zero`
+fn key((negative, rank): (bool, u64), zero: u64) -> u64 {
+    if negative {
+        zero - rank
+    } else {
+        zero + rank
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exhaustive_16() {
+        let mut words: Vec<u16> = (0..=u16::MAX).collect();
+        words.sort_by_key(|&word| sort_key_16(word));
+        for pair in words.windows(2) {
+            let (a, b) = (arith::value16(pair[0]), arith::value16(pair[1]));
+            assert!(a <= b, "{:#06X} sorts before {:#06X}", pair[0], pair[1]);
+            assert_eq!(a == b, sort_key_16(pair[0]) == sort_key_16(pair[1]));
+        }
+    }
+
+    #[test]
+    fn test_extremes() {
+        let words = [
+            0x800000007F00, // most negative
+            0x800000010000, // -1.0
+            0xBFFFFF00FFFF, // -0.5 less one ulp
+            0x400000000000, // 0.5
+            0x000000000000,
+            0x000000800000, // zero with a dirty exponent
+            0x200000800000, // unnormalized at the lowest exponent
+            0x7FFFFF7FFFFF, // most positive
+        ];
+        let values = words.map(arith::value48);
+        let keys = words.map(sort_key_48);
+        for (a, b) in (0..8).flat_map(|a| (0..8).map(move |b| (a, b))) {
+            assert_eq!(
+                values[a].partial_cmp(&values[b]),
+                keys[a].partial_cmp(&keys[b])
+            );
+        }
+        assert_eq!(sort_key_32(0x80000080), 0x8000_0000 - (1 << 23));
+        assert_eq!(sort_key_48(0), 1 << 63);
+    }
+
+    #[test]
+    fn test_monotone() {
+        assert_monotone();
+        let legacy = EncodeMode::Legacy;
+        assert!(!is_monotone_region16(-2.0..=2.0, legacy));
+        assert!(is_monotone_region32(0.25..=0.5, legacy));
+        assert!(is_monotone_region48(1.0..=1.0, legacy));
+        assert!(is_monotone_region48(
+            -3.0..=-1.0,
+            EncodeMode::TwosComplement
+        ));
+
+        // Every 16-bit word's value and the inputs on either side of it
+        let mut values: Vec<f32> = (0..=u16::MAX)
+            .map(|word| arith::value16(word) as f32)
+            .flat_map(|value| [value.next_down(), value, value.next_up()])
+            .collect();
+        values.sort_by(f32::total_cmp);
+        let keys: Vec<u16> = values
+            .into_iter()
+            .map(|value| sort_key_16(half::encode_f32_with(value, EncodeMode::TwosComplement)))
+            .collect();
+        assert!(keys.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
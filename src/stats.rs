@@ -0,0 +1,254 @@
+//! # Conversion statistics
+//! A [`Stats`] sink collects data-quality figures while words are converted, so bulk
+//! conversions need no second pass.
+//!
+//! Its conversion methods convert exactly like the lenient decoders and the
+//! [`EncodeMode::TwosComplement`](crate::EncodeMode::TwosComplement) encoders, and record each
+//! value on the way through: decoders count unnormalized words as invalid, and encoders count
+//! values the format cannot represent as out of range and file the rounding error of the rest,
+//! in ULPs of the encoded word, into a histogram.
+//!
+//! ```
+//! use mil1750a_converter::stats::Stats;
+//!
+//! let mut stats = Stats::new();
+//! let words: Vec<u32> = [1.0, 0.1, -2.5].into_iter().map(|v| stats.encode32(v)).collect();
+//! let values: Vec<f32> = words.iter().map(|&w| stats.decode32(w)).collect();
+//! stats.decode32(0x20000002);
+//!
+//! assert_eq!(stats.count(), 7);
+//! assert_eq!((stats.min(), stats.max()), (Some(-2.5), Some(1.0)));
+//! assert_eq!(stats.invalid(), 1);
+//! assert_eq!(stats.histogram().iter().sum::<u64>(), 3);
+//! ```
+
+use crate::{arith, extended, single, Lenient};
+#[cfg(feature = "half")]
+use half::f16;
+
+/// Number of bins in the quantization error histogram
+pub const HISTOGRAM_BINS: usize = 16;
+
+/// Running statistics of converted values
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    count: u64,
+    finite: u64,
+    min: Option<f64>,
+    max: Option<f64>,
+    mean: f64,
+    invalid: u64,
+    out_of_range: u64,
+    histogram: [u64; HISTOGRAM_BINS],
+}
+
+impl Stats {
+    /// Empty statistics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a converted value; values that are not finite only count towards
+    /// [`count`](Self::count)
+    pub fn record(&mut self, value: f64) {
+        self.count += 1;
+        if !value.is_finite() {
+            return;
+        }
+        self.finite += 1;
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+        self.mean += (value - self.mean) / self.finite as f64;
+    }
+
+    /// Record a word that decoded to an unnormalized or dirty zero value
+    pub fn record_invalid(&mut self) {
+        self.invalid += 1;
+    }
+
+    /// Record a value that was out of range of its format
+    pub fn record_out_of_range(&mut self) {
+        self.out_of_range += 1;
+    }
+
+    /// Record a rounding error in ULPs, normally within `±0.5`; errors beyond the histogram
+    /// land in its end bins
+    pub fn record_quantization(&mut self, error_ulps: f64) {
+        if error_ulps.is_nan() {
+            return;
+        }
+        let bin = ((error_ulps + 0.5) * HISTOGRAM_BINS as f64).floor();
+        self.histogram[bin.clamp(0.0, (HISTOGRAM_BINS - 1) as f64) as usize] += 1;
+    }
+
+    /// Decode a 16-bit word as [`half::decode_lenient`](crate::half::decode_lenient) does and
+    /// record it
+    #[cfg(feature = "half")]
+    pub fn decode16(&mut self, word: u16) -> f16 {
+        self.decoded(crate::half::decode_lenient(word))
+    }
+
+    /// Decode a 32-bit word as [`single::decode_lenient`] does and record it
+    pub fn decode32(&mut self, word: u32) -> f32 {
+        self.decoded(single::decode_lenient(word))
+    }
+
+    /// Decode a 48-bit word as [`extended::decode_lenient`] does and record it
+    pub fn decode48(&mut self, word: u64) -> f64 {
+        self.decoded(extended::decode_lenient(word))
+    }
+
+    /// Encode a value as a normalized 16-bit word, saturating, and record it
+    #[cfg(feature = "half")]
+    pub fn encode16(&mut self, value: f16) -> u16 {
+        let value = value.into();
+        let word = self.encoded(value, arith::try_round16(value), arith::round16);
+        self.quantized(value, arith::value16(word), arith::ulp16(word));
+        word
+    }
+
+    /// Encode a value as a normalized 32-bit word, saturating, and record it
+    pub fn encode32(&mut self, value: f32) -> u32 {
+        let value = value.into();
+        let word = self.encoded(value, arith::try_round32(value), arith::round32);
+        self.quantized(value, arith::value32(word), arith::ulp32(word));
+        word
+    }
+
+    /// Encode a value as a normalized 48-bit word, saturating, and record it
+    pub fn encode48(&mut self, value: f64) -> u64 {
+        let word = self.encoded(value, arith::try_round48(value), arith::round48);
+        self.quantized(value, arith::value48(word), arith::ulp48(word));
+        word
+    }
+
+    /// Number of values recorded
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Smallest finite value recorded
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+
+    /// Largest finite value recorded
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+
+    /// Mean of the finite values recorded
+    pub fn mean(&self) -> Option<f64> {
+        (self.finite > 0).then_some(self.mean)
+    }
+
+    /// Number of unnormalized words decoded
+    pub fn invalid(&self) -> u64 {
+        self.invalid
+    }
+
+    /// Number of values that could not be encoded
+    pub fn out_of_range(&self) -> u64 {
+        self.out_of_range
+    }
+
+    /// Counts of rounding errors, the bins evenly covering `-0.5..0.5` ULP
+    pub fn histogram(&self) -> &[u64; HISTOGRAM_BINS] {
+        &self.histogram
+    }
+
+    /// Fold the statistics of another sink, such as one fed by another thread, into these
+    pub fn merge(&mut self, other: &Stats) {
+        if other.finite > 0 {
+            let total = (self.finite + other.finite) as f64;
+            self.mean += (other.mean - self.mean) * other.finite as f64 / total;
+        }
+        self.min = merge_bound(self.min, other.min, f64::min);
+        self.max = merge_bound(self.max, other.max, f64::max);
+        self.count += other.count;
+        self.finite += other.finite;
+        self.invalid += other.invalid;
+        self.out_of_range += other.out_of_range;
+        for (bin, other) in self.histogram.iter_mut().zip(other.histogram) {
+            *bin += other;
+        }
+    }
+
+    fn decoded<T: Copy + Into<f64>>(&mut self, decoded: Lenient<T>) -> T {
+        if !decoded.normalized {
+            self.record_invalid();
+        }
+        self.record(decoded.value.into());
+        decoded.value
+    }
+
+    fn encoded<W>(&mut self, value: f64, word: Option<W>, saturate: fn(f64) -> W) -> W {
+        self.record(value);
+        word.unwrap_or_else(|| {
+            self.record_out_of_range();
+            saturate(value)
+        })
+    }
+
+    fn quantized(&mut self, value: f64, encoded: f64, ulp: f64) {
+        if value.is_finite() && (encoded - value).abs() <= ulp {
+            self.record_quantization((encoded - value) / ulp);
+        }
+    }
+}
+
+fn merge_bound(a: Option<f64>, b: Option<f64>, pick: fn(f64, f64) -> f64) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(pick(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut stats = Stats::new();
+        assert_eq!((stats.min(), stats.mean()), (None, None));
+        for value in [2.0, f64::NAN, -4.0, 8.0] {
+            stats.record(value);
+        }
+        assert_eq!(stats.count(), 4);
+        assert_eq!(
+            (stats.min(), stats.max(), stats.mean()),
+            (Some(-4.0), Some(8.0), Some(2.0))
+        );
+
+        let mut other = Stats::new();
+        other.record(6.0);
+        other.record_invalid();
+        stats.merge(&other);
+        assert_eq!(
+            (stats.count(), stats.invalid(), stats.mean()),
+            (5, 1, Some(3.0))
+        );
+        assert_eq!(stats.max(), Some(8.0));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mut stats = Stats::new();
+        assert_eq!(
+            stats.encode48(f64::MAX),
+            extended::encode_with(f64::MAX, Default::default())
+        );
+        assert_eq!(stats.encode32(1.0), 0x40000001);
+        assert_eq!(stats.decode32(0x40000001), 1.0);
+        assert_eq!(stats.decode48(0xC00000010000), -1.0);
+        assert_eq!((stats.out_of_range(), stats.invalid()), (1, 1));
+        assert_eq!(stats.histogram()[HISTOGRAM_BINS / 2], 1);
+        assert_eq!(stats.histogram().iter().sum::<u64>(), 1);
+
+        stats.record_quantization(-0.5);
+        stats.record_quantization(3.0);
+        assert_eq!(stats.histogram()[0], 1);
+        assert_eq!(stats.histogram()[HISTOGRAM_BINS - 1], 1);
+    }
+}
@@ -0,0 +1,432 @@
+//! # Streaming decoder
+//! Decode words as their bytes arrive, for a UART or a 1553 FIFO on the target itself.
+//!
+//! A [`StreamDecoder`] holds the bytes of the word in progress in a fixed buffer inside itself,
+//! so it never allocates: push bytes one at a time with [`push`](StreamDecoder::push), whole
+//! 16-bit FIFO words with [`push_word`](StreamDecoder::push_word), or slices with
+//! [`feed`](StreamDecoder::feed), which hands every completed value to a callback. Words are
+//! decoded with [`MilFormat::decode`], like the `tokio` codecs.
+//!
+//! The decoder itself needs nothing beyond `core`, but the crate as a whole still links `std`.
+//! With the `tracing` feature [`feed`](StreamDecoder::feed) emits a `trace` event of the bytes
+//! taken and values decoded, and [`reset`](StreamDecoder::reset) a `debug` event when it drops
+//! bytes of an incomplete word.
+//!
+//! ```
+//! use mil1750a_converter::stream::StreamDecoder;
+//! use mil1750a_converter::F32;
+//!
+//! let mut decoder = StreamDecoder::<F32>::new();
+//! let mut values = [0.0; 2];
+//! let mut count = 0;
+//! for chunk in [&[0x40, 0x00][..], &[0x00, 0x01, 0x53], &[0xBE, 0x77, 0x03, 0x40]] {
+//!     decoder.feed(chunk, |value| {
+//!         values[count] = value;
+//!         count += 1;
+//!     });
+//! }
+//! assert_eq!(values, [1.0, 5.234]);
+//! assert_eq!(decoder.pending(), 1);
+//! ```
+//!
+//! ## Recovery
+//!
+//! Recorder streams have dropouts. [`feed_recovering`](StreamDecoder::feed_recovering) and
+//! [`push_recovering`](StreamDecoder::push_recovering) decode with
+//! [`MilFormat::decode_strict`] instead, which reads every normalized word to the same value,
+//! and on a word it rejects drop bytes until the decoder is back in
+//! step as its [`Resync`] says, then report the bytes dropped as a [`Gap`] rather than
+//! stopping. With the `tracing` feature each gap is also a `warn` event.
+//!
+//! ```
+//! use mil1750a_converter::stream::{Resync, StreamDecoder};
+//! use mil1750a_converter::F32;
+//!
+//! let mut decoder = StreamDecoder::<F32>::new().resync(Resync::SyncWord(0xEB90));
+//! let bytes = [
+//!     0x40, 0x00, 0x00, 0x01, // 1.0
+//!     0x20, 0x00, 0x00, 0x01, // unnormalized
+//!     0x12, 0x34, 0xEB, 0x90, // noise, then the sync word
+//!     0x53, 0xBE, 0x77, 0x03, // 5.234
+//! ];
+//! let (mut values, mut gaps) = (Vec::new(), Vec::new());
+//! decoder.feed_recovering(&bytes, |value| values.push(value), |gap| gaps.push(gap));
+//! assert_eq!(values, [1.0, 5.234]);
+//! assert_eq!((gaps[0].offset, gaps[0].bytes), (4, 8));
+//! ```
+
+use crate::{DecodeError, MilFormat, WordOrder};
+use core::marker::PhantomData;
+
+/// Bytes of the widest word
+const MAX_BYTES: usize = 6;
+
+/// How a recovering [`StreamDecoder`] gets back in step after a word it rejects
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Resync {
+    /// Drop the rejected word alone and decode the next
+    #[default]
+    Skip,
+    /// Drop bytes up to and including the next occurrence of a 16-bit sync word, high byte
+    /// first, and decode the words after it
+    SyncWord(u16),
+    /// Drop bytes up to the start of the next frame of this many bytes, frames starting at
+    /// the first byte pushed
+    FrameLength(u64),
+}
+
+/// Bytes a recovering [`StreamDecoder`] dropped to get back in step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gap {
+    /// Offset of the first byte dropped, from the first byte pushed
+    pub offset: u64,
+    /// Number of bytes dropped, the rejected word included
+    pub bytes: u64,
+    /// Why the word starting the gap was rejected
+    pub error: DecodeError,
+}
+
+/// What a byte pushed to a recovering [`StreamDecoder`] completed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Recovered<T> {
+    /// A word decoded to a value
+    Value(T),
+    /// The decoder is back in step after dropping bytes
+    Gap(Gap),
+}
+
+/// Where a recovering decoder is in the stream
+#[derive(Debug, Clone, Copy)]
+enum State {
+    /// Decoding words
+    Locked,
+    /// Dropping bytes since the word at `start` was rejected
+    Hunting {
+        start: u64,
+        error: DecodeError,
+        /// The last two bytes dropped, for finding a sync word
+        window: u16,
+        /// Bytes dropped since the rejected word
+        dropped: u64,
+    },
+}
+
+/// An incremental decoder of a byte stream of `F` words
+#[derive(Debug, Clone)]
+pub struct StreamDecoder<F: MilFormat> {
+    buffer: [u8; MAX_BYTES],
+    len: usize,
+    word_order: WordOrder,
+    resync: Resync,
+    state: State,
+    position: u64,
+    format: PhantomData<F>,
+}
+
+impl<F: MilFormat> StreamDecoder<F> {
+    /// A decoder of big-endian words, most significant word first
+    pub const fn new() -> Self {
+        StreamDecoder {
+            buffer: [0; MAX_BYTES],
+            len: 0,
+            word_order: WordOrder::MsWordFirst,
+            resync: Resync::Skip,
+            state: State::Locked,
+            position: 0,
+            format: PhantomData,
+        }
+    }
+
+    /// Read the 16-bit words of each word in `order`
+    pub const fn word_order(mut self, order: WordOrder) -> Self {
+        self.word_order = order;
+        self
+    }
+
+    /// Get back in step as `resync` says after a word the recovering methods reject;
+    /// [`Resync::Skip`] by default
+    ///
+    /// # Panics
+    ///
+    /// Panics if a frame length is zero.
+    pub const fn resync(mut self, resync: Resync) -> Self {
+        assert!(
+            !matches!(resync, Resync::FrameLength(0)),
+            "frames must be at least one byte long"
+        );
+        self.resync = resync;
+        self
+    }
+
+    /// Add a byte, returning the decoded value if it completes a word
+    pub fn push(&mut self, byte: u8) -> Option<F::Ieee> {
+        self.take(byte).map(F::decode)
+    }
+
+    /// Add a byte, returning the value of the word it completes or, if the decoder has just
+    /// got back in step, the gap it dropped; see [Recovery](self#recovery)
+    pub fn push_recovering(&mut self, byte: u8) -> Option<Recovered<F::Ieee>> {
+        let State::Hunting {
+            start,
+            error,
+            window,
+            dropped,
+        } = self.state
+        else {
+            let value = F::decode_strict(self.take(byte)?);
+            return match (value, self.resync) {
+                (Ok(value), _) => Some(Recovered::Value(value)),
+                (Err(error), Resync::Skip) => Some(Recovered::Gap(Gap {
+                    offset: self.position - F::BYTES as u64,
+                    bytes: F::BYTES as u64,
+                    error,
+                })),
+                (Err(error), _) => {
+                    self.state = State::Hunting {
+                        start: self.position - F::BYTES as u64,
+                        error,
+                        window: 0,
+                        dropped: 0,
+                    };
+                    self.hunted()
+                }
+            };
+        };
+        self.position += 1;
+        self.state = State::Hunting {
+            start,
+            error,
+            window: window << 8 | u16::from(byte),
+            dropped: dropped + 1,
+        };
+        self.hunted()
+    }
+
+    /// Add a slice of bytes as [`push_recovering`](Self::push_recovering) does, calling
+    /// `emit` with each decoded value and `gap` with each gap in order, and return how many
+    /// values were decoded
+    pub fn feed_recovering(
+        &mut self,
+        bytes: &[u8],
+        mut emit: impl FnMut(F::Ieee),
+        mut gap: impl FnMut(Gap),
+    ) -> usize {
+        let mut count = 0;
+        for &byte in bytes {
+            match self.push_recovering(byte) {
+                Some(Recovered::Value(value)) => {
+                    emit(value);
+                    count += 1;
+                }
+                Some(Recovered::Gap(dropped)) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        offset = dropped.offset,
+                        bytes = dropped.bytes,
+                        error = %dropped.error,
+                        "stream resynchronized"
+                    );
+                    gap(dropped);
+                }
+                None => {}
+            }
+        }
+        count
+    }
+
+    /// Add a byte to the word in progress, returning the word if it is complete
+    fn take(&mut self, byte: u8) -> Option<F::Bits> {
+        self.buffer[self.len] = byte;
+        self.len += 1;
+        self.position += 1;
+        if self.len < F::BYTES {
+            return None;
+        }
+        self.len = 0;
+        Some(F::read_ordered(&self.buffer, self.word_order))
+    }
+
+    /// End a hunt if the decoder is back in step, returning the gap
+    fn hunted(&mut self) -> Option<Recovered<F::Ieee>> {
+        let State::Hunting {
+            start,
+            error,
+            window,
+            dropped,
+        } = self.state
+        else {
+            return None;
+        };
+        let in_step = match self.resync {
+            Resync::Skip => true,
+            Resync::SyncWord(sync) => dropped >= 2 && window == sync,
+            Resync::FrameLength(length) => self.position.is_multiple_of(length),
+        };
+        if !in_step {
+            return None;
+        }
+        self.state = State::Locked;
+        Some(Recovered::Gap(Gap {
+            offset: start,
+            bytes: self.position - start,
+            error,
+        }))
+    }
+
+    /// Add a 16-bit word, high byte first, returning the decoded value if it completes a word
+    pub fn push_word(&mut self, word: u16) -> Option<F::Ieee> {
+        let [high, low] = word.to_be_bytes();
+        // Every format is at least two bytes wide, so at most one of these completes a word
+        self.push(high).or(self.push(low))
+    }
+
+    /// Add a slice of bytes, calling `emit` with each decoded value in order, and return how
+    /// many values were decoded
+    pub fn feed(&mut self, bytes: &[u8], mut emit: impl FnMut(F::Ieee)) -> usize {
+        let mut count = 0;
+        for &byte in bytes {
+            if let Some(value) = self.push(byte) {
+                emit(value);
+                count += 1;
+            }
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            bytes = bytes.len(),
+            decoded = count,
+            pending = self.len,
+            "stream fed"
+        );
+        count
+    }
+
+    /// Number of bytes of an incomplete word held
+    pub fn pending(&self) -> usize {
+        self.len
+    }
+
+    /// Whether a recovering decoder is dropping bytes to get back in step
+    pub fn is_hunting(&self) -> bool {
+        matches!(self.state, State::Hunting { .. })
+    }
+
+    /// Drop any incomplete word, for resynchronizing after a gap in the stream; a hunt for
+    /// the next frame or sync word goes on
+    pub fn reset(&mut self) {
+        #[cfg(feature = "tracing")]
+        if self.len > 0 {
+            tracing::debug!(dropped = self.len, "incomplete word dropped");
+        }
+        self.len = 0;
+    }
+}
+
+impl<F: MilFormat> Default for StreamDecoder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{extended, F32, F48};
+
+    #[test]
+    fn test_push() {
+        let bytes = extended::encode(105.639485637361).to_be_bytes();
+        let mut decoder = StreamDecoder::<F48>::new();
+        for &byte in &bytes[2..7] {
+            assert_eq!(decoder.push(byte), None);
+        }
+        assert_eq!(decoder.push(bytes[7]), Some(105.63948563742451));
+        assert_eq!(decoder.pending(), 0);
+
+        let mut decoder = StreamDecoder::<F48>::new().word_order(WordOrder::LsWordFirst);
+        assert_eq!(decoder.push_word(0x4000), None);
+        decoder.reset();
+        assert_eq!(decoder.push_word(0x0000), None);
+        assert_eq!(decoder.push_word(0x0001), None);
+        assert_eq!(decoder.push_word(0x4000), Some(1.0));
+
+        // Both readings agree on normalized words, negative exponents included
+        let bytes = [0x40, 0x00, 0x00, 0xFF, 0x80, 0x00, 0x00, 0xFE];
+        let mut decoder = StreamDecoder::<F32>::new();
+        let (mut fed, mut recovered) = (Vec::new(), Vec::new());
+        decoder.feed(&bytes, |value| fed.push(value));
+        decoder.feed_recovering(&bytes, |value| recovered.push(value), |_| unreachable!());
+        assert_eq!(fed, [0.25, -0.25]);
+        assert_eq!(recovered, fed);
+    }
+
+    #[test]
+    fn test_push_word_unaligned() {
+        let mut decoder = StreamDecoder::<F48>::new();
+        assert_eq!(decoder.push(0x40), None);
+        assert_eq!(decoder.push_word(0x0000), None);
+        assert_eq!(decoder.push_word(0x0100), None);
+        assert_eq!(decoder.push_word(0x0000), Some(1.0));
+        assert_eq!(decoder.pending(), 1);
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_recovery() {
+        use crate::{DecodeError, F16};
+
+        // Frames of three 16-bit words; the second frame's first word has a dirty zero
+        let words = [
+            0x4001, 0x8000, 0x6344, 0x0001, 0x4001, 0x4001, 0x8000, 0x2002, 0x4001,
+        ];
+        let bytes: Vec<u8> = words
+            .iter()
+            .flat_map(|word: &u16| word.to_be_bytes())
+            .collect();
+        let mut decoder = StreamDecoder::<F16>::new().resync(Resync::FrameLength(6));
+        let (mut values, mut gaps) = (Vec::new(), Vec::new());
+        let count = decoder.feed_recovering(
+            &bytes[..9],
+            |value| values.push(f32::from(value)),
+            |gap| gaps.push(gap),
+        );
+        assert_eq!((count, decoder.is_hunting()), (3, true));
+        decoder.feed_recovering(
+            &bytes[9..],
+            |value| values.push(f32::from(value)),
+            |gap| gaps.push(gap),
+        );
+        assert_eq!(values, [1.0, -1.0, 12.40625, -1.0]);
+        let dirty_zero = DecodeError::NonZeroExponent { word: 1, bits: 16 };
+        let unnormalized = DecodeError::Unnormalized {
+            word: 0x2002,
+            bits: 16,
+        };
+        assert_eq!(
+            gaps,
+            [
+                Gap {
+                    offset: 6,
+                    bytes: 6,
+                    error: dirty_zero
+                },
+                Gap {
+                    offset: 14,
+                    bytes: 4,
+                    error: unnormalized
+                },
+            ]
+        );
+
+        let mut decoder = StreamDecoder::<F16>::new();
+        assert_eq!(decoder.push_recovering(0x00), None);
+        assert_eq!(
+            decoder.push_recovering(0x01),
+            Some(Recovered::Gap(Gap {
+                offset: 0,
+                bytes: 2,
+                error: dirty_zero
+            }))
+        );
+        assert!(!decoder.is_hunting());
+    }
+}
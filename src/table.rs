@@ -0,0 +1,116 @@
+//! # Compile-time tables
+//! Encode tables of constants while compiling, for parameter tables embedded in firmware.
+//!
+//! [`mil16_table!`](crate::mil16_table), [`mil32_table!`](crate::mil32_table) and
+//! [`mil48_table!`](crate::mil48_table) expand a list of values to an array of words, each
+//! rounded to nearest with ties away from zero like
+//! [`EncodeMode::TwosComplement`](EncodeMode::TwosComplement). In a `static` or `const`
+//! initializer the words are computed by the compiler, and a value the format cannot hold is a
+//! compile error rather than a wrong word.
+//!
+//! ```
+//! use mil1750a_converter::{mil32_table, mil48_table};
+//!
+//! static GAINS: [u32; 4] = mil32_table![1.0, 2.5, 3.75, -0.1];
+//! const OFFSETS: [u64; 2] = mil48_table![105.639485637361, 1];
+//! assert_eq!(GAINS, [0x40000001, 0x50000002, 0x78000002, 0x99999AFD]);
+//! assert_eq!(OFFSETS, [0x69A3B50754AB, 0x400000010000]);
+//! ```
+//!
+//! ```compile_fail
+//! static TOO_LARGE: [u16; 1] = mil1750a_converter::mil16_table![1e10];
+//! ```
+
+use crate::arith;
+
+/// Round a value to the nearest 16-bit word, in a const context
+///
+/// # Panics
+///
+/// Panics if `value` is NaN, infinite or out of range; in a `const` or `static` initializer
+/// that is a compile error.
+pub const fn encode16(value: f64) -> u16 {
+    match arith::try_round16(value) {
+        Some(word) => word,
+        None => panic!("value cannot be encoded as a 16-bit MIL-1750A word"),
+    }
+}
+
+/// Round a value to the nearest 32-bit word, in a const context
+///
+/// # Panics
+///
+/// Panics if `value` is NaN, infinite or out of range; in a `const` or `static` initializer
+/// that is a compile error.
+pub const fn encode32(value: f64) -> u32 {
+    match arith::try_round32(value) {
+        Some(word) => word,
+        None => panic!("value cannot be encoded as a 32-bit MIL-1750A word"),
+    }
+}
+
+/// Round a value to the nearest 48-bit word, in a const context
+///
+/// # Panics
+///
+/// Panics if `value` is NaN, infinite or out of range; in a `const` or `static` initializer
+/// that is a compile error.
+pub const fn encode48(value: f64) -> u64 {
+    match arith::try_round48(value) {
+        Some(word) => word,
+        None => panic!("value cannot be encoded as a 48-bit MIL-1750A word"),
+    }
+}
+
+/// An array of 16-bit words encoded from a list of numbers, see the [`table`](crate::table)
+/// module
+#[macro_export]
+macro_rules! mil16_table {
+    ($($value:expr),* $(,)?) => {
+        [$($crate::table::encode16($value as f64)),*]
+    };
+}
+
+/// An array of 32-bit words encoded from a list of numbers, see the [`table`](crate::table)
+/// module
+#[macro_export]
+macro_rules! mil32_table {
+    ($($value:expr),* $(,)?) => {
+        [$($crate::table::encode32($value as f64)),*]
+    };
+}
+
+/// An array of 48-bit words encoded from a list of numbers, see the [`table`](crate::table)
+/// module
+#[macro_export]
+macro_rules! mil48_table {
+    ($($value:expr),* $(,)?) => {
+        [$($crate::table::encode48($value as f64)),*]
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{extended, single, EncodeMode};
+
+    static SMALL: [u16; 3] = mil16_table![12.40625, -1, 0.0];
+
+    #[test]
+    fn test_tables() {
+        assert_eq!(SMALL, [0x6344, 0x8000, 0x0000]);
+        const VALUES: [f64; 3] = [5.234, -1234.5, 1e-30];
+        const WORDS: [u32; 3] = mil32_table![VALUES[0], VALUES[1], VALUES[2]];
+        for (word, value) in WORDS.into_iter().zip(VALUES) {
+            assert_eq!(
+                word,
+                single::encode_with(value as f32, EncodeMode::TwosComplement)
+            );
+        }
+        let empty: [u64; 0] = mil48_table![];
+        assert!(empty.is_empty());
+        assert_eq!(
+            mil48_table![-0.5][0],
+            extended::encode_with(-0.5, EncodeMode::TwosComplement)
+        );
+    }
+}
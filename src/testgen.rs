@@ -0,0 +1,479 @@
+//! # Test vectors
+//! Curated `(value, word)` pairs for each format, for testbenches of other implementations.
+//!
+//! The vectors cover zero, every power of two the format holds, the largest and the most
+//! negative mantissa at every exponent, the extremes of the format, and values exactly halfway
+//! between two words. Words are the normalized encodings of
+//! [`EncodeMode::TwosComplement`](crate::EncodeMode::TwosComplement), so halfway values are
+//! rounded away from zero. Values are given as `f64`, which holds every one of them exactly,
+//! including the 16- and 32-bit values that [`f16`](half::f16) and [`f32`] cannot.
+//!
+//! ```
+//! use mil1750a_converter::testgen::{vectors32, Case};
+//!
+//! let vectors = vectors32();
+//! let max = vectors.iter().find(|v| v.case == Case::Extreme).unwrap();
+//! assert_eq!(max.word, 0x7FFFFF7F);
+//! assert!(vectors.iter().any(|v| v.value == 1.0 && v.word == 0x40000001));
+//! ```
+//!
+//! The `adversarial` functions instead crowd their inputs around the encoder's decisions, to stress
+//! implementations that must match it bit for bit: every rounding tie of every exponent and the
+//! nearest inputs on either side of it, the nearest inputs on either side of every power of two,
+//! values just beyond the largest and below the smallest magnitude, and IEEE subnormals. Inputs are
+//! in the precision the encoder is given, `f32` for the 16- and 32-bit formats and `f64` for the
+//! 48-bit one, so every value converts to that type exactly; ties below the smallest normal `f32`,
+//! which it cannot hold, are left out. Words saturate and flush to zero as
+//! [`EncodeMode::TwosComplement`] does.
+//!
+//! [`EncodeMode::TwosComplement`]: crate::EncodeMode::TwosComplement
+//!
+//! ```
+//! use mil1750a_converter::testgen::{adversarial32, Case};
+//! use mil1750a_converter::{single, EncodeMode};
+//!
+//! for vector in adversarial32() {
+//!     let input = vector.value as f32;
+//!     assert_eq!(single::encode_with(input, EncodeMode::TwosComplement), vector.word);
+//! }
+//! assert!(adversarial32().iter().any(|v| v.case == Case::Subnormal && v.word != 0));
+//! ```
+//!
+//! A [`sweep`] instead spaces values evenly, or evenly in ratio, between two bounds and encodes
+//! them in a chosen [`FormatTag`], for stimulus tables. It exports as CSV, as a hex file for
+//! Verilog's `$readmemh`, or as a VHDL constant array.
+//!
+//! ```
+//! use mil1750a_converter::batch::FormatTag;
+//! use mil1750a_converter::testgen::{sweep, Spacing};
+//!
+//! let table = sweep(1.0, 8.0, 4, Spacing::Logarithmic, FormatTag::F32).unwrap();
+//! assert_eq!(table.points[1], (2.0, 0x4000_0002));
+//! assert_eq!(table.csv().lines().nth(1), Some("1,0x40000001"));
+//! assert_eq!(table.readmemh().lines().last(), Some("40000004 // 8"));
+//! assert!(table.vhdl("stimulus").contains("constant stimulus : stimulus_t := ("));
+//! ```
+
+use crate::arith;
+use crate::batch::FormatTag;
+use crate::Mil1750Error;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+/// What a test vector exercises
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Case {
+    /// Zero
+    Zero,
+    /// An exact power of two, positive or negative
+    PowerOfTwo,
+    /// The largest positive or the most negative mantissa at an exponent
+    ExponentBoundary,
+    /// The largest, most negative or smallest magnitude value of the format
+    Extreme,
+    /// A value exactly halfway between two words
+    RoundingTie,
+    /// The nearest input on either side of a rounding tie
+    NearTie,
+    /// The nearest input on either side of a power of two, where the exponent changes
+    ExponentTransition,
+    /// A value beyond the largest magnitude of the format or below its smallest, which
+    /// saturates or flushes to zero
+    OutOfRange,
+    /// An IEEE subnormal of the encoder's input type
+    Subnormal,
+}
+
+/// A value and the word it encodes to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector<W> {
+    /// The value, exactly
+    pub value: f64,
+    /// Its encoded word
+    pub word: W,
+    /// What the vector exercises
+    pub case: Case,
+}
+
+/// Test vectors for the 16-bit format
+pub fn vectors16() -> Vec<Vector<u16>> {
+    vectors(9, -32..=31, arith::try_round16)
+}
+
+/// Test vectors for the 32-bit format
+pub fn vectors32() -> Vec<Vector<u32>> {
+    vectors(23, -128..=127, arith::try_round32)
+}
+
+/// Test vectors for the 48-bit format
+pub fn vectors48() -> Vec<Vector<u64>> {
+    vectors(39, -128..=127, arith::try_round48)
+}
+
+/// Inputs around the decisions of the 16-bit encoder, in `f32` precision
+pub fn adversarial16() -> Vec<Vector<u16>> {
+    adversarial(9, -32..=31, f32_step, F32_SUBNORMALS, arith::round16)
+}
+
+/// Inputs around the decisions of the 32-bit encoder, in `f32` precision
+pub fn adversarial32() -> Vec<Vector<u32>> {
+    adversarial(23, -128..=127, f32_step, F32_SUBNORMALS, arith::round32)
+}
+
+/// Inputs around the decisions of the 48-bit encoder, in `f64` precision
+pub fn adversarial48() -> Vec<Vector<u64>> {
+    let subnormals = [f64::from_bits(1), f64::from_bits((1 << 52) - 1)];
+    adversarial(39, -128..=127, f64_step, subnormals, arith::round48)
+}
+
+/// The smallest and largest positive `f32` subnormals
+const F32_SUBNORMALS: [f64; 2] = [1.401298464324817e-45, 1.1754942106924411e-38];
+
+/// The next `f32` after `value` up or down, or `None` if `value` is not an `f32`
+fn f32_step(value: f64, up: bool) -> Option<f64> {
+    let single = value as f32;
+    (f64::from(single) == value).then(|| {
+        f64::from(if up {
+            single.next_up()
+        } else {
+            single.next_down()
+        })
+    })
+}
+
+/// The next `f64` after `value`, up or down
+fn f64_step(value: f64, up: bool) -> Option<f64> {
+    Some(if up {
+        value.next_up()
+    } else {
+        value.next_down()
+    })
+}
+
+/// Adversarial inputs for a format with `fraction` mantissa fraction bits and the given
+/// exponents, with neighbours in the input precision found by `step`
+fn adversarial<W>(
+    fraction: u32,
+    exponents: RangeInclusive<i32>,
+    step: fn(f64, bool) -> Option<f64>,
+    subnormals: [f64; 2],
+    round: fn(f64) -> W,
+) -> Vec<Vector<W>> {
+    let ulp = 2f64.powi(-(fraction as i32));
+    let (min, max) = (*exponents.start(), *exponents.end());
+    let scale = |exponent: i32| 2f64.powi(exponent);
+    let around = |value: f64| [step(value, false), step(value, true)];
+    let next = |value: f64, up: bool| step(value, up).expect("an input");
+
+    let mut cases = Vec::new();
+    for exponent in exponents {
+        for mantissa in [0.5 + ulp / 2.0, 1.0 - ulp / 2.0] {
+            for sign in [1.0, -1.0] {
+                let tie = sign * mantissa * scale(exponent);
+                if let [Some(below), Some(above)] = around(tie) {
+                    cases.push((tie, Case::RoundingTie));
+                    cases.extend([below, above].map(|value| (value, Case::NearTie)));
+                }
+            }
+        }
+        // Mantissas of 0.5 and -1.0 start an exponent
+        for power in [scale(exponent - 1), -scale(exponent)] {
+            let neighbours = [next(power, false), next(power, true)];
+            cases.extend(neighbours.map(|value| (value, Case::ExponentTransition)));
+        }
+    }
+    let (largest, smallest) = ((1.0 - ulp) * scale(max), 0.5 * scale(min));
+    cases.extend(
+        [
+            next(largest, true),
+            scale(max),
+            next(-scale(max), false),
+            smallest / 2.0,
+            -smallest / 2.0,
+        ]
+        .map(|value| (value, Case::OutOfRange)),
+    );
+    for subnormal in subnormals {
+        cases.extend([subnormal, -subnormal].map(|value| (value, Case::Subnormal)));
+    }
+
+    cases
+        .into_iter()
+        .map(|(value, case)| Vector {
+            value,
+            word: round(value),
+            case,
+        })
+        .collect()
+}
+
+/// Vectors for a format with `fraction` mantissa fraction bits and the given exponents
+fn vectors<W>(
+    fraction: u32,
+    exponents: RangeInclusive<i32>,
+    encode: fn(f64) -> Option<W>,
+) -> Vec<Vector<W>> {
+    let ulp = 2f64.powi(-(fraction as i32));
+    let (min, max) = (*exponents.start(), *exponents.end());
+    let scale = |exponent: i32| 2f64.powi(exponent);
+
+    let mut cases = vec![(0.0, Case::Zero)];
+    // Positive powers of two have mantissa 0.5, negative ones -1.0
+    cases.extend((min - 1..max).map(|k| (scale(k), Case::PowerOfTwo)));
+    cases.extend((min..=max).map(|k| (-scale(k), Case::PowerOfTwo)));
+    for exponent in exponents.clone() {
+        cases.push(((1.0 - ulp) * scale(exponent), Case::ExponentBoundary));
+        cases.push((-(0.5 + ulp) * scale(exponent), Case::ExponentBoundary));
+    }
+    cases.extend([
+        ((1.0 - ulp) * scale(max), Case::Extreme),
+        (-scale(max), Case::Extreme),
+        (0.5 * scale(min), Case::Extreme),
+        (-(0.5 + ulp) * scale(min), Case::Extreme),
+    ]);
+    for exponent in [min, -1, 0, 1, max] {
+        // Halfway above the smallest and below the largest mantissa, and their negatives
+        for mantissa in [0.5 + ulp / 2.0, 1.0 - ulp / 2.0] {
+            for sign in [1.0, -1.0] {
+                cases.push((sign * mantissa * scale(exponent), Case::RoundingTie));
+            }
+        }
+    }
+
+    cases
+        .into_iter()
+        .filter_map(|(value, case)| {
+            Some(Vector {
+                value,
+                word: encode(value)?,
+                case,
+            })
+        })
+        .collect()
+}
+
+/// How the values of a [`sweep`] are spaced between its bounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Spacing {
+    /// By equal steps
+    Linear,
+    /// By equal ratios; the bounds must have the same sign and neither may be zero
+    Logarithmic,
+}
+
+/// Values between two bounds and the words they encode to, see [`sweep`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sweep {
+    /// The format of the words
+    pub format: FormatTag,
+    /// Each value and its word, its 16-bit words most significant first in the low bits
+    pub points: Vec<(f64, u64)>,
+}
+
+impl Sweep {
+    /// The points as CSV: a `value,word` header, then a row per point with the word in hex
+    pub fn csv(&self) -> String {
+        let mut csv = String::from("value,word\n");
+        for &(value, word) in &self.points {
+            let _ = writeln!(csv, "{value},0x{word:0digits$X}", digits = self.digits());
+        }
+        csv
+    }
+
+    /// The words for Verilog's `$readmemh`, one per line, each commented with its value
+    pub fn readmemh(&self) -> String {
+        let mut hex = String::new();
+        for &(value, word) in &self.points {
+            let _ = writeln!(hex, "{word:0digits$X} // {value}", digits = self.digits());
+        }
+        hex
+    }
+
+    /// The words as a VHDL constant array called `name`, of a type called `name_t`, each
+    /// commented with its value
+    pub fn vhdl(&self, name: &str) -> String {
+        let (bits, digits) = (16 * self.format.words(), self.digits());
+        let mut vhdl = String::new();
+        let _ = writeln!(
+            vhdl,
+            "type {name}_t is array (0 to {}) of std_logic_vector({} downto 0);",
+            self.points.len() as isize - 1,
+            bits - 1
+        );
+        let _ = writeln!(vhdl, "constant {name} : {name}_t := (");
+        for (index, &(value, word)) in self.points.iter().enumerate() {
+            let separator = if index + 1 < self.points.len() {
+                ","
+            } else {
+                ""
+            };
+            let _ = writeln!(vhdl, "    x\"{word:0digits$X}\"{separator} -- {value}");
+        }
+        vhdl.push_str(");\n");
+        vhdl
+    }
+
+    /// Number of hex digits of a word
+    fn digits(&self) -> usize {
+        4 * self.format.words()
+    }
+}
+
+/// `count` values from `start` to `end`, both included, spaced as `spacing` says and encoded
+/// in `format`, rounding floats to the nearest normalized word
+///
+/// One value is just `start`. Values the format cannot hold are an error, rather than
+/// saturating.
+///
+/// # Panics
+///
+/// Panics if the spacing is [`Spacing::Logarithmic`] and the bounds have different signs or
+/// either is zero.
+pub fn sweep(
+    start: f64,
+    end: f64,
+    count: usize,
+    spacing: Spacing,
+    format: FormatTag,
+) -> Result<Sweep, Mil1750Error> {
+    if spacing == Spacing::Logarithmic {
+        assert!(
+            start * end > 0.0,
+            "a logarithmic sweep needs bounds of the same sign, not {start} and {end}"
+        );
+    }
+    let steps = count.saturating_sub(1).max(1) as f64;
+    let points = (0..count)
+        .map(|index| {
+            let fraction = index as f64 / steps;
+            // The last value is `end` exactly, whatever the rounding of the steps
+            let value = match spacing {
+                _ if index > 0 && index + 1 == count => end,
+                Spacing::Linear => start + (end - start) * fraction,
+                Spacing::Logarithmic => start * (end / start).powf(fraction),
+            };
+            Ok((value, format.encode(value)?))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Sweep { format, points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors_round_trip() {
+        fn check<W: Copy>(vectors: &[Vector<W>], decode: fn(W) -> f64, ulp: fn(W) -> f64) {
+            for vector in vectors {
+                let decoded = decode(vector.word);
+                if vector.case == Case::RoundingTie {
+                    assert!(decoded.abs() > vector.value.abs(), "{}", vector.value);
+                    assert!((decoded - vector.value).abs() <= ulp(vector.word) / 2.0);
+                } else {
+                    assert_eq!(decoded, vector.value);
+                }
+            }
+        }
+        check(&vectors16(), arith::value16, arith::ulp16);
+        check(&vectors32(), arith::value32, arith::ulp32);
+        check(&vectors48(), arith::value48, arith::ulp48);
+    }
+
+    #[test]
+    fn test_adversarial() {
+        // The inputs either side of a tie must round to the word on their side of it
+        fn check<W: Copy>(vectors: &[Vector<W>], decode: fn(W) -> f64) {
+            for (index, tie) in vectors.iter().enumerate() {
+                if tie.case != Case::RoundingTie {
+                    continue;
+                }
+                let [below, above] = [&vectors[index + 1], &vectors[index + 2]];
+                assert_eq!((below.case, above.case), (Case::NearTie, Case::NearTie));
+                if decode(below.word) == decode(above.word) {
+                    // Both saturate, beyond the largest word
+                    continue;
+                }
+                assert!(decode(below.word) < tie.value, "{}", below.value);
+                assert!(decode(above.word) > tie.value, "{}", above.value);
+            }
+        }
+        let vectors = adversarial16();
+        check(&vectors, arith::value16);
+        check(&adversarial32(), arith::value32);
+        check(&adversarial48(), arith::value48);
+
+        // Just above 2^30, where the 16-bit exponent changes
+        let above = vectors
+            .iter()
+            .find(|v| v.value == f64::from(2f32.powi(30).next_up()));
+        assert_eq!(
+            above.map(|v| (v.word, v.case)),
+            Some((0x401F, Case::ExponentTransition))
+        );
+        let subnormals = vectors.iter().filter(|v| v.case == Case::Subnormal);
+        assert!(subnormals.map(|v| v.word).all(|word| word == 0));
+        let extremes = adversarial48();
+        let beyond = extremes.iter().filter(|v| v.case == Case::OutOfRange);
+        let words: Vec<_> = beyond.map(|v| v.word).collect();
+        assert_eq!(
+            words,
+            [0x7FFFFF7FFFFF, 0x7FFFFF7FFFFF, 0x8000007F0000, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_vectors16() {
+        let vectors = vectors16();
+        let find = |value: f64| vectors.iter().find(|v| v.value == value).map(|v| v.word);
+        assert_eq!(find(1.0), Some(0x4001));
+        assert_eq!(find(-1.0), Some(0x8000));
+        assert_eq!(find(0.0), Some(0));
+        assert_eq!(find((1.0 - 2f64.powi(-9)) * 2f64.powi(31)), Some(0x7FDF));
+        // The tie below the top of the largest exponent would round out of range
+        assert_eq!(find((1.0 - 2f64.powi(-10)) * 2f64.powi(31)), None);
+        assert!(vectors.iter().all(|v| v.value.abs() <= 2f64.powi(31)));
+        assert!(vectors.iter().any(|v| v.case == Case::RoundingTie));
+    }
+
+    #[test]
+    fn test_sweep() {
+        let table = sweep(-1.0, 1.0, 5, Spacing::Linear, FormatTag::F16).unwrap();
+        let values: Vec<_> = table.points.iter().map(|&(value, _)| value).collect();
+        assert_eq!(values, [-1.0, -0.5, 0.0, 0.5, 1.0]);
+        assert_eq!(table.points[0].1, 0x8000);
+        assert_eq!(
+            table.csv(),
+            "value,word\n-1,0x8000\n-0.5,0x803F\n0,0x0000\n0.5,0x4000\n1,0x4001\n"
+        );
+        assert_eq!(
+            sweep(0.5, 0.5, 1, Spacing::Linear, FormatTag::F48)
+                .unwrap()
+                .vhdl("v"),
+            "type v_t is array (0 to 0) of std_logic_vector(47 downto 0);\n\
+             constant v : v_t := (\n    x\"400000000000\" -- 0.5\n);\n"
+        );
+
+        let table = sweep(0.001, 1000.0, 7, Spacing::Logarithmic, FormatTag::F48).unwrap();
+        assert_eq!(table.points[6].0, 1000.0);
+        assert!((table.points[3].0 - 1.0).abs() < 1e-12);
+        assert_eq!(
+            sweep(0.0, 2.0, 3, Spacing::Linear, FormatTag::Fixed { bscale: 0 }),
+            Err(Mil1750Error::OutOfRange {
+                value: 1.0,
+                target: crate::TargetFormat::Fixed16
+            })
+        );
+        assert!(sweep(1.0, 2.0, 0, Spacing::Linear, FormatTag::F32)
+            .unwrap()
+            .readmemh()
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "same sign")]
+    fn test_sweep_through_zero() {
+        let _ = sweep(-1.0, 1.0, 3, Spacing::Logarithmic, FormatTag::F32);
+    }
+}
@@ -0,0 +1,1160 @@
+//! # Wrapper types
+//! Typed MIL-1750A words.
+//!
+//! Each type stores its word as big-endian bytes, exactly as it appears in memory dumps and on
+//! the bus, and has an alignment of 1, so buffers can be reinterpreted as slices of words
+//! (see the `bytemuck` feature).
+//!
+//! Equality, ordering and hashing follow the numeric value of the word, so non-normalized
+//! encodings of the same value and zeros with any exponent compare equal, and negative values
+//! sort below positive ones. Compare [`to_bits`](Mil32::to_bits) to tell encodings apart.
+//!
+//! The types parse from strings with [`str::parse`]: decimal numbers such as `"25.63"` are
+//! encoded, while `"0x66851F05"` and `"0b0110_0110_1000_0101"` are taken as raw words.
+//! Underscores may separate the digits of raw words.
+//!
+//! They also convert with [`TryFrom`] from slices of exactly one word's bytes or 16-bit words,
+//! big-endian and most significant word first, or in another layout with `from_byte_slice` and
+//! `from_word_slice`:
+//!
+//! ```
+//! use mil1750a_converter::{ByteOrder, Mil1750Error, Mil48, WordOrder};
+//!
+//! let buffer: [u8; 7] = [0x40, 0x00, 0x00, 0x01, 0x00, 0x00, 0xFF];
+//! assert_eq!(Mil48::try_from(&buffer[0..6]).unwrap().to_bits(), 0x400000010000);
+//! assert!(matches!(
+//!     Mil48::try_from(&buffer[0..5]),
+//!     Err(Mil1750Error::WrongLength { len: 5, expected: 6, .. })
+//! ));
+//! let swapped = [0x0000, 0x0001, 0x4000];
+//! let word = Mil48::from_word_slice(&swapped, WordOrder::LsWordFirst).unwrap();
+//! assert_eq!(word.to_bits(), 0x400000010000);
+//! let little = [0x00, 0x40, 0x01, 0x00, 0x00, 0x00];
+//! let word = Mil48::from_byte_slice(&little, ByteOrder::LittleEndian, WordOrder::MsWordFirst);
+//! assert_eq!(word.unwrap().to_bits(), 0x400000010000);
+//! ```
+
+use crate::{arith, ByteOrder, EncodeMode, Mil1750Error, ParseError, TargetFormat, WordOrder};
+use crate::{extended, single};
+#[cfg(feature = "half")]
+use half::f16;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+use std::str::FromStr;
+
+/// A 16-bit MIL-1750A word
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "half")] {
+/// use half::f16;
+/// use mil1750a_converter::Mil16;
+/// let word = Mil16::from_f16(f16::from_f32(12.4));
+/// assert_eq!(word.to_bits(), 0x6344);
+/// assert_eq!(word.to_f16(), f16::from_f32(12.40625));
+/// # }
+/// ```
+#[derive(Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct Mil16([u8; 2]);
+
+/// A 32-bit MIL-1750A word
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::Mil32;
+/// let word = Mil32::from_f32(5.234);
+/// assert_eq!(word.to_bits(), 0x53BE7703);
+/// assert_eq!(word.to_be_bytes(), [0x53, 0xBE, 0x77, 0x03]);
+/// ```
+#[derive(Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct Mil32([u8; 4]);
+
+/// A 48-bit MIL-1750A word
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::Mil48;
+/// let word = Mil48::from_f64(105.639485637361);
+/// assert_eq!(word.to_bits(), 0x69A3B50754AB);
+/// assert_eq!(word.to_f64(), 105.63948563742451);
+/// ```
+#[derive(Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct Mil48([u8; 6]);
+
+/// A value from a lenient decoder, with whether its word was normalized
+///
+/// Lenient decoders accept every word and return the value a 1750A processor computes from it,
+/// `mantissa * 2^exponent` with both read as two's complement, however the word was formed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Lenient<T> {
+    /// The value of the word
+    pub value: T,
+    /// `false` if the mantissa was not normalized, or was zero with a non-zero exponent
+    pub normalized: bool,
+}
+
+impl<T> Lenient<T> {
+    pub(crate) fn new(value: T, form: arith::Form) -> Self {
+        Lenient {
+            value,
+            normalized: matches!(form, arith::Form::Normalized | arith::Form::Zero),
+        }
+    }
+
+    /// The value with the word it was decoded from
+    pub fn with_raw<B>(self, raw: B) -> Decoded<B, T> {
+        Decoded {
+            raw,
+            value: self.value,
+            valid: self.normalized,
+        }
+    }
+}
+
+/// A decoded value together with the word it came from
+///
+/// Once a value has been decoded its word cannot be recovered from it, since unnormalized words
+/// and zeros with any exponent share values with other words, so reports that need the original
+/// bits next to the engineering value keep this instead of the bare value.
+///
+/// # Examples
+///
+/// ```
+/// use mil1750a_converter::{single, Decoded};
+/// assert_eq!(
+///     single::decode_traced(0x20000002),
+///     Decoded { raw: 0x20000002, value: 1.0, valid: false }
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Decoded<B, T> {
+    /// The word as it was read
+    pub raw: B,
+    /// The value of the word
+    pub value: T,
+    /// `false` if the word is one a strict decoder rejects
+    pub valid: bool,
+}
+
+impl Mil16 {
+    /// Wrap a raw 16-bit word
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits.to_be_bytes())
+    }
+
+    /// The raw 16-bit word
+    pub const fn to_bits(self) -> u16 {
+        u16::from_be_bytes(self.0)
+    }
+
+    /// Wrap a word from its big-endian bytes
+    pub const fn from_be_bytes(bytes: [u8; 2]) -> Self {
+        Self(bytes)
+    }
+
+    /// The word as big-endian bytes
+    pub const fn to_be_bytes(self) -> [u8; 2] {
+        self.0
+    }
+
+    /// The 10-bit two's-complement mantissa, worth `mantissa * 2^(exponent - 9)`
+    pub const fn mantissa(self) -> i16 {
+        (self.to_bits() as i16) >> 6
+    }
+
+    /// The 6-bit two's-complement exponent
+    pub const fn exponent(self) -> i8 {
+        ((self.to_bits() as i8) << 2) >> 2
+    }
+
+    /// The word with its mantissa replaced, normalized or not, rejecting mantissas outside
+    /// -512..=511
+    pub const fn with_mantissa(self, mantissa: i16) -> Result<Self, Mil1750Error> {
+        if mantissa < -(1 << 9) || mantissa >= 1 << 9 {
+            return Err(Mil1750Error::MantissaTooWide {
+                mantissa: mantissa as i64,
+                target: TargetFormat::Float16,
+            });
+        }
+        Ok(Self::from_bits(
+            ((mantissa as u16) << 6) | (self.to_bits() & 0x3F),
+        ))
+    }
+
+    /// The word with its exponent replaced, rejecting exponents outside -32..=31
+    pub const fn with_exponent(self, exponent: i8) -> Result<Self, Mil1750Error> {
+        if exponent < -32 || exponent > 31 {
+            return Err(Mil1750Error::ExponentTooWide {
+                exponent,
+                target: TargetFormat::Float16,
+            });
+        }
+        Ok(Self::from_bits(
+            (self.to_bits() & !0x3F) | (exponent as u16 & 0x3F),
+        ))
+    }
+
+    /// The canonical encoding of the word's value: normalized as far as the exponent allows,
+    /// with zero as all-zero bits
+    pub fn canonical(self) -> Self {
+        Self::from_bits(arith::canonical16(self.to_bits()))
+    }
+
+    /// Encode a value, rounding to the nearest normalized word and saturating, see
+    /// [`half::encode_with`](crate::half::encode_with)
+    #[cfg(feature = "half")]
+    pub fn from_f16(value: f16) -> Self {
+        Self::from_f32(value.into())
+    }
+
+    /// Decode the word as the standard defines it, rounded to half precision
+    #[cfg(feature = "half")]
+    pub fn to_f16(self) -> f16 {
+        f16::from_f32(self.to_f32())
+    }
+
+    /// Encode a value, rejecting NaN and infinities
+    ///
+    /// Every finite `f16` is within the range of the 16-bit format.
+    #[cfg(feature = "half")]
+    pub fn try_from_f16(value: f16) -> Result<Self, Mil1750Error> {
+        check_range(value.into(), TargetFormat::Float16, 9, -32, 31)?;
+        Ok(Self::from_f16(value))
+    }
+
+    /// Encode an `f32`, see [`half::encode_f32`](crate::half::encode_f32)
+    pub fn from_f32(value: f32) -> Self {
+        Self::from_bits(crate::half::encode_f32(value))
+    }
+
+    /// Decode the word to an `f32`, exactly as its [ordering](Ord) compares it
+    pub fn to_f32(self) -> f32 {
+        arith::value16(self.to_bits()) as f32
+    }
+
+    /// Encode an `f32`, rejecting NaN, infinities and values out of range
+    pub fn try_from_f32(value: f32) -> Result<Self, Mil1750Error> {
+        check_range(value.into(), TargetFormat::Float16, 9, -32, 31)?;
+        Ok(Self::from_f32(value))
+    }
+}
+
+impl Mil32 {
+    /// Wrap a raw 32-bit word
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits.to_be_bytes())
+    }
+
+    /// The raw 32-bit word
+    pub const fn to_bits(self) -> u32 {
+        u32::from_be_bytes(self.0)
+    }
+
+    /// Wrap a word from its big-endian bytes
+    pub const fn from_be_bytes(bytes: [u8; 4]) -> Self {
+        Self(bytes)
+    }
+
+    /// The word as big-endian bytes
+    pub const fn to_be_bytes(self) -> [u8; 4] {
+        self.0
+    }
+
+    /// The 24-bit two's-complement mantissa, worth `mantissa * 2^(exponent - 23)`
+    pub const fn mantissa(self) -> i32 {
+        (self.to_bits() as i32) >> 8
+    }
+
+    /// The exponent
+    pub const fn exponent(self) -> i8 {
+        self.to_bits() as u8 as i8
+    }
+
+    /// The word with its mantissa replaced, normalized or not, rejecting mantissas that do not
+    /// fit in 24 bits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mil1750a_converter::Mil32;
+    /// let one = Mil32::from_bits(0x40000001);
+    /// assert_eq!((one.mantissa(), one.exponent()), (0x400000, 1));
+    /// assert_eq!(one.with_exponent(3).to_bits(), 0x40000003);
+    /// assert_eq!(one.with_mantissa(-0x800000).unwrap().to_bits(), 0x80000001);
+    /// assert!(one.with_mantissa(0x800000).is_err());
+    /// ```
+    pub const fn with_mantissa(self, mantissa: i32) -> Result<Self, Mil1750Error> {
+        if mantissa < -(1 << 23) || mantissa >= 1 << 23 {
+            return Err(Mil1750Error::MantissaTooWide {
+                mantissa: mantissa as i64,
+                target: TargetFormat::Float32,
+            });
+        }
+        Ok(Self::from_bits(
+            ((mantissa as u32) << 8) | (self.to_bits() & 0xFF),
+        ))
+    }
+
+    /// The word with its exponent replaced
+    pub const fn with_exponent(self, exponent: i8) -> Self {
+        Self::from_bits((self.to_bits() & !0xFF) | exponent as u8 as u32)
+    }
+
+    /// The canonical encoding of the word's value: normalized as far as the exponent allows,
+    /// with zero as all-zero bits
+    pub fn canonical(self) -> Self {
+        Self::from_bits(arith::canonical32(self.to_bits()))
+    }
+
+    /// Encode a value, rounding to the nearest normalized word and saturating, see
+    /// [`single::encode_with`]
+    pub fn from_f32(value: f32) -> Self {
+        Self::from_bits(single::encode_with(value, EncodeMode::TwosComplement))
+    }
+
+    /// Decode the word as the standard defines it, exactly as its [ordering](Ord) compares it
+    pub fn to_f32(self) -> f32 {
+        arith::value32(self.to_bits()) as f32
+    }
+
+    /// Encode a value, rejecting NaN, infinities and magnitudes outside the format's range
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mil1750a_converter::{Mil1750Error, Mil32, TargetFormat};
+    /// assert_eq!(Mil32::try_from_f32(1.0).unwrap().to_bits(), 0x40000001);
+    /// assert_eq!(
+    ///     Mil32::try_from_f32(f32::MAX),
+    ///     Err(Mil1750Error::OutOfRange {
+    ///         value: f32::MAX.into(),
+    ///         target: TargetFormat::Float32
+    ///     })
+    /// );
+    /// ```
+    pub fn try_from_f32(value: f32) -> Result<Self, Mil1750Error> {
+        check_range(value.into(), TargetFormat::Float32, 23, -128, 127)?;
+        Ok(Self::from_f32(value))
+    }
+}
+
+impl Mil48 {
+    /// Wrap a raw 48-bit word, ignoring the upper 16 bits of `bits`
+    pub const fn from_bits(bits: u64) -> Self {
+        let bytes = bits.to_be_bytes();
+        Self([bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]])
+    }
+
+    /// The raw 48-bit word
+    pub const fn to_bits(self) -> u64 {
+        let [a, b, c, d, e, f] = self.0;
+        u64::from_be_bytes([0, 0, a, b, c, d, e, f])
+    }
+
+    /// Wrap a word from its big-endian bytes
+    pub const fn from_be_bytes(bytes: [u8; 6]) -> Self {
+        Self(bytes)
+    }
+
+    /// The word as big-endian bytes
+    pub const fn to_be_bytes(self) -> [u8; 6] {
+        self.0
+    }
+
+    /// The 40-bit two's-complement mantissa, worth `mantissa * 2^(exponent - 39)`, joined from
+    /// the high 24 bits of the word and its low 16
+    pub const fn mantissa(self) -> i64 {
+        let bits = self.to_bits();
+        let mantissa = ((bits >> 24) << 16) | (bits & 0xFFFF);
+        ((mantissa << 24) as i64) >> 24
+    }
+
+    /// The exponent, the third byte of the word
+    pub const fn exponent(self) -> i8 {
+        (self.to_bits() >> 16) as u8 as i8
+    }
+
+    /// The word with its mantissa replaced, normalized or not, rejecting mantissas that do not
+    /// fit in 40 bits
+    pub const fn with_mantissa(self, mantissa: i64) -> Result<Self, Mil1750Error> {
+        if mantissa < -(1 << 39) || mantissa >= 1 << 39 {
+            return Err(Mil1750Error::MantissaTooWide {
+                mantissa,
+                target: TargetFormat::Float48,
+            });
+        }
+        let mantissa = mantissa as u64 & 0xFF_FFFF_FFFF;
+        Ok(Self::from_bits(
+            ((mantissa >> 16) << 24) | (self.to_bits() & 0xFF_0000) | (mantissa & 0xFFFF),
+        ))
+    }
+
+    /// The word with its exponent replaced
+    pub const fn with_exponent(self, exponent: i8) -> Self {
+        Self::from_bits((self.to_bits() & !0xFF_0000) | ((exponent as u8 as u64) << 16))
+    }
+
+    /// The canonical encoding of the word's value: normalized as far as the exponent allows,
+    /// with zero as all-zero bits
+    pub fn canonical(self) -> Self {
+        Self::from_bits(arith::canonical48(self.to_bits()))
+    }
+
+    /// Encode a value, rounding to the nearest normalized word and saturating, see
+    /// [`extended::encode_with`]
+    pub fn from_f64(value: f64) -> Self {
+        Self::from_bits(extended::encode_with(value, EncodeMode::TwosComplement))
+    }
+
+    /// Decode the word as the standard defines it, exactly as its [ordering](Ord) compares it
+    pub fn to_f64(self) -> f64 {
+        arith::value48(self.to_bits())
+    }
+
+    /// Wrap a raw 48-bit word, rejecting words with any of the upper 16 bits set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mil1750a_converter::Mil48;
+    /// assert!(Mil48::try_from_bits(0x69A3B50754AB).is_ok());
+    /// assert!(Mil48::try_from_bits(0x1_0000_0000_0000).is_err());
+    /// ```
+    pub const fn try_from_bits(bits: u64) -> Result<Self, Mil1750Error> {
+        if bits >> 48 != 0 {
+            return Err(Mil1750Error::WordTooWide {
+                word: bits,
+                target: TargetFormat::Float48,
+            });
+        }
+        Ok(Self::from_bits(bits))
+    }
+
+    /// Encode a value, rejecting NaN, infinities and magnitudes outside the format's range
+    pub fn try_from_f64(value: f64) -> Result<Self, Mil1750Error> {
+        check_range(value, TargetFormat::Float48, 39, -128, 127)?;
+        Ok(Self::from_f64(value))
+    }
+}
+
+/// Check that `value` encodes with an exponent in `min_exponent..=max_exponent`, given a
+/// mantissa with `fraction_bits` bits after the sign, mirroring the encoders' exponent choice.
+fn check_range(
+    value: f64,
+    target: TargetFormat,
+    fraction_bits: i32,
+    min_exponent: i32,
+    max_exponent: i32,
+) -> Result<(), Mil1750Error> {
+    if !value.is_finite() {
+        return Err(Mil1750Error::NotFinite { value, target });
+    }
+    if value == 0.0 {
+        return Ok(());
+    }
+
+    let mut exponent = value.abs().log2().ceil() as i32;
+    let mantissa = (value * 2f64.powi(fraction_bits - exponent)).round();
+    if mantissa == 2f64.powi(fraction_bits) {
+        exponent += 1;
+    }
+
+    if (min_exponent..=max_exponent).contains(&exponent) {
+        Ok(())
+    } else {
+        Err(Mil1750Error::OutOfRange { value, target })
+    }
+}
+
+/// The 16-bit words of a slice of exactly `N` of them, each in `byte_order`
+fn slice_words<const N: usize>(
+    bytes: &[u8],
+    byte_order: ByteOrder,
+    target: TargetFormat,
+) -> Result<[u16; N], Mil1750Error> {
+    if bytes.len() != 2 * N {
+        return Err(wrong_length(bytes.len(), 2 * N, target));
+    }
+    let mut words = [0; N];
+    for (word, pair) in words.iter_mut().zip(bytes.chunks_exact(2)) {
+        let pair = [pair[0], pair[1]];
+        *word = match byte_order {
+            ByteOrder::BigEndian => u16::from_be_bytes(pair),
+            ByteOrder::LittleEndian => u16::from_le_bytes(pair),
+        };
+    }
+    Ok(words)
+}
+
+/// The word made of 16-bit words in `order`
+fn join_words<const N: usize>(mut words: [u16; N], order: WordOrder) -> u64 {
+    if order == WordOrder::LsWordFirst {
+        words.reverse();
+    }
+    words
+        .iter()
+        .fold(0, |bits, &word| (bits << 16) | u64::from(word))
+}
+
+fn wrong_length(len: usize, expected: usize, target: TargetFormat) -> Mil1750Error {
+    Mil1750Error::WrongLength {
+        len,
+        expected,
+        target,
+    }
+}
+
+/// Implement construction from slices of bytes and of 16-bit words on a wrapper type of
+/// `$words` 16-bit words
+macro_rules! impl_from_slice {
+    ($ty:ident, $bits:ty, $words:literal, $target:expr) => {
+        impl $ty {
+            /// Read a word from a slice of exactly its bytes, the bytes of each 16-bit word in
+            /// `byte_order` and the 16-bit words in `word_order`
+            pub fn from_byte_slice(
+                bytes: &[u8],
+                byte_order: ByteOrder,
+                word_order: WordOrder,
+            ) -> Result<Self, Mil1750Error> {
+                let words = slice_words::<$words>(bytes, byte_order, $target)?;
+                Ok(Self::from_bits(join_words(words, word_order) as $bits))
+            }
+
+            /// Read a word from a slice of exactly its 16-bit words, in `word_order`
+            pub fn from_word_slice(
+                words: &[u16],
+                word_order: WordOrder,
+            ) -> Result<Self, Mil1750Error> {
+                let words: [u16; $words] = words
+                    .try_into()
+                    .map_err(|_| wrong_length(words.len(), $words, $target))?;
+                Ok(Self::from_bits(join_words(words, word_order) as $bits))
+            }
+        }
+
+        impl TryFrom<&[u8]> for $ty {
+            type Error = Mil1750Error;
+
+            /// Read a word from exactly its big-endian bytes, most significant word first
+            fn try_from(bytes: &[u8]) -> Result<Self, Mil1750Error> {
+                Self::from_byte_slice(bytes, ByteOrder::BigEndian, WordOrder::MsWordFirst)
+            }
+        }
+
+        impl TryFrom<&[u16]> for $ty {
+            type Error = Mil1750Error;
+
+            /// Read a word from exactly its 16-bit words, most significant first
+            fn try_from(words: &[u16]) -> Result<Self, Mil1750Error> {
+                Self::from_word_slice(words, WordOrder::MsWordFirst)
+            }
+        }
+    };
+}
+
+impl_from_slice!(Mil16, u16, 1, TargetFormat::Float16);
+impl_from_slice!(Mil32, u32, 2, TargetFormat::Float32);
+impl_from_slice!(Mil48, u64, 3, TargetFormat::Float48);
+
+impl TryFrom<u64> for Mil48 {
+    type Error = Mil1750Error;
+
+    fn try_from(bits: u64) -> Result<Self, Mil1750Error> {
+        Self::try_from_bits(bits)
+    }
+}
+
+/// Implement value-based equality, hashing and ordering on a wrapper type from its canonical
+/// encoding and exact value
+macro_rules! impl_value_cmp {
+    ($ty:ident, $value:path) => {
+        impl PartialEq for $ty {
+            fn eq(&self, other: &$ty) -> bool {
+                self.canonical().to_bits() == other.canonical().to_bits()
+            }
+        }
+
+        impl Eq for $ty {}
+
+        impl Hash for $ty {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.canonical().to_bits().hash(state);
+            }
+        }
+
+        impl PartialOrd for $ty {
+            fn partial_cmp(&self, other: &$ty) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $ty {
+            fn cmp(&self, other: &$ty) -> Ordering {
+                // The exact values are never NaN
+                $value(self.to_bits()).total_cmp(&$value(other.to_bits()))
+            }
+        }
+    };
+}
+
+impl_value_cmp!(Mil16, arith::value16);
+impl_value_cmp!(Mil32, arith::value32);
+impl_value_cmp!(Mil48, arith::value48);
+
+/// Parse the digits of a raw word after its `0x` or `0b` prefix, which starts at `offset`
+fn parse_word(digits: &str, offset: usize, radix: u32, bits: u32) -> Result<u64, ParseError> {
+    let mut word = 0u64;
+    let mut any = false;
+    for (index, digit) in digits.char_indices() {
+        if digit == '_' {
+            continue;
+        }
+        let value = digit.to_digit(radix).ok_or(ParseError::InvalidDigit {
+            digit,
+            index: offset + index,
+            radix,
+        })?;
+        word = word
+            .checked_mul(radix as u64)
+            .map(|word| word | value as u64)
+            .filter(|word| word >> bits == 0)
+            .ok_or(ParseError::TooWide { bits })?;
+        any = true;
+    }
+    if any {
+        Ok(word)
+    } else {
+        Err(ParseError::Empty)
+    }
+}
+
+/// Split a raw word into its digits and radix, or `None` for a decimal value
+fn word_notation(s: &str) -> Option<(&str, u32)> {
+    let prefix = s.get(..2)?;
+    match prefix {
+        "0x" | "0X" => Some((&s[2..], 16)),
+        "0b" | "0B" => Some((&s[2..], 2)),
+        _ => None,
+    }
+}
+
+impl FromStr for Mil16 {
+    type Err = ParseError;
+
+    /// Parse a decimal value, encoded from double precision, or a raw word
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        if let Some((digits, radix)) = word_notation(s) {
+            return Ok(Mil16::from_bits(parse_word(digits, 2, radix, 16)? as u16));
+        }
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let value: f64 = s.parse()?;
+        check_range(value, TargetFormat::Float16, 9, -32, 31)?;
+        Ok(Mil16::from_bits(arith::round16(value)))
+    }
+}
+
+impl FromStr for Mil32 {
+    type Err = ParseError;
+
+    /// Parse a decimal value, encoded from double precision, or a raw word
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        if let Some((digits, radix)) = word_notation(s) {
+            return Ok(Mil32::from_bits(parse_word(digits, 2, radix, 32)? as u32));
+        }
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        // Rounded once from the parsed value, as rounding to `f32` first could round it twice
+        let value: f64 = s.parse()?;
+        check_range(value, TargetFormat::Float32, 23, -128, 127)?;
+        Ok(Mil32::from_bits(arith::round32(value)))
+    }
+}
+
+impl FromStr for Mil48 {
+    type Err = ParseError;
+
+    /// Parse a decimal value, encoded from double precision, or a raw word
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        if let Some((digits, radix)) = word_notation(s) {
+            return Ok(Mil48::from_bits(parse_word(digits, 2, radix, 48)?));
+        }
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        Ok(Mil48::try_from_f64(s.parse()?)?)
+    }
+}
+
+impl fmt::Debug for Mil16 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Mil16({:#06X})", self.to_bits())
+    }
+}
+
+impl fmt::Debug for Mil32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Mil32({:#010X})", self.to_bits())
+    }
+}
+
+impl fmt::Debug for Mil48 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Mil48({:#014X})", self.to_bits())
+    }
+}
+
+#[cfg(feature = "half")]
+impl From<f16> for Mil16 {
+    fn from(value: f16) -> Self {
+        Self::from_f16(value)
+    }
+}
+
+#[cfg(feature = "half")]
+impl From<Mil16> for f16 {
+    fn from(word: Mil16) -> Self {
+        word.to_f16()
+    }
+}
+
+impl From<f32> for Mil32 {
+    fn from(value: f32) -> Self {
+        Self::from_f32(value)
+    }
+}
+
+impl From<Mil32> for f32 {
+    fn from(word: Mil32) -> Self {
+        word.to_f32()
+    }
+}
+
+impl From<f64> for Mil48 {
+    fn from(value: f64) -> Self {
+        Self::from_f64(value)
+    }
+}
+
+impl From<Mil48> for f64 {
+    fn from(word: Mil48) -> Self {
+        word.to_f64()
+    }
+}
+
+/// Implement a binary operator and its assigning form on a wrapper type through its [`arith`]
+/// function
+macro_rules! impl_binary_op {
+    ($ty:ident, $trait:ident, $method:ident, $assign:ident, $assign_method:ident, $engine:path) => {
+        impl $trait for $ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: $ty) -> $ty {
+                $ty::from_bits($engine(self.to_bits(), rhs.to_bits()))
+            }
+        }
+
+        impl $assign for $ty {
+            fn $assign_method(&mut self, rhs: $ty) {
+                *self = $trait::$method(*self, rhs);
+            }
+        }
+    };
+}
+
+impl_binary_op!(Mil32, Add, add, AddAssign, add_assign, arith::add32);
+impl_binary_op!(Mil32, Sub, sub, SubAssign, sub_assign, arith::sub32);
+impl_binary_op!(Mil32, Mul, mul, MulAssign, mul_assign, arith::mul32);
+impl_binary_op!(Mil32, Div, div, DivAssign, div_assign, arith::div32);
+impl_binary_op!(Mil32, Rem, rem, RemAssign, rem_assign, arith::rem32);
+impl_binary_op!(Mil48, Add, add, AddAssign, add_assign, arith::add48);
+impl_binary_op!(Mil48, Sub, sub, SubAssign, sub_assign, arith::sub48);
+impl_binary_op!(Mil48, Mul, mul, MulAssign, mul_assign, arith::mul48);
+impl_binary_op!(Mil48, Div, div, DivAssign, div_assign, arith::div48);
+impl_binary_op!(Mil48, Rem, rem, RemAssign, rem_assign, arith::rem48);
+
+/// Negation saturates for the most negative value, whose negation is not representable
+impl Neg for Mil32 {
+    type Output = Mil32;
+
+    fn neg(self) -> Mil32 {
+        Mil32::from_bits(arith::neg32(self.to_bits()))
+    }
+}
+
+/// Negation saturates for the most negative value, whose negation is not representable
+impl Neg for Mil48 {
+    type Output = Mil48;
+
+    fn neg(self) -> Mil48 {
+        Mil48::from_bits(arith::neg48(self.to_bits()))
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Mil16 {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "Mil16({=u16:#06X})", self.to_bits())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Mil32 {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "Mil32({=u32:#010X})", self.to_bits())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Mil48 {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "Mil48({=u64:#014X})", self.to_bits())
+    }
+}
+
+// SAFETY: each type is a `repr(transparent)` wrapper around a byte array, so every bit
+// pattern is valid, the all-zero pattern included, and there is no padding.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mil16 {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Mil16 {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mil32 {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Mil32 {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mil48 {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Mil48 {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_and_bytes() {
+        assert_eq!(Mil16::from_bits(0x6344).to_be_bytes(), [0x63, 0x44]);
+        assert_eq!(
+            Mil32::from_be_bytes([0x99, 0x7A, 0xE1, 0x05]).to_bits(),
+            0x997AE105
+        );
+        assert_eq!(
+            Mil48::from_bits(0xFFFF_69A3B50754AB).to_bits(),
+            0x69A3B50754AB
+        );
+        assert_eq!(
+            Mil48::from_bits(0x69A3B50754AB).to_be_bytes(),
+            [0x69, 0xA3, 0xB5, 0x07, 0x54, 0xAB]
+        );
+    }
+
+    #[test]
+    fn test_value_conversions() {
+        assert_eq!(Mil16::from_f32(25.63).to_bits(), 0x6685);
+        assert_eq!(f32::from(Mil32::from_bits(0x40000001)), 1.0);
+        assert_eq!(Mil48::from(std::f64::consts::PI).to_bits(), 0x6487ED025111);
+        // Values below 0.5 in magnitude have negative exponents
+        assert_eq!(Mil32::from_f32(0.25).to_f32(), 0.25);
+        assert_eq!((Mil32::from_f32(1.0) / Mil32::from_f32(4.0)).to_f32(), 0.25);
+        assert!((Mil48::from_f64(-0.1).to_f64() + 0.1).abs() < 1e-12);
+        assert_eq!(f64::from(Mil48::from_bits(0x800000FF0000)), -0.5);
+        assert_eq!(Mil16::from_f32(1.0).to_bits(), 0x4001);
+        assert_eq!(Mil16::from_f32(-1.0).to_bits(), 0x8000);
+        assert!(Mil16::from_f32(1.0) > Mil16::from_f32(0.5));
+        assert_eq!(Mil16::from_bits(0x403F).to_f32(), 0.25);
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_f16_conversions() {
+        assert_eq!(Mil16::from(f16::from_f32(25.63)).to_bits(), 0x6685);
+        assert_eq!(Mil16::from_f16(f16::ONE).to_bits(), 0x4001);
+        assert_eq!(Mil16::from_f16(f16::NEG_ONE).to_bits(), 0x8000);
+        assert!(Mil16::from_f16(f16::ONE) > Mil16::from_f16(f16::from_f32(0.5)));
+        assert_eq!(Mil16::from_bits(0x403F).to_f16(), f16::from_f32(0.25));
+        assert!(matches!(
+            Mil16::try_from_f16(f16::NAN),
+            Err(Mil1750Error::NotFinite { .. })
+        ));
+        assert_eq!(
+            Mil16::try_from_f16(f16::from_f32(12.4)).map(Mil16::to_bits),
+            Ok(0x6344)
+        );
+    }
+
+    #[test]
+    fn test_checked_encode() {
+        assert!(matches!(
+            Mil16::try_from_f32(f32::NAN),
+            Err(Mil1750Error::NotFinite { .. })
+        ));
+        assert_eq!(Mil16::try_from_f32(-25.63).unwrap().to_bits(), 0x9985);
+        assert!(Mil16::try_from_f32(2f32.powi(31)).is_err());
+        assert_eq!(Mil16::from_bits(0x6344).to_f32(), 12.40625);
+        assert_eq!(Mil32::try_from_f32(-25.63).unwrap().to_bits(), 0x997AE105);
+        assert!(Mil32::try_from_f32(f32::INFINITY).is_err());
+        assert!(Mil32::try_from_f32(1e-40).is_err());
+        assert!(Mil32::try_from_f32(-2f32.powi(127)).is_ok());
+        assert!(Mil48::try_from_f64(1e39).is_err());
+        assert_eq!(Mil48::try_from_f64(0.0).unwrap().to_bits(), 0);
+    }
+
+    #[test]
+    fn test_try_from_slices() {
+        let bytes: &[u8] = &[0x53, 0xBE, 0x77, 0x03];
+        assert_eq!(Mil32::try_from(bytes).unwrap().to_bits(), 0x53BE7703);
+        assert_eq!(
+            Mil32::from_byte_slice(bytes, ByteOrder::LittleEndian, WordOrder::LsWordFirst)
+                .unwrap()
+                .to_bits(),
+            0x0377BE53
+        );
+        assert_eq!(
+            Mil32::try_from(&[0x53BEu16, 0x7703][..]).unwrap().to_bits(),
+            0x53BE7703
+        );
+        assert_eq!(Mil16::try_from(&[0x6344u16][..]).unwrap().to_bits(), 0x6344);
+        assert_eq!(
+            Mil16::try_from(&bytes[..1]),
+            Err(Mil1750Error::WrongLength {
+                len: 1,
+                expected: 2,
+                target: TargetFormat::Float16
+            })
+        );
+        assert_eq!(
+            Mil32::try_from(&[0x4000u16][..]),
+            Err(Mil1750Error::WrongLength {
+                len: 1,
+                expected: 2,
+                target: TargetFormat::Float32
+            })
+        );
+        assert!(Mil48::try_from(&[0u16; 4][..]).is_err());
+    }
+
+    #[test]
+    fn test_try_from_bits() {
+        assert_eq!(
+            Mil48::try_from(0x6487ED025111).unwrap().to_bits(),
+            0x6487ED025111
+        );
+        assert_eq!(
+            Mil48::try_from(u64::MAX),
+            Err(Mil1750Error::WordTooWide {
+                word: u64::MAX,
+                target: TargetFormat::Float48
+            })
+        );
+    }
+
+    #[test]
+    fn test_fields() {
+        let word = Mil48::from_bits(0x69A3B50754AB);
+        assert_eq!((word.mantissa(), word.exponent()), (0x69A3B554AB, 7));
+        let negative = word.with_mantissa(-1).unwrap().with_exponent(-128);
+        assert_eq!(negative.to_bits(), 0xFFFFFF80FFFF);
+        assert_eq!((negative.mantissa(), negative.exponent()), (-1, -128));
+        assert_eq!(
+            word.with_mantissa(1 << 39),
+            Err(Mil1750Error::MantissaTooWide {
+                mantissa: 1 << 39,
+                target: TargetFormat::Float48
+            })
+        );
+
+        let word = Mil16::from_bits(0x6344);
+        assert_eq!((word.mantissa(), word.exponent()), (0x18D, 4));
+        let word = word
+            .with_mantissa(-512)
+            .unwrap()
+            .with_exponent(-32)
+            .unwrap();
+        assert_eq!(word.to_bits(), 0x8020);
+        assert_eq!((word.mantissa(), word.exponent()), (-512, -32));
+        assert!(word.with_mantissa(512).is_err());
+        assert_eq!(
+            word.with_exponent(32),
+            Err(Mil1750Error::ExponentTooWide {
+                exponent: 32,
+                target: TargetFormat::Float16
+            })
+        );
+        assert_eq!(Mil32::from_bits(0x800000FF).exponent(), -1);
+    }
+
+    #[test]
+    fn test_operators() {
+        let one = Mil32::from_f32(1.0);
+        let value = Mil32::from_f32(5.234);
+        assert_eq!((one + value).to_bits(), 0x63BE7703);
+        assert_eq!(value - value, Mil32::default());
+        assert_eq!(one * one, one);
+        assert_eq!((one / Mil32::from_f32(-1.0)).to_bits(), 0x80000000);
+        assert_eq!((value % one).to_bits(), 0x77CEE0FE);
+
+        let two = Mil48::from_f64(2.0);
+        assert_eq!((two * two).to_bits(), 0x400000030000);
+        assert_eq!((two / two + two - two % two).to_bits(), 0x600000020000);
+        assert_eq!((-two).to_bits(), 0x800000010000);
+
+        let mut total = Mil32::default();
+        for _ in 0..2 {
+            total += value;
+        }
+        total -= value;
+        total *= -one;
+        total /= one;
+        assert_eq!(total, -value);
+    }
+
+    #[test]
+    fn test_value_cmp() {
+        use std::collections::HashSet;
+
+        let half = Mil32::from_f32(0.5);
+        assert_eq!(Mil32::from_bits(0x20000001), half);
+        assert_eq!(Mil32::from_bits(0x0000007F), Mil32::default());
+        assert_ne!(Mil32::from_bits(0x20000001).to_bits(), half.to_bits());
+
+        let mut words = [5.234, -25.63, 0.5, -0.5, 0.0].map(Mil32::from_f32);
+        words.sort();
+        assert_eq!(words, [-25.63, -0.5, 0.0, 0.5, 5.234].map(Mil32::from_f32));
+        assert!(Mil48::from_f64(-1.0) < Mil48::from_f64(-0.5));
+        assert!(Mil16::from_bits(0xFFC0) < Mil16::default());
+
+        let set: HashSet<Mil32> = [
+            half,
+            Mil32::from_bits(0x20000001),
+            Mil32::from_bits(0x10000002),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("25.63".parse::<Mil32>().unwrap().to_bits(), 0x66851F05);
+        assert_eq!("0x66851F05".parse::<Mil32>().unwrap().to_bits(), 0x66851F05);
+        assert_eq!(
+            "0b0110_0110_1000_0101_0001_1111_0000_0101"
+                .parse::<Mil32>()
+                .unwrap()
+                .to_bits(),
+            0x66851F05
+        );
+        assert_eq!("12.4".parse::<Mil16>().unwrap().to_bits(), 0x6344);
+        assert_eq!("0X6344".parse::<Mil16>().unwrap().to_bits(), 0x6344);
+        // Just below halfway between two words, but rounded to the halfway point by an `f32`
+        assert_eq!(
+            "1.000000118".parse::<Mil32>().unwrap().to_bits(),
+            0x40000001
+        );
+        assert_eq!(
+            Mil32::from_f32("1.000000118".parse().unwrap()).to_bits(),
+            0x40000101
+        );
+        assert_eq!(
+            "1.0019531240686774".parse::<Mil16>().unwrap().to_bits(),
+            0x4001
+        );
+        assert_eq!(
+            Mil16::from_f32("1.0019531240686774".parse().unwrap()).to_bits(),
+            0x4041
+        );
+        assert_eq!(
+            "105.639485637361".parse::<Mil48>().unwrap().to_bits(),
+            0x69A3B50754AB
+        );
+        assert_eq!(
+            "0x69A3_B507_54AB".parse::<Mil48>().unwrap().to_bits(),
+            0x69A3B50754AB
+        );
+    }
+
+    #[test]
+    fn test_from_str_errors() {
+        assert_eq!("".parse::<Mil32>(), Err(ParseError::Empty));
+        assert_eq!("0x".parse::<Mil32>(), Err(ParseError::Empty));
+        assert_eq!(
+            "0x12G4".parse::<Mil16>(),
+            Err(ParseError::InvalidDigit {
+                digit: 'G',
+                index: 4,
+                radix: 16
+            })
+        );
+        assert_eq!(
+            "0x1_0000".parse::<Mil16>(),
+            Err(ParseError::TooWide { bits: 16 })
+        );
+        assert_eq!(
+            "0x1_0000_0000_0000_0000".parse::<Mil48>(),
+            Err(ParseError::TooWide { bits: 48 })
+        );
+        assert!(matches!(
+            "25,63".parse::<Mil32>(),
+            Err(ParseError::InvalidNumber(_))
+        ));
+        assert_eq!(
+            "inf".parse::<Mil48>(),
+            Err(ParseError::Value(Mil1750Error::NotFinite {
+                value: f64::INFINITY,
+                target: TargetFormat::Float48
+            }))
+        );
+        assert!(matches!(
+            "1e30".parse::<Mil16>(),
+            Err(ParseError::Value(Mil1750Error::OutOfRange { .. }))
+        ));
+        assert_eq!(
+            "1e40".parse::<Mil32>(),
+            Err(ParseError::Value(Mil1750Error::OutOfRange {
+                value: 1e40,
+                target: TargetFormat::Float32
+            }))
+        );
+        assert!(matches!(
+            "-inf".parse::<Mil32>(),
+            Err(ParseError::Value(Mil1750Error::NotFinite { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(format!("{:?}", Mil16::from_bits(0x6344)), "Mil16(0x6344)");
+        assert_eq!(format!("{:?}", Mil32::from_bits(0x1)), "Mil32(0x00000001)");
+        assert_eq!(
+            format!("{:?}", Mil48::from_bits(0x69A3B50754AB)),
+            "Mil48(0x69A3B50754AB)"
+        );
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_cast_slice() {
+        let buf: [u8; 9] = [0x00, 0x40, 0x00, 0x00, 0x01, 0x53, 0xBE, 0x77, 0x03];
+        let words: &[Mil32] = bytemuck::cast_slice(&buf[1..]);
+        assert_eq!(
+            words,
+            [Mil32::from_bits(0x40000001), Mil32::from_bits(0x53BE7703)]
+        );
+        let words: &[Mil48] = bytemuck::cast_slice(&buf[..6]);
+        assert_eq!(words[0].to_bits(), 0x004000000153);
+    }
+}
@@ -0,0 +1,230 @@
+//! # Exhaustive verification
+//! Check the 16-bit conversions against an oracle for every one of the 65,536 words, as
+//! evidence for certification reviews.
+//!
+//! [`verify_16bit_exhaustive`] decodes each word with
+//! [`half::decode_lenient_f32`](crate::half::decode_lenient_f32) and compares its value with the
+//! oracle's. It then encodes the oracle's value with
+//! [`EncodeMode::TwosComplement`](crate::EncodeMode::TwosComplement) and checks that the oracle
+//! reads the same value back from the new word, so an unnormalized word passes when it re-encodes
+//! to its normalized form. Values nearer zero than any normalized word, which only unnormalized
+//! words hold and which the encoder flushes to zero, are not re-encoded. [`reference16`] is a
+//! built-in oracle, computed from the bit fields alone.
+//!
+//! ```
+//! use mil1750a_converter::verify::{reference16, verify_16bit_exhaustive};
+//! use mil1750a_converter::{half, DecodeMode};
+//!
+//! let report = verify_16bit_exhaustive(reference16);
+//! assert!(report.passed(), "{report}");
+//! assert_eq!(report.checked(), 65536);
+//!
+//! // The legacy decoder reads the exponent as unsigned
+//! let legacy =
+//!     verify_16bit_exhaustive(|word| half::decode_f32_with(word, DecodeMode::Legacy).into());
+//! assert!(!legacy.passed());
+//! ```
+
+use crate::{half, EncodeMode};
+use std::fmt;
+
+/// The normalized 16-bit words nearest zero, -(0.5 + 2^-9) × 2^-32 and 0.5 × 2^-32
+const NEAREST_ZERO: [f64; 2] = [-1.1687006917782128e-10, 1.1641532182693481e-10];
+
+/// A word on which the conversions and the oracle disagree
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mismatch {
+    /// The word decodes to another value than the oracle's
+    Decode {
+        /// The word
+        word: u16,
+        /// The oracle's value of the word
+        expected: f64,
+        /// The decoded value
+        actual: f64,
+    },
+    /// The oracle's value of the word encodes to a word the oracle reads as another value
+    Reencode {
+        /// The word
+        word: u16,
+        /// The oracle's value of the word
+        value: f64,
+        /// The word the value encodes to
+        reencoded: u16,
+    },
+}
+
+impl Mismatch {
+    /// The word that mismatched
+    pub fn word(&self) -> u16 {
+        match *self {
+            Mismatch::Decode { word, .. } | Mismatch::Reencode { word, .. } => word,
+        }
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Mismatch::Decode {
+                word,
+                expected,
+                actual,
+            } => write!(f, "{word:#06x} decodes to {actual}, expected {expected}"),
+            Mismatch::Reencode {
+                word,
+                value,
+                reencoded,
+            } => write!(
+                f,
+                "{word:#06x} is {value}, which encodes to {reencoded:#06x}"
+            ),
+        }
+    }
+}
+
+/// The outcome of an exhaustive verification, mismatches in word order
+///
+/// [`Display`](fmt::Display) prints a summary line and one line per mismatch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    mismatches: Vec<Mismatch>,
+    checked: usize,
+    reencoded: usize,
+    unnormalized: usize,
+}
+
+impl Report {
+    /// Every mismatch found
+    pub fn mismatches(&self) -> &[Mismatch] {
+        &self.mismatches
+    }
+
+    /// Number of words decoded
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+
+    /// Number of words whose value was re-encoded
+    pub fn reencoded(&self) -> usize {
+        self.reencoded
+    }
+
+    /// Number of words the decoder reported as not normalized
+    pub fn unnormalized(&self) -> usize {
+        self.unnormalized
+    }
+
+    /// Whether no word mismatched
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} words decoded, {} re-encoded, {} unnormalized, {} mismatches",
+            if self.passed() { "PASS" } else { "FAIL" },
+            self.checked,
+            self.reencoded,
+            self.unnormalized,
+            self.mismatches.len(),
+        )?;
+        for mismatch in &self.mismatches {
+            writeln!(f, "{mismatch}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of a 16-bit word as the standard defines it, from its bit fields alone: the top
+/// ten bits as a two's-complement fraction times two to the power of the low six bits, also
+/// two's complement
+pub fn reference16(word: u16) -> f64 {
+    let mantissa = (word as i16 >> 6) as f64;
+    let exponent = ((word << 10) as i16 >> 10) as i32;
+    mantissa * 2f64.powi(exponent - 9)
+}
+
+/// Decode and re-encode every 16-bit word, comparing each value with `oracle`'s, see the
+/// [module documentation](self)
+pub fn verify_16bit_exhaustive(oracle: impl Fn(u16) -> f64) -> Report {
+    let mut report = Report::default();
+    for word in 0..=u16::MAX {
+        let expected = oracle(word);
+        let decoded = half::decode_lenient_f32(word);
+        report.checked += 1;
+        if !decoded.normalized {
+            report.unnormalized += 1;
+        }
+        let actual = f64::from(decoded.value);
+        if actual != expected {
+            report.mismatches.push(Mismatch::Decode {
+                word,
+                expected,
+                actual,
+            });
+        }
+
+        if expected != 0.0 && NEAREST_ZERO[0] < expected && expected < NEAREST_ZERO[1] {
+            continue;
+        }
+        report.reencoded += 1;
+        let reencoded = half::encode_f32_with(expected as f32, EncodeMode::TwosComplement);
+        if oracle(reencoded) != expected {
+            report.mismatches.push(Mismatch::Reencode {
+                word,
+                value: expected,
+                reencoded,
+            });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arith;
+
+    #[test]
+    fn test_reference16() {
+        assert_eq!(reference16(0x6344), 12.40625);
+        assert_eq!(reference16(0x8000), -1.0);
+        assert_eq!(reference16(0x4001), 1.0);
+        assert_eq!(reference16(0x4020), 0.5 * 2f64.powi(-32));
+        assert_eq!(reference16(0x7FDF), (1.0 - 2f64.powi(-9)) * 2f64.powi(31));
+        for word in 0..=u16::MAX {
+            assert_eq!(reference16(word), arith::value16(word), "{word:#06x}");
+        }
+    }
+
+    #[test]
+    fn test_verify_16bit_exhaustive() {
+        let report = verify_16bit_exhaustive(reference16);
+        assert!(report.passed(), "{report}");
+        // Unnormalized words of the smallest exponents hold values nearer zero than any
+        // normalized word
+        assert_eq!(report.checked() - report.reencoded(), 1013);
+        assert_eq!(
+            NEAREST_ZERO.map(|value| value.to_bits()),
+            [reference16(0xBFE0), reference16(0x4020)].map(f64::to_bits)
+        );
+        assert!(report.to_string().starts_with("PASS: 65536 words decoded"));
+
+        // An oracle one ULP off at a single word
+        let report = verify_16bit_exhaustive(|word| {
+            reference16(word) + if word == 0x4001 { 2f64.powi(-9) } else { 0.0 }
+        });
+        // 0x4001 decodes to 1.0 rather than the oracle's value, which re-encodes to another
+        // word, and every other word of 1.0 re-encodes to 0x4001
+        let words: Vec<_> = report.mismatches().iter().map(Mismatch::word).collect();
+        assert_eq!(
+            words,
+            [0x49, 0x88, 0x107, 0x206, 0x405, 0x804, 0x1003, 0x2002, 0x4001, 0x4001]
+        );
+        assert!(report.to_string().contains("0x4001 decodes to 1, expected"));
+    }
+}
@@ -0,0 +1,130 @@
+//! # WebAssembly bindings
+//! [`wasm_bindgen`](mod@wasm_bindgen) exports for running the converter in the browser, built with
+//! `wasm-pack build --features wasm`.
+//!
+//! Encoders validate their input and throw a JavaScript `Error` carrying the
+//! [`Mil1750Error`] message instead of producing a meaningless word. 48-bit words are passed
+//! as `BigInt`s (`BigUint64Array` for arrays).
+//!
+//! ```js
+//! import { encode32, decode32 } from "mil1750a";
+//!
+//! decode32(encode32(5.234));
+//! ```
+
+use crate::{Mil16, Mil1750Error, Mil32, Mil48, MilFormat, TargetFormat, F16, F32};
+use half::f16;
+use wasm_bindgen::prelude::*;
+
+/// Encode a number as a 16-bit MIL-1750A word, rounding it to half precision first
+#[wasm_bindgen(js_name = encode16)]
+pub fn encode16(value: f32) -> Result<u16, JsError> {
+    let half = f16::from_f32(value);
+    if value.is_finite() && half.is_infinite() {
+        return Err(Mil1750Error::OutOfRange {
+            value: value.into(),
+            target: TargetFormat::Float16,
+        }
+        .into());
+    }
+    Ok(Mil16::try_from_f16(half)?.to_bits())
+}
+
+/// Encode a number as a 32-bit MIL-1750A word
+#[wasm_bindgen(js_name = encode32)]
+pub fn encode32(value: f32) -> Result<u32, JsError> {
+    Ok(Mil32::try_from_f32(value)?.to_bits())
+}
+
+/// Encode a number as a 48-bit MIL-1750A word
+#[wasm_bindgen(js_name = encode48)]
+pub fn encode48(value: f64) -> Result<u64, JsError> {
+    Ok(Mil48::try_from_f64(value)?.to_bits())
+}
+
+/// Decode a 16-bit MIL-1750A word
+#[wasm_bindgen(js_name = decode16)]
+pub fn decode16(word: u16) -> f32 {
+    F16::decode(word).to_f32()
+}
+
+/// Decode a 32-bit MIL-1750A word
+#[wasm_bindgen(js_name = decode32)]
+pub fn decode32(word: u32) -> f32 {
+    F32::decode(word)
+}
+
+/// Decode a 48-bit MIL-1750A word, throwing if it is wider than 48 bits
+#[wasm_bindgen(js_name = decode48)]
+pub fn decode48(word: u64) -> Result<f64, JsError> {
+    Ok(Mil48::try_from_bits(word)?.to_f64())
+}
+
+/// Encode a `Float32Array` as 16-bit MIL-1750A words
+#[wasm_bindgen(js_name = encode16Array)]
+pub fn encode16_array(values: &[f32]) -> Result<Vec<u16>, JsError> {
+    values.iter().map(|&value| encode16(value)).collect()
+}
+
+/// Encode a `Float32Array` as 32-bit MIL-1750A words
+#[wasm_bindgen(js_name = encode32Array)]
+pub fn encode32_array(values: &[f32]) -> Result<Vec<u32>, JsError> {
+    values.iter().map(|&value| encode32(value)).collect()
+}
+
+/// Encode a `Float64Array` as 48-bit MIL-1750A words
+#[wasm_bindgen(js_name = encode48Array)]
+pub fn encode48_array(values: &[f64]) -> Result<Vec<u64>, JsError> {
+    values.iter().map(|&value| encode48(value)).collect()
+}
+
+/// Decode a `Uint16Array` of 16-bit MIL-1750A words
+#[wasm_bindgen(js_name = decode16Array)]
+pub fn decode16_array(words: &[u16]) -> Vec<f32> {
+    words.iter().map(|&word| decode16(word)).collect()
+}
+
+/// Decode a `Uint32Array` of 32-bit MIL-1750A words
+#[wasm_bindgen(js_name = decode32Array)]
+pub fn decode32_array(words: &[u32]) -> Vec<f32> {
+    words.iter().map(|&word| decode32(word)).collect()
+}
+
+/// Decode a `BigUint64Array` of 48-bit MIL-1750A words
+#[wasm_bindgen(js_name = decode48Array)]
+pub fn decode48_array(words: &[u64]) -> Result<Vec<f64>, JsError> {
+    words.iter().map(|&word| decode48(word)).collect()
+}
+
+// Errors cannot be constructed outside of a JavaScript host, so these only cover the
+// successful paths; the validation itself is tested on the wrapper types.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scalar() {
+        assert_eq!(encode16(12.4).ok(), Some(0x6344));
+        assert_eq!(encode32(5.234).ok(), Some(0x53BE7703));
+        assert_eq!(encode48(105.639485637361).ok(), Some(0x69A3B50754AB));
+        assert_eq!(decode16(0x6344), 12.40625);
+        assert_eq!(decode16(0x8000), -1.0);
+        assert_eq!(encode16(1.0).ok(), Some(0x4001));
+        assert_eq!(decode32(0x40000001), 1.0);
+        assert_eq!(decode48(0x69A3B50754AB).ok(), Some(105.63948563742451));
+    }
+
+    #[test]
+    fn test_arrays() {
+        let words = encode32_array(&[1.0, -1.0]).ok().unwrap();
+        assert_eq!(words, [0x40000001, 0x80000000]);
+        assert_eq!(decode32_array(&words), [1.0, -1.0]);
+        assert_eq!(
+            decode16_array(&encode16_array(&[12.40625]).ok().unwrap()),
+            [12.40625]
+        );
+        let words = encode48_array(&[0.0, std::f64::consts::PI]).ok().unwrap();
+        assert_eq!(words, [0, 0x6487ED025111]);
+        assert_eq!(decode48_array(&words).ok().unwrap()[0], 0.0);
+    }
+}
@@ -0,0 +1,208 @@
+//! # Binary file writer
+//! Lay out records of MIL-1750A words in a file, as loaders and bus simulators expect them.
+//!
+//! A [`RecordWriter`] writes each call as one record: the words of a slice of values, of a
+//! slice of raw words or of a [`Schema`] frame, in the writer's byte order and word order.
+//! After each record come [`padding`](RecordWriter::padding) fill bytes, then as many more as
+//! bring the next record to a multiple of [`align`](RecordWriter::align) bytes from the start.
+//! Values are encoded before anything is written, so a record that cannot be encoded leaves
+//! the file as it was.
+//!
+//! ```
+//! use mil1750a_converter::writer::RecordWriter;
+//! use mil1750a_converter::{ByteOrder, WordOrder, F32};
+//!
+//! let mut writer = RecordWriter::new(Vec::new())
+//!     .byte_order(ByteOrder::LittleEndian)
+//!     .word_order(WordOrder::LsWordFirst)
+//!     .align(8);
+//! writer.write_values::<F32>(&[1.0]).unwrap();
+//! writer.write_words::<F32>(&[0x53BE7703]).unwrap();
+//! assert_eq!(
+//!     writer.into_inner(),
+//!     [1, 0, 0, 0x40, 0, 0, 0, 0, 0x03, 0x77, 0xBE, 0x53, 0, 0, 0, 0]
+//! );
+//! ```
+
+use crate::schema::{Frame, Schema, SchemaError};
+use crate::{ByteOrder, Mil1750Error, MilFormat, WordOrder};
+use std::io::{self, Write};
+
+/// Reasons a record cannot be written
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum WriteError {
+    /// The underlying writer failed
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    /// A value cannot be encoded
+    #[error("value {index} of the record: {error}")]
+    Encode {
+        /// Index of the value in the record
+        index: usize,
+        /// Why it cannot be encoded
+        #[source]
+        error: Mil1750Error,
+    },
+    /// A frame cannot be encoded by its schema
+    #[error("{0}")]
+    Schema(#[from] SchemaError),
+}
+
+/// A writer of records of words, see the [module documentation](self)
+#[derive(Debug)]
+pub struct RecordWriter<W> {
+    inner: W,
+    byte_order: ByteOrder,
+    word_order: WordOrder,
+    padding: usize,
+    align: usize,
+    fill: u8,
+    position: u64,
+}
+
+impl<W: Write> RecordWriter<W> {
+    /// A writer of big-endian words, most significant word first, with no padding
+    pub fn new(inner: W) -> Self {
+        RecordWriter {
+            inner,
+            byte_order: ByteOrder::BigEndian,
+            word_order: WordOrder::MsWordFirst,
+            padding: 0,
+            align: 1,
+            fill: 0,
+            position: 0,
+        }
+    }
+
+    /// Write the two bytes of each 16-bit word in `order`
+    pub fn byte_order(mut self, order: ByteOrder) -> Self {
+        self.byte_order = order;
+        self
+    }
+
+    /// Write the 16-bit words of 32- and 48-bit words in `order`
+    pub fn word_order(mut self, order: WordOrder) -> Self {
+        self.word_order = order;
+        self
+    }
+
+    /// Write `bytes` fill bytes after each record
+    pub fn padding(mut self, bytes: usize) -> Self {
+        self.padding = bytes;
+        self
+    }
+
+    /// Start each record at a multiple of `bytes` from the start of the file
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is zero.
+    pub fn align(mut self, bytes: usize) -> Self {
+        assert!(bytes > 0, "records must be aligned to at least one byte");
+        self.align = bytes;
+        self
+    }
+
+    /// Pad with `byte` rather than zero
+    pub fn fill(mut self, byte: u8) -> Self {
+        self.fill = byte;
+        self
+    }
+
+    /// Encode `values` and write them as one record
+    pub fn write_values<F: MilFormat>(&mut self, values: &[F::Ieee]) -> Result<(), WriteError> {
+        let words = values
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| {
+                F::try_encode(value).map_err(|error| WriteError::Encode { index, error })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.write_words::<F>(&words)
+    }
+
+    /// Write raw `words` as one record
+    pub fn write_words<F: MilFormat>(&mut self, words: &[F::Bits]) -> Result<(), WriteError> {
+        let mut record = vec![0; words.len() * F::BYTES];
+        for (&word, out) in words.iter().zip(record.chunks_exact_mut(F::BYTES)) {
+            F::write_ordered(word, self.word_order, out);
+        }
+        self.write_record(record)
+    }
+
+    /// Encode `frame` with `schema` and write its 16-bit words as one record
+    pub fn write_frame(&mut self, schema: &Schema, frame: &Frame) -> Result<(), WriteError> {
+        let record = schema
+            .encode(frame)?
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect();
+        self.write_record(record)
+    }
+
+    /// Number of bytes written so far, padding included
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Flush the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// The underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Write a record of big-endian 16-bit words in the byte order, then its padding
+    fn write_record(&mut self, mut record: Vec<u8>) -> Result<(), WriteError> {
+        if self.byte_order == ByteOrder::LittleEndian {
+            record.chunks_exact_mut(2).for_each(|pair| pair.swap(0, 1));
+        }
+        let end = self.position + (record.len() + self.padding) as u64;
+        let aligned = end.next_multiple_of(self.align as u64);
+        record.resize((aligned - self.position) as usize, self.fill);
+        self.inner.write_all(&record)?;
+        self.position = aligned;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::FieldFormat;
+    use crate::F48;
+
+    #[test]
+    fn test_write_frames() {
+        let schema = Schema::new().field("altitude", 0, FieldFormat::F32).field(
+            "sample_count",
+            2,
+            FieldFormat::Int16,
+        );
+        let frame = schema.decode(&[0x53BE, 0x7703, 0xFFFE]).unwrap();
+        let mut writer = RecordWriter::new(Vec::new()).padding(1).align(4).fill(0xEE);
+        writer.write_frame(&schema, &frame).unwrap();
+        writer.write_words::<F48>(&[0x400000010000]).unwrap();
+        assert_eq!(writer.position(), 16);
+        assert_eq!(
+            writer.into_inner(),
+            [
+                0x53, 0xBE, 0x77, 0x03, 0xFF, 0xFE, 0xEE, 0xEE, //
+                0x40, 0x00, 0x00, 0x01, 0x00, 0x00, 0xEE, 0xEE,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unencodable_record() {
+        let mut writer = RecordWriter::new(Vec::new());
+        let error = writer.write_values::<F48>(&[1.0, f64::NAN]).unwrap_err();
+        assert!(matches!(error, WriteError::Encode { index: 1, .. }));
+        assert_eq!(writer.position(), 0);
+        assert!(writer.into_inner().is_empty());
+    }
+}